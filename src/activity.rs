@@ -0,0 +1,118 @@
+//! coding activity from git repositories, to find work which happened outside of the tracked
+//! intervals
+
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Commit {
+    pub time: DateTime<Utc>,
+    pub repository: String,
+    pub summary: String,
+}
+
+fn git(repository: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .output()
+        .map_err(|e| Error::InvalidState(format!("could not run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::InvalidState(format!(
+            "could not read the commits of {}: {}",
+            repository.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// parses the output of `git log --format=%aI%x09%s`
+fn parse_log(repository: &str, log: &str) -> Vec<Commit> {
+    log.lines()
+        .filter_map(|line| {
+            let (time, summary) = line.split_once('\t')?;
+            Some(Commit {
+                time: DateTime::parse_from_rfc3339(time).ok()?.with_timezone(&Utc),
+                repository: repository.to_string(),
+                summary: summary.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// the commits of the user configured in the repository, in the given time span
+pub fn commits(
+    repository: &Path,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Result<Vec<Commit>, Error> {
+    let mut args = vec![
+        "log".to_string(),
+        "--all".to_string(),
+        "--format=%aI%x09%s".to_string(),
+    ];
+    if let Ok(email) = git(repository, &["config", "user.email"]) {
+        if !email.trim().is_empty() {
+            args.push(format!("--author={}", email.trim()));
+        }
+    }
+    if let Some((from, to)) = range {
+        args.push(format!("--since={}", from.to_rfc3339()));
+        args.push(format!("--until={}", to.to_rfc3339()));
+    }
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let log = git(repository, &args)?;
+    let name = repository
+        .canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| repository.display().to_string());
+    Ok(parse_log(&name, &log))
+}
+
+/// the commits which were not made within one of the intervals
+pub fn untracked<'a>(
+    commits: &'a [Commit],
+    intervals: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Vec<&'a Commit> {
+    commits
+        .iter()
+        .filter(|commit| {
+            !intervals
+                .iter()
+                .any(|(start, stop)| (*start..=*stop).contains(&commit.time))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_untracked() {
+        let commits = parse_log(
+            "timetracking",
+            "2021-04-01T10:15:00+02:00\tfix the parser\n\
+             2021-04-01T20:00:00+02:00\tadd a test\n\
+             invalid line\n",
+        );
+        assert_eq!(2, commits.len());
+        assert_eq!(Utc.ymd(2021, 4, 1).and_hms(8, 15, 0), commits[0].time);
+
+        let intervals = [(
+            Utc.ymd(2021, 4, 1).and_hms(7, 0, 0),
+            Utc.ymd(2021, 4, 1).and_hms(12, 0, 0),
+        )];
+        assert_eq!(vec![&commits[1]], untracked(&commits, &intervals));
+    }
+}
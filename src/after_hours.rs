@@ -0,0 +1,74 @@
+//! flags intervals on days off or outside of the working hours of the config, since that time is
+//! often compensated differently
+
+use chrono::{DateTime, Datelike, Duration, Local, Weekday};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Flags {
+    /// on a day which isn't a work day, usually the weekend
+    pub weekend: bool,
+    /// on a work day, but before the start or after the end of the working hours
+    pub after_hours: bool,
+}
+
+pub struct WorkingHours {
+    pub days: Vec<Weekday>,
+    /// the start and end as offsets from the start of the day
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl WorkingHours {
+    pub fn flags(&self, start: DateTime<Local>, end: DateTime<Local>) -> Flags {
+        let (start, end) = (start.naive_local(), end.naive_local());
+        let mut flags = Flags::default();
+        let mut date = start.date();
+        loop {
+            let day_start = date.and_hms(0, 0, 0);
+            if !self.days.contains(&date.weekday()) {
+                flags.weekend = true;
+            } else if start < day_start + self.start || end > day_start + self.end {
+                flags.after_hours = true;
+            }
+            date = date.succ();
+            if date.and_hms(0, 0, 0) >= end {
+                return flags;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_flags() {
+        let hours = WorkingHours {
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            start: Duration::hours(9),
+            end: Duration::hours(17),
+        };
+        // 2021-04-01 is a thursday
+        let at = |day, hour| Local.ymd(2021, 4, day).and_hms(hour, 0, 0);
+        let flags = |weekend, after_hours| Flags {
+            weekend,
+            after_hours,
+        };
+        assert_eq!(flags(false, false), hours.flags(at(1, 9), at(1, 17)));
+        assert_eq!(flags(false, true), hours.flags(at(1, 16), at(1, 18)));
+        assert_eq!(flags(false, true), hours.flags(at(1, 8), at(1, 10)));
+        assert_eq!(flags(true, false), hours.flags(at(3, 10), at(3, 12)));
+        // from friday night until saturday
+        assert_eq!(flags(true, true), hours.flags(at(2, 22), at(3, 2)));
+        // ending at midnight doesn't touch the next day
+        assert_eq!(flags(false, true), hours.flags(at(1, 22), at(2, 0)));
+    }
+}
@@ -0,0 +1,80 @@
+//! short forms for commands, defined in the `[aliases]` table of the config, e.g.
+//! `w = "show week"`
+
+use std::collections::HashMap;
+
+/// the global options which are followed by a value, so the value isn't taken as the command
+const OPTIONS_WITH_VALUE: &[&str] = &["-d", "--data-file", "--output", "--now"];
+
+/// splits the alias into arguments at whitespace. Double quotes keep the text between them
+/// together, e.g. `stop "lunch break"`.
+fn split(alias: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    for c in alias.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => args.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+/// the index of the command, after the program and the global options
+pub fn command_index(args: &[String]) -> usize {
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        if arg == "--" || !arg.starts_with('-') {
+            break;
+        }
+        index += iif::iif!(OPTIONS_WITH_VALUE.contains(&arg.as_str()), 2, 1);
+    }
+    index
+}
+
+/// replaces the command with its alias. Aliases are expanded once, so an alias can't refer to
+/// another alias, but it can have the name of the command it expands to.
+pub fn expand(args: Vec<String>, aliases: &HashMap<String, String>) -> Vec<String> {
+    let index = command_index(&args);
+    match args.get(index).and_then(|command| aliases.get(command)) {
+        Some(alias) => {
+            let mut expanded = args[..index].to_vec();
+            expanded.extend(split(alias));
+            expanded.extend_from_slice(&args[index + 1..]);
+            expanded
+        }
+        None => args,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let args = |args: &str| args.split(' ').map(str::to_string).collect::<Vec<_>>();
+        let aliases = vec![
+            ("w".to_string(), "show week".to_string()),
+            ("lunch".to_string(), "stop \"lunch break\"".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            args("tt --output json show week -p"),
+            expand(args("tt --output json w -p"), &aliases)
+        );
+        assert_eq!(
+            vec!["tt", "-d", "w", "stop", "lunch break"],
+            expand(args("tt -d w lunch"), &aliases)
+        );
+        assert_eq!(args("tt start w"), expand(args("tt start w"), &aliases));
+        assert_eq!(args("tt"), expand(args("tt"), &aliases));
+    }
+}
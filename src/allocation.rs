@@ -0,0 +1,95 @@
+//! the split of an interval across projects with `tt allocate`, e.g. for retainer work which is
+//! billed to two clients at the same time. Reports by project attribute each share of the
+//! interval to its project instead of the project of the interval.
+
+use crate::error::Error;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Share {
+    pub project: String,
+    pub percent: u8,
+}
+
+/// parses shares like "client-a=60", which add up to 100 percent
+pub fn parse(shares: &[String]) -> Result<Vec<Share>, Error> {
+    let mut parsed: Vec<Share> = Vec::new();
+    for share in shares {
+        let invalid = || {
+            Error::Parse(format!(
+                "invalid share \"{}\", expected project=percent",
+                share
+            ))
+        };
+        let (project, percent) = share.split_once('=').ok_or_else(invalid)?;
+        let percent = percent.trim().parse::<u8>().map_err(|_| invalid())?;
+        let project = project.trim().to_string();
+        if project.is_empty() || percent == 0 {
+            return Err(invalid());
+        }
+        if parsed.iter().any(|share| share.project == project) {
+            return Err(Error::Parse(format!(
+                "the project \"{}\" has two shares",
+                project
+            )));
+        }
+        parsed.push(Share { project, percent });
+    }
+    let total = parsed
+        .iter()
+        .map(|share| u32::from(share.percent))
+        .sum::<u32>();
+    if !parsed.is_empty() && total != 100 {
+        return Err(Error::Parse(format!(
+            "the shares add up to {} instead of 100 percent",
+            total
+        )));
+    }
+    Ok(parsed)
+}
+
+/// the time of each share. The seconds which are lost by rounding go to the last share, so the
+/// shares add up to the time.
+pub fn split(time: Duration, shares: &[Share]) -> Vec<(&str, Duration)> {
+    let seconds = time.num_seconds();
+    let mut rest = seconds;
+    shares
+        .iter()
+        .enumerate()
+        .map(|(index, share)| {
+            let part = match index + 1 == shares.len() {
+                true => rest,
+                false => seconds * i64::from(share.percent) / 100,
+            };
+            rest -= part;
+            (share.project.as_str(), Duration::seconds(part))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let shares = parse(&["client-a=60".to_string(), "client-b=40".to_string()]).unwrap();
+        // the rounding remainder goes to the last share
+        assert_eq!(
+            vec![
+                ("client-a", Duration::seconds(60)),
+                ("client-b", Duration::seconds(41))
+            ],
+            split(Duration::seconds(101), &shares)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(parse(&["client-a=60".to_string(), "client-b=30".to_string()]).is_err());
+        assert!(parse(&["client-a=50".to_string(), "client-a=50".to_string()]).is_err());
+        assert!(parse(&["client-a".to_string()]).is_err());
+        assert!(parse(&[]).unwrap().is_empty());
+    }
+}
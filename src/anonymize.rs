@@ -0,0 +1,150 @@
+//! replaces the texts of the events with numbered placeholders, so the data can be attached to
+//! bug reports. The times stay the same, and equal texts get the same placeholder, so the
+//! structure of the data is kept.
+
+use crate::{TrackingData, TrackingEvent};
+
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct Placeholders {
+    names: HashMap<(&'static str, String), String>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Placeholders {
+    fn replace(&mut self, kind: &'static str, text: &str) -> String {
+        let counts = &mut self.counts;
+        self.names
+            .entry((kind, text.to_string()))
+            .or_insert_with(|| {
+                let count = counts.entry(kind).or_insert(0);
+                *count += 1;
+                format!("{} {}", kind, count)
+            })
+            .clone()
+    }
+
+    /// keeps the hierarchy of projects like "client/website"
+    fn replace_project(&mut self, project: &str) -> String {
+        project
+            .split('/')
+            .map(|part| self.replace("project", part))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+pub fn anonymize(data: &[TrackingEvent]) -> Vec<TrackingEvent> {
+    let mut placeholders = Placeholders::default();
+    let mut data = data.to_vec();
+    for event in &mut data {
+        let (TrackingEvent::Start(TrackingData {
+            description, meta, ..
+        })
+        | TrackingEvent::Stop(TrackingData {
+            description, meta, ..
+        })) = event;
+        *description = description
+            .as_ref()
+            .map(|text| placeholders.replace("description", text));
+        meta.project = meta
+            .project
+            .as_ref()
+            .map(|project| placeholders.replace_project(project));
+        for share in &mut meta.allocation {
+            share.project = placeholders.replace_project(&share.project);
+        }
+        meta.context = meta
+            .context
+            .as_ref()
+            .map(|context| placeholders.replace("context", context));
+        meta.task = meta
+            .task
+            .as_ref()
+            .map(|task| placeholders.replace("task", task));
+        for tag in &mut meta.tags {
+            *tag = placeholders.replace("tag", tag);
+        }
+        for interruption in &mut meta.interruptions {
+            interruption.note = interruption
+                .note
+                .as_ref()
+                .map(|note| placeholders.replace("note", note));
+        }
+        meta.window_title = meta
+            .window_title
+            .as_ref()
+            .map(|title| placeholders.replace("window", title));
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocation::Share;
+    use crate::test_data::{at, start, with_meta};
+    use crate::Metadata;
+
+    fn event(hour: u32, description: &str, project: &str, tag: &str) -> TrackingEvent {
+        let meta = Metadata {
+            project: Some(project.to_string()),
+            tags: vec![tag.to_string()],
+            ..Metadata::default()
+        };
+        with_meta(start(at(hour, 0), description), meta)
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let with_task = |event, task: &str| match event {
+            TrackingEvent::Start(mut data) => {
+                data.meta.task = Some(task.to_string());
+                TrackingEvent::Start(data)
+            }
+            event => event,
+        };
+        let with_allocation = |event, projects: [&str; 2]| match event {
+            TrackingEvent::Start(mut data) => {
+                data.meta.allocation = projects
+                    .iter()
+                    .map(|project| Share {
+                        project: project.to_string(),
+                        percent: 50,
+                    })
+                    .collect();
+                TrackingEvent::Start(data)
+            }
+            event => event,
+        };
+        let data = [
+            with_task(
+                event(8, "acme meeting", "acme/website", "billable"),
+                "SECRET-123",
+            ),
+            event(9, "code review", "acme", "billable"),
+            with_allocation(
+                event(10, "acme meeting", "initech/website", "billable"),
+                ["acme", "acme-corp"],
+            ),
+        ];
+        let expected = [
+            with_task(
+                event(8, "description 1", "project 1/project 2", "tag 1"),
+                "task 1",
+            ),
+            event(9, "description 2", "project 1", "tag 1"),
+            with_allocation(
+                event(10, "description 1", "project 3/project 2", "tag 1"),
+                ["project 1", "project 4"],
+            ),
+        ];
+        assert_eq!(expected.to_vec(), anonymize(&data));
+    }
+
+    #[test]
+    fn test_anonymize_empty() {
+        assert!(anonymize(&[]).is_empty());
+    }
+}
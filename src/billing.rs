@@ -0,0 +1,174 @@
+//! rounds the billed time up to the increments which are configured per project, like "30m per
+//! day" or "6m per interval"
+
+use crate::duration_parse;
+use crate::error::Error;
+use crate::project;
+use crate::settings::ProjectSettings;
+use crate::TrackingEvent;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Per {
+    Interval,
+    Day,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rounding {
+    pub increment: Duration,
+    pub per: Per,
+}
+
+impl Rounding {
+    pub fn parse(rounding: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::Parse(format!(
+                "invalid rounding \"{}\", expected an increment like \"30m per day\" or \"6m per \
+                 interval\"",
+                rounding
+            ))
+        };
+        let (increment, per) = rounding.split_once(" per ").ok_or_else(invalid)?;
+        let increment = duration_parse::parse(increment.trim()).map_err(|_| invalid())?;
+        let per = match per.trim() {
+            "interval" => Per::Interval,
+            "day" => Per::Day,
+            _ => return Err(invalid()),
+        };
+        if increment <= Duration::zero() {
+            return Err(invalid());
+        }
+        Ok(Self { increment, per })
+    }
+
+    fn round_up(&self, duration: Duration) -> Duration {
+        let increment = self.increment.num_seconds();
+        let increments = (duration.num_seconds() + increment - 1) / increment;
+        Duration::seconds(increments * increment)
+    }
+}
+
+/// the rounding of the project, or of the closest parent which has one
+pub fn rounding(
+    projects: &HashMap<String, ProjectSettings>,
+    project: &str,
+) -> Result<Option<Rounding>, Error> {
+    // the config keys are lowercase
+    project::ancestors(project)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .find_map(|ancestor| projects.get(&ancestor.to_lowercase())?.rounding.as_deref())
+        .map(Rounding::parse)
+        .transpose()
+}
+
+/// the billed time of each interval, or none if its project isn't rounded. With rounding per
+/// day, the time which is added to reach the increment goes to the last interval of the day.
+/// Running intervals are billed until `now`.
+pub fn billed(
+    projects: &HashMap<String, ProjectSettings>,
+    data: &[TrackingEvent],
+    now: DateTime<Utc>,
+) -> Result<Vec<Option<Duration>>, Error> {
+    let mut billed = Vec::new();
+    let mut days: HashMap<(String, NaiveDate), (Rounding, Duration, usize)> = HashMap::new();
+    let mut events = data.iter();
+    while let Some(start) = events.find(|e| e.is_start()) {
+        let end = events
+            .find(|e| e.is_stop())
+            .map_or(now, |stop| stop.time(true));
+        let duration = end - start.time(true);
+        let project = match start.project() {
+            Some(project) => project,
+            None => {
+                billed.push(None);
+                continue;
+            }
+        };
+        match rounding(projects, &project)? {
+            Some(rounding) if rounding.per == Per::Interval => {
+                billed.push(Some(rounding.round_up(duration)));
+            }
+            Some(rounding) => {
+                let day = start.time(true).with_timezone(&Local).date().naive_local();
+                let (_, total, last) = days.entry((project, day)).or_insert((
+                    rounding,
+                    Duration::zero(),
+                    billed.len(),
+                ));
+                *total = *total + duration;
+                *last = billed.len();
+                billed.push(Some(duration));
+            }
+            None => billed.push(None),
+        }
+    }
+    for (rounding, total, last) in days.into_values() {
+        if let Some(duration) = &mut billed[last] {
+            *duration = *duration + rounding.round_up(total) - total;
+        }
+    }
+    Ok(billed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{event, local, with_meta};
+    use crate::Metadata;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Rounding {
+                increment: Duration::minutes(6),
+                per: Per::Interval
+            },
+            Rounding::parse("6m per interval").unwrap()
+        );
+        assert!(Rounding::parse("6m").is_err());
+        assert!(Rounding::parse("0m per day").is_err());
+        assert!(Rounding::parse("6m per week").is_err());
+    }
+
+    #[test]
+    fn test_billed() {
+        let projects = vec![("a", "30m per day"), ("b", "6m per interval")]
+            .into_iter()
+            .map(|(project, rounding)| {
+                let settings = ProjectSettings {
+                    rounding: Some(rounding.to_string()),
+                    ..ProjectSettings::default()
+                };
+                (project.to_string(), settings)
+            })
+            .collect();
+        let at = |hour, minute| local(1, hour, minute);
+        let interval = |project: &str, from, to| {
+            let meta = Metadata {
+                project: Some(project.to_string()),
+                ..Metadata::default()
+            };
+            vec![
+                with_meta(event(true, from), meta.clone()),
+                with_meta(event(false, to), meta),
+            ]
+        };
+        let data = [
+            interval("a/web", at(8, 0), at(8, 10)),
+            interval("b", at(9, 0), at(9, 7)),
+            interval("a/web", at(10, 0), at(10, 5)),
+            interval("c", at(11, 0), at(11, 5)),
+        ]
+        .concat();
+        let minutes = |minutes| Some(Duration::minutes(minutes));
+        assert_eq!(
+            vec![minutes(10), minutes(12), minutes(20), None],
+            billed(&projects, &data, at(18, 0)).unwrap()
+        );
+    }
+}
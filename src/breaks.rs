@@ -0,0 +1,151 @@
+//! suggests breaks based on a policy like "50/10", which means a break of 10 minutes after
+//! 50 minutes of work, and deducts the lunch break which some timesheets require
+
+use crate::duration_parse;
+use crate::error::Error;
+use chrono::{DateTime, Duration, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Policy {
+    pub work: Duration,
+    pub pause: Duration,
+}
+
+impl Policy {
+    pub fn parse(policy: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::Parse(format!(
+                "invalid break policy \"{}\", expected minutes of work and break like \"50/10\"",
+                policy
+            ))
+        };
+        let (work, pause) = policy.split_once('/').ok_or_else(invalid)?;
+        let minutes = |text: &str| match text.trim().parse::<u32>() {
+            Ok(minutes) if minutes > 0 => Ok(Duration::minutes(i64::from(minutes))),
+            _ => Err(invalid()),
+        };
+        Ok(Self {
+            work: minutes(work)?,
+            pause: minutes(pause)?,
+        })
+    }
+}
+
+/// when the next break should start, if an interval is running. Intervals which are separated
+/// by less than the break length count as continuous work.
+pub fn next_break(
+    intervals: &[(DateTime<Utc>, DateTime<Utc>)],
+    running: bool,
+    policy: Policy,
+) -> Option<DateTime<Utc>> {
+    if !running {
+        return None;
+    }
+    let (mut start, _) = *intervals.last()?;
+    for (previous, next) in intervals.iter().rev().zip(intervals.iter().rev().skip(1)) {
+        if previous.0 - next.1 >= policy.pause {
+            break;
+        }
+        start = next.0;
+    }
+    Some(start + policy.work)
+}
+
+/// a rule like "6h/30m": a day with more than 6h of tracked time and no break of at least 30m
+/// gets 30m deducted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LunchRule {
+    pub after: Duration,
+    pub pause: Duration,
+}
+
+impl LunchRule {
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::Parse(format!(
+                "invalid lunch deduction \"{}\", expected the work time and the break like \
+                 \"6h/30m\"",
+                rule
+            ))
+        };
+        let (after, pause) = rule.split_once('/').ok_or_else(invalid)?;
+        let duration = |text: &str| match duration_parse::parse(text.trim()) {
+            Ok(duration) if duration > Duration::zero() => Ok(duration),
+            _ => Err(invalid()),
+        };
+        Ok(Self {
+            after: duration(after)?,
+            pause: duration(pause)?,
+        })
+    }
+
+    /// the time to deduct from the sorted intervals of a day
+    pub fn deduction(&self, intervals: &[(DateTime<Utc>, DateTime<Utc>)]) -> Option<Duration> {
+        let tracked = intervals
+            .iter()
+            .fold(Duration::zero(), |total, (start, end)| {
+                total + (*end - *start)
+            });
+        let took_break = intervals
+            .windows(2)
+            .any(|pair| pair[1].0 - pair[0].1 >= self.pause);
+        (tracked > self.after && !took_break).then_some(self.pause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::at;
+
+    #[test]
+    fn test_parse_policy() {
+        assert!(Policy::parse("50/10").is_ok());
+        assert!(Policy::parse("50").is_err());
+        assert!(Policy::parse("0/10").is_err());
+    }
+
+    #[test]
+    fn test_next_break() {
+        let policy = Policy::parse("50/10").unwrap();
+        // a break of 5 minutes is too short, one of 15 minutes counts
+        let intervals = [
+            (at(8, 0), at(9, 0)),
+            (at(9, 15), at(9, 40)),
+            (at(9, 45), at(10, 0)),
+        ];
+        assert_eq!(Some(at(10, 5)), next_break(&intervals, true, policy));
+    }
+
+    #[test]
+    fn test_next_break_stopped_or_empty() {
+        let policy = Policy::parse("50/10").unwrap();
+        assert_eq!(None, next_break(&[(at(8, 0), at(9, 0))], false, policy));
+        assert_eq!(None, next_break(&[], true, policy));
+    }
+
+    #[test]
+    fn test_parse_lunch_rule() {
+        assert!(LunchRule::parse("6h/30m").is_ok());
+        assert!(LunchRule::parse("6h").is_err());
+        assert!(LunchRule::parse("6h/0m").is_err());
+    }
+
+    #[test]
+    fn test_lunch_deduction() {
+        let rule = LunchRule::parse("6h/30m").unwrap();
+        let deduction = |intervals: &[_]| rule.deduction(intervals);
+        // 7h with a break of 15 minutes
+        assert_eq!(
+            Some(Duration::minutes(30)),
+            deduction(&[(at(8, 0), at(12, 0)), (at(12, 15), at(15, 15))])
+        );
+        assert_eq!(
+            None,
+            deduction(&[(at(8, 0), at(12, 0)), (at(12, 30), at(15, 30))])
+        );
+        // exactly 6h don't need a break
+        assert_eq!(None, deduction(&[(at(8, 0), at(14, 0))]));
+        assert_eq!(None, deduction(&[]));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::duration_parse;
+use crate::error::Error;
+use chrono::Duration;
+
+/// a time like "17:30", "0:30:15" or "8" (hours), or a duration with units like "1h30m"
+fn parse_term(term: &str) -> Result<Duration, Error> {
+    if term.chars().any(char::is_alphabetic) {
+        return duration_parse::parse(term);
+    }
+    let invalid = || Error::Parse(format!("invalid time \"{}\"", term));
+    let mut parts = term.split(':');
+    let mut next = |max: i64| -> Result<Option<i64>, Error> {
+        match parts.next() {
+            None => Ok(None),
+            Some(part) => match part.parse::<i64>() {
+                Ok(value) if (0..max).contains(&value) && !part.starts_with('+') => Ok(Some(value)),
+                _ => Err(invalid()),
+            },
+        }
+    };
+    let hours = next(i64::MAX)?.ok_or_else(invalid)?;
+    let minutes = next(60)?.unwrap_or(0);
+    let seconds = next(60)?.unwrap_or(0);
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(Duration::hours(hours) + Duration::minutes(minutes) + Duration::seconds(seconds))
+}
+
+/// evaluates a sum of times, e.g. "17:30 - 08:45 - 0:30"
+pub fn evaluate(expression: &str) -> Result<Duration, Error> {
+    let mut tokens = Vec::new();
+    let mut term = String::new();
+    for c in expression.chars() {
+        if c == '+' || c == '-' || c.is_whitespace() {
+            if !term.is_empty() {
+                tokens.push(std::mem::take(&mut term));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            term.push(c);
+        }
+    }
+    if !term.is_empty() {
+        tokens.push(term);
+    }
+
+    let invalid = || Error::Parse(format!("invalid expression \"{}\"", expression));
+    let mut tokens = tokens.iter();
+    let mut result = parse_term(tokens.next().ok_or_else(invalid)?)?;
+    while let Some(operator) = tokens.next() {
+        let duration = parse_term(tokens.next().ok_or_else(invalid)?)?;
+        result = match operator.as_str() {
+            "+" => result + duration,
+            "-" => result - duration,
+            _ => return Err(invalid()),
+        };
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(
+            Duration::minutes(8 * 60 + 15),
+            evaluate("17:30 - 08:45 - 0:30").unwrap()
+        );
+        assert_eq!(
+            Duration::seconds(-(60 * 60 + 30)),
+            evaluate("1:00-2:00:30").unwrap()
+        );
+        assert_eq!(Duration::hours(10), evaluate("8 + 2").unwrap());
+        assert_eq!(Duration::minutes(75), evaluate("1.5h - 15m").unwrap());
+        assert!(evaluate("17:30 08:00").is_err());
+        assert!(evaluate("17:70").is_err());
+        assert!(evaluate("17:30 -").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! an append-only feed of the changes to the data, saved next to the data file. Each change gets
+//! the next sequence number, so sync tools can ask for the changes after the last one they saw.
+
+use crate::error::Error;
+use crate::journal::Operation;
+use serde::{Deserialize, Serialize};
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub sequence: u64,
+    #[serde(flatten)]
+    pub operation: Operation,
+}
+
+pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    let mut path = data_file.as_ref().as_os_str().to_owned();
+    path.push(".changes");
+    path.into()
+}
+
+fn invalid(path: &Path) -> Error {
+    Error::InvalidState(format!("could not decode the changes {}", path.display()))
+}
+
+/// the sequence number of the last change, which is read from the end of the file, or 0 without
+/// changes
+fn last_sequence(path: &Path) -> Result<u64, Error> {
+    let mut file = match File::open(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        file => file?,
+    };
+    let length = file.metadata()?.len();
+    let mut chunk = 4096;
+    loop {
+        let start = length.saturating_sub(chunk);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail)?;
+        let tail = String::from_utf8_lossy(&tail);
+        let mut lines = tail.trim_end().rsplit('\n');
+        let last = lines.next().unwrap_or("");
+        // the first line of the chunk may be cut off
+        if lines.next().is_some() || start == 0 {
+            if last.is_empty() {
+                return Ok(0);
+            }
+            let change: Change = serde_json::from_str(last).map_err(|_| invalid(path))?;
+            return Ok(change.sequence);
+        }
+        chunk *= 2;
+    }
+}
+
+/// appends the operation with the next sequence number and returns it. Operations without
+/// changes are skipped.
+pub fn append(path: &Path, operation: Operation) -> Result<Option<u64>, Error> {
+    if operation.added.is_empty() && operation.removed.is_empty() {
+        return Ok(None);
+    }
+    let change = Change {
+        sequence: last_sequence(path)? + 1,
+        operation,
+    };
+    let mut line = serde_json::to_string(&change).expect("could not serialize change");
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(Some(change.sequence))
+}
+
+/// the changes after the cursor, the oldest first
+pub fn since(path: &Path, cursor: u64) -> Result<Vec<Change>, Error> {
+    let file = match File::open(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        file => file?,
+    };
+    let mut changes = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let change: Change = serde_json::from_str(&line).map_err(|_| invalid(path))?;
+        if change.sequence > cursor {
+            changes.push(change);
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, start};
+
+    #[test]
+    fn test_append() {
+        let path = std::env::temp_dir().join("timetracking_test.changes");
+        let _ = std::fs::remove_file(&path);
+        let operation = |hour, description: &str| Operation {
+            command: "tt start".to_string(),
+            time: at(hour, 0),
+            added: vec![start(at(hour, 0), &description.repeat(1000))],
+            removed: Vec::new(),
+        };
+        assert_eq!(0, last_sequence(&path).unwrap());
+        // the changes are longer than the chunks the last one is read with
+        for hour in 1..=3 {
+            assert_eq!(
+                Some(u64::from(hour)),
+                append(&path, operation(hour, "work ")).unwrap()
+            );
+        }
+        let empty = Operation {
+            added: Vec::new(),
+            ..operation(4, "")
+        };
+        assert_eq!(None, append(&path, empty).unwrap());
+
+        let changes = since(&path, 1).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            vec![2, 3],
+            changes
+                .iter()
+                .map(|change| change.sequence)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(operation(3, "work "), changes[1].operation);
+    }
+}
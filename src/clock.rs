@@ -0,0 +1,26 @@
+//! the current time. Everything which needs the current time asks this module, so it can be
+//! frozen with the hidden `--now` option and in tests.
+
+use chrono::{Date, DateTime, Local, Utc};
+use std::cell::Cell;
+
+thread_local! {
+    static FROZEN: Cell<Option<DateTime<Utc>>> = const { Cell::new(None) };
+}
+
+/// makes `now` return the given time instead of the system time. `None` unfreezes the clock.
+pub fn freeze(time: Option<DateTime<Utc>>) {
+    FROZEN.with(|frozen| frozen.set(time));
+}
+
+pub fn now() -> DateTime<Utc> {
+    FROZEN.with(Cell::get).unwrap_or_else(Utc::now)
+}
+
+pub fn local_now() -> DateTime<Local> {
+    now().with_timezone(&Local)
+}
+
+pub fn today() -> Date<Local> {
+    local_now().date()
+}
@@ -0,0 +1,79 @@
+use crate::settings::ContextSettings;
+use iif::iif;
+use std::collections::HashMap;
+use std::process::Command;
+
+pub fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(text).filter(|text| !text.is_empty())
+}
+
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|hostname| !hostname.is_empty())
+        .or_else(|| command_output("hostname", &[]))
+}
+
+#[cfg(target_os = "linux")]
+fn wifi_ssid() -> Option<String> {
+    command_output("iwgetid", &["-r"])
+}
+
+#[cfg(target_os = "macos")]
+fn wifi_ssid() -> Option<String> {
+    command_output("networksetup", &["-getairportnetwork", "en0"])
+        .and_then(|text| text.split(": ").nth(1).map(str::to_string))
+}
+
+#[cfg(windows)]
+fn wifi_ssid() -> Option<String> {
+    command_output("netsh", &["wlan", "show", "interfaces"]).and_then(|text| {
+        text.lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("SSID"))
+            .and_then(|line| line.splitn(2, ':').nth(1))
+            .map(|ssid| ssid.trim().to_string())
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn wifi_ssid() -> Option<String> {
+    None
+}
+
+/// config keys are case insensitive, so the lookup has to be as well
+fn lookup(map: &HashMap<String, String>, key: Option<String>) -> Option<String> {
+    let key = key?;
+    map.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(&key))
+        .map(|(_, context)| context.clone())
+}
+
+/// detects the current context from the configured wifi ssids and hostnames, falling back to
+/// the configured default context.
+pub fn detect_context(settings: &ContextSettings) -> Option<String> {
+    // only query the system if there is something to match against
+    iif!(
+        settings.ssids.is_empty(),
+        None,
+        lookup(&settings.ssids, wifi_ssid())
+    )
+    .or_else(|| {
+        iif!(
+            settings.hostnames.is_empty(),
+            None,
+            lookup(&settings.hostnames, hostname())
+        )
+    })
+    .or_else(|| settings.default.clone())
+}
+
+/// an explicitly given context always takes precedence over the detected one
+pub fn resolve_context(settings: &ContextSettings, context: Option<String>) -> Option<String> {
+    context.or_else(|| detect_context(settings))
+}
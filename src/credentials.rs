@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use keyring::{Entry, Error};
+
+use std::io::{self, IsTerminal};
+
+/// all credentials are stored under this service name, with the integration as user
+const KEYRING_SERVICE: &str = "timetracking";
+
+fn entry(service: &str) -> Result<Entry> {
+    Entry::new(KEYRING_SERVICE, service).context("could not access the keyring")
+}
+
+pub fn set(service: &str, secret: &str) -> Result<()> {
+    entry(service)?
+        .set_password(secret)
+        .context("could not store credential in the keyring")
+}
+
+pub fn get(service: &str) -> Result<Option<String>> {
+    match entry(service)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("could not read credential from the keyring"),
+    }
+}
+
+pub fn remove(service: &str) -> Result<()> {
+    match entry(service)?.delete_credential() {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context("could not remove credential from the keyring"),
+    }
+}
+
+/// turns off the echo of the terminal until it is dropped, so a secret which is typed in isn't
+/// shown and doesn't stay in the scrollback
+#[cfg(unix)]
+struct HiddenInput;
+
+#[cfg(unix)]
+impl HiddenInput {
+    fn stty(setting: &str) -> io::Result<()> {
+        use std::fs::File;
+        use std::process::{Command, Stdio};
+
+        let status = Command::new("stty")
+            .arg(setting)
+            .stdin(File::open("/dev/tty")?)
+            .stderr(Stdio::null())
+            .status()?;
+        iif::iif!(
+            status.success(),
+            Ok(()),
+            Err(io::Error::other("stty failed"))
+        )
+    }
+
+    fn new() -> io::Result<Self> {
+        Self::stty("-echo")?;
+        Ok(Self)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for HiddenInput {
+    fn drop(&mut self) {
+        let _ = Self::stty("echo");
+        // the newline of the input wasn't echoed
+        eprintln!();
+    }
+}
+
+/// turns off the echo for a secret typed in on a terminal. Where that isn't possible, the secret
+/// is read with the echo and a warning, instead of not being read at all.
+#[cfg(unix)]
+fn hide_input() -> Option<HiddenInput> {
+    match HiddenInput::new() {
+        Ok(hidden) => Some(hidden),
+        Err(e) => {
+            eprintln!(
+                "Could not turn off the echo of the terminal ({}), the credential will be shown \
+                 while it is typed!",
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn hide_input() -> Option<()> {
+    eprintln!("The credential will be shown while it is typed!");
+    None
+}
+
+/// reads a secret from stdin, without echoing it on a terminal where possible
+pub fn read_secret() -> Result<String> {
+    let _hidden = io::stdin().is_terminal().then(hide_input).flatten();
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Could not read from stdin!")?;
+    Ok(input.trim().to_string())
+}
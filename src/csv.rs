@@ -0,0 +1,65 @@
+use crate::error::Error;
+
+/// splits csv content into rows of fields. Fields can be quoted with `"`, quoted fields can
+/// contain the delimiter, line breaks and `""` for a quote.
+pub fn parse(content: &str, delimiter: char) -> Result<Vec<Vec<String>>, Error> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            c if quoted => field.push(c),
+            c if c == delimiter => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(Error::Parse(
+            "the csv file ends inside of a quoted field".to_string(),
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.len() > 1 || row.first().is_some_and(|field| !field.is_empty()));
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content =
+            "Task,Start\r\n\"Review, \"\"urgent\"\"\",08:00\n\nmulti\n\"line\nfield\",09:00";
+        assert_eq!(
+            vec![
+                vec!["Task", "Start"],
+                vec!["Review, \"urgent\"", "08:00"],
+                vec!["multi"],
+                vec!["line\nfield", "09:00"],
+            ],
+            parse(content, ',').unwrap()
+        );
+        assert_eq!(vec![vec!["a", "", "b"]], parse("a;;b\n", ';').unwrap());
+        assert!(parse("\"open", ',').is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! the tracked time per day, stored next to the data file, so `tt show` over long time spans
+//! doesn't have to read every event. It is updated whenever the data changes, and it is only
+//! used as long as the data files and the local time zone are the ones it was written for.
+
+use crate::error::Error;
+use crate::TrackingEvent;
+use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Day {
+    /// in seconds
+    pub tracked: i64,
+    /// in seconds, when the seconds of the events are ignored
+    pub tracked_minutes: i64,
+    /// the start of the first and the end of the last interval
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+/// the length and modification time of a data file
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct FileState {
+    path: PathBuf,
+    length: u64,
+    modified: SystemTime,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DayIndex {
+    zone: Option<String>,
+    files: Vec<FileState>,
+    /// the days with intervals. Days whose last interval doesn't end on the same day are none.
+    days: BTreeMap<NaiveDate, Option<Day>>,
+}
+
+pub fn date(event: &TrackingEvent) -> NaiveDate {
+    event.time(true).with_timezone(&Local).date().naive_local()
+}
+
+fn file_states(files: &[PathBuf]) -> Vec<FileState> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some(FileState {
+                path: path.clone(),
+                length: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// sums up the intervals of the events of one day, like the work time of a day is calculated
+fn day(events: &[TrackingEvent]) -> Option<Option<Day>> {
+    let mut events = events.iter();
+    let mut day: Option<Day> = None;
+    while let Some(start) = events.find(|e| e.is_start()) {
+        let stop = match events.find(|e| e.is_stop()) {
+            Some(stop) => stop,
+            None => return Some(None),
+        };
+        let (start, stop) = (start.time(true), stop.time(true));
+        let without_seconds = |time: DateTime<Utc>| time.with_second(0).expect("valid second");
+        let day = day.get_or_insert(Day {
+            tracked: 0,
+            tracked_minutes: 0,
+            first: start,
+            last: stop,
+        });
+        day.tracked += (stop - start).num_seconds();
+        day.tracked_minutes += (without_seconds(stop) - without_seconds(start)).num_seconds();
+        day.last = stop;
+    }
+    day.map(Some)
+}
+
+impl DayIndex {
+    pub fn path<P: AsRef<Path>>(data_path: P) -> PathBuf {
+        let mut path = data_path.as_ref().as_os_str().to_owned();
+        path.push(".days");
+        PathBuf::from(path)
+    }
+
+    /// reads the index, if it was written for the current state of the data files
+    pub fn read(path: &Path, files: &[PathBuf]) -> Option<Self> {
+        let index: Self = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+        (index.zone == crate::zone::current() && index.files == file_states(files)).then_some(index)
+    }
+
+    pub fn build(data: &[TrackingEvent]) -> Self {
+        let mut index = Self::default();
+        index.update(data, data.iter().map(date));
+        index
+    }
+
+    /// calculates the given days again from the sorted events
+    pub fn update(&mut self, data: &[TrackingEvent], changed: impl IntoIterator<Item = NaiveDate>) {
+        for changed in changed.into_iter().collect::<BTreeSet<_>>() {
+            let first = data.partition_point(|event| date(event) < changed);
+            let length = data[first..].partition_point(|event| date(event) == changed);
+            match day(&data[first..first + length]) {
+                Some(day) => self.days.insert(changed, day),
+                None => self.days.remove(&changed),
+            };
+        }
+    }
+
+    /// writes the index for the data files, which have to be written already
+    pub fn write(&mut self, path: &Path, files: &[PathBuf]) -> Result<(), Error> {
+        self.zone = crate::zone::current();
+        self.files = file_states(files);
+        let content = serde_json::to_vec(self).expect("could not serialize the day index");
+        Ok(std::fs::write(path, content)?)
+    }
+
+    /// the days of the range, or none if one of them has an interval which continues on the next
+    /// day
+    pub fn days(&self, from: NaiveDate, to: NaiveDate) -> Option<Vec<&Day>> {
+        if from > to {
+            return Some(Vec::new());
+        }
+        self.days
+            .range(from..=to)
+            .map(|(_, day)| day.as_ref())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{event, local};
+    use chrono::Duration;
+
+    /// the time in the local zone on the day of april 2021
+    fn at(day: u32, hour: u32, second: i64) -> DateTime<Utc> {
+        local(day, hour, 0) + Duration::seconds(second)
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2021, 4, day)
+    }
+
+    /// two intervals on the first day and a running one on the second
+    fn data() -> Vec<TrackingEvent> {
+        vec![
+            event(true, at(1, 8, 30)),
+            event(false, at(1, 10, 0)),
+            event(true, at(1, 11, 0)),
+            event(false, at(1, 12, 0)),
+            event(true, at(2, 8, 0)),
+        ]
+    }
+
+    fn first() -> Day {
+        Day {
+            tracked: Duration::minutes(180).num_seconds() - 30,
+            tracked_minutes: Duration::minutes(180).num_seconds(),
+            first: at(1, 8, 30),
+            last: at(1, 12, 0),
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let index = DayIndex::build(&data());
+        assert_eq!(Some(vec![&first()]), index.days(day(1), day(1)));
+        assert_eq!(Some(vec![]), DayIndex::build(&[]).days(day(1), day(30)));
+    }
+
+    #[test]
+    fn test_build_running() {
+        // the running interval of the second day isn't in the index
+        let index = DayIndex::build(&data());
+        assert_eq!(None, index.days(day(1), day(2)));
+    }
+
+    #[test]
+    fn test_update() {
+        let mut data = data();
+        let mut index = DayIndex::build(&data);
+        data.push(event(false, at(2, 9, 0)));
+        index.update(&data, vec![day(2)]);
+        assert_eq!(2, index.days(day(1), day(30)).unwrap().len());
+        data.truncate(4);
+        index.update(&data, vec![day(2)]);
+        assert_eq!(Some(vec![&first()]), index.days(day(1), day(30)));
+    }
+}
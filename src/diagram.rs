@@ -0,0 +1,132 @@
+//! diagrams of the time per project for wikis and docs: Mermaid pie and gantt charts, and a
+//! Graphviz graph of the project tree
+
+use crate::project;
+use chrono::{DateTime, Duration, Local};
+
+use std::collections::BTreeMap;
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "'"))
+}
+
+fn hours(duration: Duration) -> String {
+    format!("{:.2}", duration.num_seconds() as f64 / 3600.0)
+}
+
+/// a pie chart with a slice per label and its tracked time in hours
+pub fn pie(title: &str, slices: &[(String, Duration)]) -> String {
+    let mut diagram = format!("pie title {}\n", title.replace('\n', " "));
+    for (label, time) in slices {
+        diagram += &format!("    {} : {}\n", quote(label), hours(*time));
+    }
+    diagram
+}
+
+/// the start, end and name of a task of the gantt chart
+pub type Task = (DateTime<Local>, DateTime<Local>, String);
+
+/// a gantt chart with a section per label and a task per interval, named after the description
+/// of the interval
+pub fn gantt(title: &str, sections: &[(String, Vec<Task>)]) -> String {
+    let mut diagram = format!(
+        "gantt\n    title {}\n    dateFormat YYYY-MM-DD HH:mm\n    axisFormat %m-%d\n",
+        title.replace('\n', " ")
+    );
+    for (label, intervals) in sections {
+        // colons and hashes end the names in mermaid
+        let clean = |text: &str| text.replace([':', '#', ';', '\n'], " ");
+        diagram += &format!("    section {}\n", clean(label));
+        for (start, end, name) in intervals {
+            diagram += &format!(
+                "    {} :{}, {}\n",
+                clean(name),
+                start.format("%Y-%m-%d %H:%M"),
+                end.format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+    diagram
+}
+
+/// a graph of the projects, each with the time of it and its sub projects, and an edge from each
+/// project to its sub projects
+pub fn dot(totals: &[(String, Duration)]) -> String {
+    let mut nodes = BTreeMap::new();
+    for (project, time) in totals {
+        for ancestor in project::ancestors(project) {
+            let total = nodes
+                .entry(ancestor.to_string())
+                .or_insert_with(Duration::zero);
+            *total = *total + *time;
+        }
+    }
+    let mut diagram = String::from("digraph projects {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (project, time) in &nodes {
+        let (hours, minutes) = (time.num_hours(), time.num_minutes() % 60);
+        diagram += &format!(
+            "    {} [label={}];\n",
+            quote(project),
+            quote(&format!("{}\\n{:02}:{:02}", project, hours, minutes))
+        );
+    }
+    for project in nodes.keys() {
+        if let Some((parent, _)) = project.rsplit_once('/') {
+            diagram += &format!("    {} -> {};\n", quote(parent), quote(project));
+        }
+    }
+    diagram += "}\n";
+    diagram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_pie() {
+        let slices = [
+            ("client-a".to_string(), Duration::minutes(90)),
+            ("say \"hi\"".to_string(), Duration::minutes(20)),
+        ];
+        assert_eq!(
+            "pie title April\n    \"client-a\" : 1.50\n    \"say 'hi'\" : 0.33\n",
+            pie("April", &slices)
+        );
+    }
+
+    #[test]
+    fn test_gantt() {
+        let at = |hour| Local.ymd(2021, 4, 1).and_hms(hour, 0, 0);
+        let sections = [(
+            "client-a".to_string(),
+            vec![(at(9), at(12), "website: header".to_string())],
+        )];
+        assert_eq!(
+            "gantt\n    title April\n    dateFormat YYYY-MM-DD HH:mm\n    axisFormat %m-%d\n    \
+             section client-a\n    website  header :2021-04-01 09:00, 2021-04-01 12:00\n",
+            gantt("April", &sections)
+        );
+    }
+
+    #[test]
+    fn test_dot() {
+        let totals = [
+            ("client-a/website".to_string(), Duration::minutes(90)),
+            ("client-a".to_string(), Duration::minutes(30)),
+            ("internal".to_string(), Duration::minutes(45)),
+        ];
+        let expected = "\
+digraph projects {
+    rankdir=LR;
+    node [shape=box];
+    \"client-a\" [label=\"client-a\\n02:00\"];
+    \"client-a/website\" [label=\"client-a/website\\n01:30\"];
+    \"internal\" [label=\"internal\\n00:45\"];
+    \"client-a\" -> \"client-a/website\";
+}
+";
+        assert_eq!(expected, dot(&totals));
+    }
+}
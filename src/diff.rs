@@ -0,0 +1,158 @@
+//! shows which events a command would add and remove, before the changes are applied
+
+use crate::TrackingEvent;
+
+use std::io::{self, IsTerminal};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// colors are only used on a terminal and can be turned off with NO_COLOR
+pub fn use_color() -> bool {
+    io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// the events which were added and removed, in the order of time. Both sides are walked in the
+/// order of time, so sorted data is compared in linear time.
+pub fn changes(
+    before: &[TrackingEvent],
+    after: &[TrackingEvent],
+) -> (Vec<TrackingEvent>, Vec<TrackingEvent>) {
+    fn sorted(events: &[TrackingEvent]) -> Vec<&TrackingEvent> {
+        let mut sorted = events.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|event| event.time(true));
+        sorted
+    }
+    let (before, after) = (sorted(before), sorted(after));
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    let (mut b, mut a) = (0, 0);
+    while b < before.len() || a < after.len() {
+        let time = match (before.get(b), after.get(a)) {
+            (Some(old), Some(new)) => old.time(true).min(new.time(true)),
+            (Some(event), None) | (None, Some(event)) => event.time(true),
+            (None, None) => unreachable!(),
+        };
+        // only the events at the same time need to be compared with each other
+        let group = |events: &[&TrackingEvent], from: usize| {
+            let to = from
+                + events[from..]
+                    .iter()
+                    .take_while(|event| event.time(true) == time)
+                    .count();
+            (from, to)
+        };
+        let (old, new) = (group(&before, b), group(&after, a));
+        let (old_group, new_group) = (&before[old.0..old.1], &after[new.0..new.1]);
+        removed.extend(
+            old_group
+                .iter()
+                .filter(|event| !new_group.contains(event))
+                .map(|event| (*event).clone()),
+        );
+        added.extend(
+            new_group
+                .iter()
+                .filter(|event| !old_group.contains(event))
+                .map(|event| (*event).clone()),
+        );
+        b = old.1;
+        a = new.1;
+    }
+    (added, removed)
+}
+
+/// the removed events prefixed with "-" and the added events prefixed with "+", ordered by
+/// time. A changed event shows up as its old version followed by the new one.
+pub fn render(before: &[TrackingEvent], after: &[TrackingEvent], color: bool) -> Vec<String> {
+    let (added, removed) = changes(before, after);
+    let mut changes = removed
+        .into_iter()
+        .map(|event| (false, event))
+        .chain(added.into_iter().map(|event| (true, event)))
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|(added, event)| (event.time(true), *added));
+    changes
+        .into_iter()
+        .map(|(added, event)| {
+            let line = crate::get_human_readable(std::slice::from_ref(&event)).remove(0);
+            match (added, color) {
+                (false, false) => format!("- {}", line),
+                (true, false) => format!("+ {}", line),
+                (false, true) => format!("{}- {}{}", RED, line, RESET),
+                (true, true) => format!("{}+ {}{}", GREEN, line, RESET),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, start};
+
+    #[test]
+    fn test_render() {
+        let before = [start(at(8, 0), "coding"), start(at(10, 0), "meeting")];
+        let after = [
+            start(at(8, 0), "code review"),
+            start(at(10, 0), "meeting"),
+            start(at(12, 0), "lunch"),
+        ];
+        assert_eq!(
+            vec![
+                "- Start at 2021-04-01 08:00:00 \"coding\"",
+                "+ Start at 2021-04-01 08:00:00 \"code review\"",
+                "+ Start at 2021-04-01 12:00:00 \"lunch\"",
+            ],
+            render(&before, &after, false)
+        );
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let before = [start(at(10, 0), "meeting")];
+        let after = [start(at(10, 0), "meeting"), start(at(12, 0), "lunch")];
+        assert_eq!(
+            vec!["\x1b[32m+ Start at 2021-04-01 12:00:00 \"lunch\"\x1b[0m"],
+            render(&before, &after, true)[..]
+        );
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = [
+            start(at(8, 0), "a"),
+            start(at(8, 0), "b"),
+            start(at(9, 0), "c"),
+            start(at(11, 0), "d"),
+        ];
+        let after = [
+            start(at(8, 0), "b"),
+            start(at(8, 0), "e"),
+            start(at(10, 0), "f"),
+            start(at(11, 0), "d"),
+        ];
+        assert_eq!(
+            (
+                vec![start(at(8, 0), "e"), start(at(10, 0), "f")],
+                vec![start(at(8, 0), "a"), start(at(9, 0), "c")]
+            ),
+            changes(&before, &after)
+        );
+    }
+
+    #[test]
+    fn test_changes_unchanged() {
+        let data = [start(at(8, 0), "a"), start(at(9, 0), "b")];
+        assert_eq!((vec![], vec![]), changes(&data, &data));
+    }
+
+    #[test]
+    fn test_changes_empty() {
+        let data = [start(at(8, 0), "a")];
+        assert_eq!((vec![], vec![]), changes(&[], &[]));
+        assert_eq!((data.to_vec(), vec![]), changes(&[], &data));
+        assert_eq!((vec![], data.to_vec()), changes(&data, &[]));
+    }
+}
@@ -0,0 +1,101 @@
+//! the findings of `tt doctor`, each with a fix for the problems it finds
+
+use serde::Serialize;
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub status: Status,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    pub fn ok(check: &'static str, message: String) -> Self {
+        Self {
+            check,
+            status: Status::Ok,
+            message,
+            fix: None,
+        }
+    }
+
+    pub fn problem(check: &'static str, status: Status, message: String, fix: String) -> Self {
+        Self {
+            check,
+            status,
+            message,
+            fix: Some(fix),
+        }
+    }
+}
+
+pub fn lines(findings: &[Finding]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for finding in findings {
+        let status = match finding.status {
+            Status::Ok => "ok",
+            Status::Warning => "warning",
+            Status::Error => "error",
+        };
+        lines.push(format!(
+            "{:8}{}: {}",
+            status, finding.check, finding.message
+        ));
+        if let Some(fix) = &finding.fix {
+            lines.push(format!("        fix: {}", fix));
+        }
+    }
+    lines
+}
+
+/// the problem with the permissions of a file which only its owner should read
+#[cfg(unix)]
+pub fn permission_problem(path: &Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path).ok()?.permissions().mode();
+    (mode & 0o077 != 0).then(|| format!("can be read by other users (mode {:o})", mode & 0o777))
+}
+
+#[cfg(not(unix))]
+pub fn permission_problem(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let findings = [
+            Finding::ok("Config", "valid".to_string()),
+            Finding::problem(
+                "Data",
+                Status::Warning,
+                "2 duplicate events".to_string(),
+                "run `tt check --fix-duplicates`".to_string(),
+            ),
+        ];
+        assert_eq!(
+            vec![
+                "ok      Config: valid",
+                "warning Data: 2 duplicate events",
+                "        fix: run `tt check --fix-duplicates`",
+            ],
+            lines(&findings)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! durations with units, like "1h30m", "90m", "1.5h" or "2d4h"
+
+use crate::error::Error;
+use chrono::Duration;
+
+/// the units in the order in which they have to appear, with their length in seconds
+const UNITS: &[(char, f64)] = &[('d', 86400.0), ('h', 3600.0), ('m', 60.0), ('s', 1.0)];
+
+/// parses a duration made of numbers followed by a unit. Each unit can be used once, from days
+/// to seconds, and the numbers can have a fraction, which is rounded to seconds.
+pub fn parse(text: &str) -> Result<Duration, Error> {
+    let invalid = || Error::Parse(format!("invalid duration \"{}\"", text));
+    let mut seconds = 0.0;
+    let mut units = UNITS.iter();
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let (_, length) = units.find(|(unit, _)| *unit == c).ok_or_else(invalid)?;
+        if number.starts_with('.') || number.ends_with('.') {
+            return Err(invalid());
+        }
+        let value = number.parse::<f64>().map_err(|_| invalid())?;
+        seconds += value * length;
+        number.clear();
+    }
+    if text.is_empty() || !number.is_empty() || seconds > i64::MAX as f64 / 1000.0 {
+        return Err(invalid());
+    }
+    Ok(Duration::seconds(seconds.round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let minutes = Duration::minutes;
+        assert_eq!(minutes(90), parse("1h30m").unwrap());
+        assert_eq!(minutes(90), parse("90m").unwrap());
+        assert_eq!(minutes(90), parse("1.5h").unwrap());
+        assert_eq!(Duration::hours(52), parse("2d4h").unwrap());
+        assert_eq!(Duration::seconds(45), parse("45s").unwrap());
+        assert_eq!(Duration::seconds(93_784), parse("1d2h3m4s").unwrap());
+        assert_eq!(Duration::seconds(20), parse("0.333m").unwrap());
+        assert_eq!(Duration::zero(), parse("0m").unwrap());
+        assert_eq!(minutes(90), parse("01h030m").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for text in [
+            "",
+            "90",
+            "h",
+            "1h30",
+            "30m1h",
+            "1h1h",
+            "1x",
+            "1 h",
+            "-1h",
+            "1..5h",
+            ".5h",
+            "5.h",
+            "1.2.3h",
+            "1H",
+            "1e3h",
+            "999999999999999999d",
+        ]
+        .iter()
+        {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+    }
+}
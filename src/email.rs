@@ -0,0 +1,113 @@
+//! the weekly report of `tt email-report` as a html mail, which is handed to a sendmail
+//! compatible command like `sendmail -t` or `msmtp -t`
+
+use crate::error::Error;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// a table with a heading, its rows and a last row with the total
+pub struct Table {
+    pub heading: String,
+    pub rows: Vec<(String, String)>,
+    pub total: Option<(String, String)>,
+}
+
+pub fn html(title: &str, tables: &[Table]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n\
+         <body style=\"font-family: sans-serif\">\n<h1>{0}</h1>\n",
+        escape(title)
+    );
+    let cell = "style=\"padding: 2px 12px 2px 0\"";
+    for table in tables {
+        html += &format!("<h2>{}</h2>\n<table>\n", escape(&table.heading));
+        for (label, time) in &table.rows {
+            html += &format!(
+                "<tr><td {0}>{1}</td><td {0} align=\"right\">{2}</td></tr>\n",
+                cell,
+                escape(label),
+                escape(time)
+            );
+        }
+        if let Some((label, time)) = &table.total {
+            html += &format!(
+                "<tr><th {0} align=\"left\">{1}</th><th {0} align=\"right\">{2}</th></tr>\n",
+                cell,
+                escape(label),
+                escape(time)
+            );
+        }
+        html += "</table>\n";
+    }
+    html += "</body>\n</html>\n";
+    html
+}
+
+/// the mail with its headers, the recipients are read from them with `-t`
+pub fn message(from: Option<&str>, to: &str, subject: &str, html: &str) -> String {
+    let mut message = String::new();
+    if let Some(from) = from {
+        message += &format!("From: {}\r\n", from);
+    }
+    message += &format!(
+        "To: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/html; charset=utf-8\r\n\
+         Content-Transfer-Encoding: 8bit\r\n\r\n",
+        to, subject
+    );
+    message += &html.replace('\n', "\r\n");
+    message
+}
+
+/// pipes the message into the command, which may have arguments like "msmtp -t"
+pub fn send(command: &str, message: &str) -> Result<(), Error> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| Error::Parse("the sendmail command is empty".to_string()))?;
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("could not open stdin")
+        .write_all(message.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(Error::InvalidState(format!(
+            "\"{}\" failed with {}",
+            command, status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html() {
+        let tables = [Table {
+            heading: "Projects".to_string(),
+            rows: vec![("<client>".to_string(), "08:00".to_string())],
+            total: Some(("Total".to_string(), "08:00".to_string())),
+        }];
+        let html = html("Week 14", &tables);
+        assert!(html.contains("<h1>Week 14</h1>"));
+        assert!(html.contains(">&lt;client&gt;</td>"));
+        assert!(html.contains(">Total</th>"));
+        let message = message(None, "me@example.com", "Week 14", &html);
+        assert!(message.starts_with("To: me@example.com\r\nSubject: Week 14\r\n"));
+        assert!(message.contains("\r\n\r\n<!DOCTYPE html>\r\n"));
+    }
+}
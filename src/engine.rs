@@ -0,0 +1,331 @@
+//! the core of the time calculations: pairing the events into intervals, splitting them into
+//! days, adding up the work time and parsing times. It doesn't touch files, the system clock or
+//! the local time zone, the current time and the zone are passed in instead. This keeps it easy
+//! to test with fixed times and zones, and to reuse where there is no file system or local zone,
+//! like in WASM. The functions in main.rs with the same purpose inject `clock::now()`, and get
+//! the zone from `main`, which passes `Local` to every command.
+
+use crate::TrackingEvent;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+
+/// the time with or without its seconds
+fn truncate(time: DateTime<Utc>, include_seconds: bool) -> DateTime<Utc> {
+    if include_seconds {
+        time
+    } else {
+        time.with_second(0).expect("could not set seconds to zero")
+    }
+}
+
+/// pairs start and stop events into intervals. A running interval ends at `now`.
+pub fn intervals(
+    data: &[TrackingEvent],
+    include_seconds: bool,
+    now: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut data_iterator = data.iter();
+    let mut intervals = Vec::new();
+    loop {
+        let start = data_iterator.find(|e| e.is_start());
+        let stop = data_iterator.find(|e| e.is_stop());
+        match (start, stop) {
+            (Some(start), Some(stop)) => {
+                intervals.push((start.time(include_seconds), stop.time(include_seconds)));
+            }
+            (Some(start), None) => {
+                intervals.push((start.time(include_seconds), truncate(now, include_seconds)));
+                break;
+            }
+            (_, _) => break,
+        }
+    }
+    intervals
+}
+
+/// splits the sorted events into the days of the zone
+pub fn days<Tz: TimeZone>(data: &[TrackingEvent], zone: &Tz) -> Vec<Vec<TrackingEvent>> {
+    let mut days: Vec<(NaiveDate, Vec<TrackingEvent>)> = Vec::new();
+    for event in data {
+        let date = event.time(true).with_timezone(zone).date().naive_local();
+        match days.last_mut() {
+            Some((day, events)) if *day == date => events.push(event.clone()),
+            _ => days.push((date, vec![event.clone()])),
+        }
+    }
+    days.into_iter().map(|(_, events)| events).collect()
+}
+
+/// the tracked time of the intervals of a day, minus the part of the minimum break which wasn't
+/// taken between the first start and the last stop
+pub fn work_time_of_day(
+    intervals: &[(DateTime<Utc>, DateTime<Utc>)],
+    min_daily_break: Duration,
+    now: DateTime<Utc>,
+) -> Duration {
+    let tracked = intervals
+        .iter()
+        .fold(Duration::zero(), |total, (start, stop)| {
+            total + (*stop - *start)
+        });
+    let first = intervals.first().map(|(start, _)| *start);
+    let last = intervals.last().map(|(_, stop)| *stop);
+    with_min_daily_break(tracked, min_daily_break, first, last, now)
+}
+
+/// takes the missing part of the minimum daily break off the work time
+pub fn with_min_daily_break(
+    mut work_day: Duration,
+    min_daily_break: Duration,
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Duration {
+    if min_daily_break > Duration::zero() {
+        let total = last.unwrap_or(now) - first.unwrap_or(now);
+        let pause = total - work_day;
+        if pause > Duration::zero() && pause < min_daily_break {
+            work_day = work_day - (min_daily_break - pause);
+        }
+    }
+    work_day.max(Duration::zero())
+}
+
+/// the work time of the events, day by day in the zone
+pub fn work_time<Tz: TimeZone>(
+    data: &[TrackingEvent],
+    zone: &Tz,
+    min_daily_break: Duration,
+    include_seconds: bool,
+    now: DateTime<Utc>,
+) -> Duration {
+    days(data, zone)
+        .iter()
+        .map(|day| work_time_of_day(&intervals(day, include_seconds, now), min_daily_break, now))
+        .fold(Duration::zero(), |total, day| total + day)
+}
+
+pub fn split_duration(duration: Duration) -> (i64, i64, i64) {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() - hours * 60;
+    let seconds = duration.num_seconds() - duration.num_minutes() * 60;
+    (hours, minutes, seconds)
+}
+
+/// a time of day like "14:30:15", "9:05", "9:5", "9", "9am", "2:30pm", "14h" or "14h30"
+pub fn parse_time_of_day(s: &str) -> Option<NaiveTime> {
+    let s = s.trim().to_ascii_lowercase();
+    let (s, meridiem) = match (s.strip_suffix("am"), s.strip_suffix("pm")) {
+        (Some(time), _) => (time.trim_end(), Some(0)),
+        (_, Some(time)) => (time.trim_end(), Some(12)),
+        _ => (s.as_str(), None),
+    };
+    let s = s.strip_suffix('h').unwrap_or(s);
+    let mut parts = s.split([':', 'h']).map(|part| {
+        let valid = (1..=2).contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit());
+        valid.then(|| part.parse::<u32>().ok()).flatten()
+    });
+    let hour = parts.next()??;
+    let minute = parts.next().unwrap_or(Some(0))?;
+    let second = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let hour = match meridiem {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+/// the time in the afternoon which a time of day like "5" or "5:30" could mean as well. Times
+/// with "am", "pm", "h" or a leading zero and times from 12 on are meant as typed.
+pub fn afternoon(s: &str) -> Option<NaiveTime> {
+    let s = s.trim();
+    let plain = s.chars().all(|c| c.is_ascii_digit() || c == ':') && !s.starts_with('0');
+    let time = parse_time_of_day(s).filter(|_| plain)?;
+    (1..12)
+        .contains(&time.hour())
+        .then(|| time + Duration::hours(12))
+}
+
+/// a time of day on `today`, or a date and a time of day like "2021-04-01 9:30", in the zone
+pub fn parse_date_time<Tz: TimeZone>(
+    s: &str,
+    today: NaiveDate,
+    zone: &Tz,
+) -> Option<DateTime<Utc>> {
+    let (date, time) = match parse_time_of_day(s) {
+        Some(time) => (today, time),
+        None => {
+            let (date, time) = s.trim().split_once(' ')?;
+            (
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+                parse_time_of_day(time)?,
+            )
+        }
+    };
+    zone.from_local_datetime(&date.and_time(time))
+        .single()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{event, on};
+    use chrono::FixedOffset;
+
+    /// two intervals on the first day, from 8:00 to 12:00 and from 22:00 to 23:00 utc
+    fn first_day() -> Vec<TrackingEvent> {
+        vec![
+            event(true, on(1, 8, 0)),
+            event(false, on(1, 12, 0)),
+            event(true, on(1, 22, 0)),
+            event(false, on(1, 23, 0)),
+        ]
+    }
+
+    fn lengths(days: Vec<Vec<TrackingEvent>>) -> Vec<usize> {
+        days.iter().map(Vec::len).collect()
+    }
+
+    #[test]
+    fn test_afternoon() {
+        assert_eq!(Some(NaiveTime::from_hms(17, 0, 0)), afternoon("5:00"));
+        assert_eq!(Some(NaiveTime::from_hms(23, 30, 0)), afternoon("11:30"));
+        assert_eq!(None, afternoon("05:00"));
+        assert_eq!(None, afternoon("5am"));
+        assert_eq!(None, afternoon("12:15"));
+        assert_eq!(None, afternoon("17:00"));
+        assert_eq!(None, afternoon("2021-04-01 5:00"));
+    }
+
+    #[test]
+    fn test_intervals() {
+        let now = on(2, 12, 0);
+        assert_eq!(
+            vec![(on(1, 8, 0), on(1, 12, 0)), (on(1, 22, 0), on(1, 23, 0))],
+            intervals(&first_day(), false, now)
+        );
+        assert_eq!(
+            Vec::<(DateTime<Utc>, DateTime<Utc>)>::new(),
+            intervals(&[], false, now)
+        );
+    }
+
+    #[test]
+    fn test_intervals_running() {
+        let seconds = Duration::seconds(30);
+        let data = [event(true, on(2, 10, 0) + seconds)];
+        let now = on(2, 12, 0) + seconds;
+        assert_eq!(
+            vec![(on(2, 10, 0), on(2, 12, 0))],
+            intervals(&data, false, now)
+        );
+        assert_eq!(
+            vec![(on(2, 10, 0) + seconds, now)],
+            intervals(&data, true, now)
+        );
+    }
+
+    #[test]
+    fn test_days() {
+        let mut data = first_day();
+        data.push(event(true, on(2, 10, 0)));
+        assert_eq!(vec![4, 1], lengths(days(&data, &Utc)));
+        assert_eq!(Vec::<usize>::new(), lengths(days(&[], &Utc)));
+    }
+
+    #[test]
+    fn test_days_boundaries() {
+        let data = first_day();
+        // 22:00 UTC is already the next day two hours east, 8:00 the day before nine hours west
+        assert_eq!(
+            vec![2, 2],
+            lengths(days(&data, &FixedOffset::east(2 * 3600)))
+        );
+        assert_eq!(
+            vec![1, 3],
+            lengths(days(&data, &FixedOffset::west(9 * 3600)))
+        );
+    }
+
+    #[test]
+    fn test_work_time() {
+        let mut data = first_day();
+        data.push(event(true, on(2, 10, 0)));
+        let now = on(2, 12, 0);
+        assert_eq!(
+            Duration::hours(7),
+            work_time(&data, &Utc, Duration::zero(), false, now)
+        );
+        // the break of 10 hours between the intervals of the first day is long enough
+        assert_eq!(
+            Duration::hours(7),
+            work_time(&data, &Utc, Duration::hours(1), false, now)
+        );
+        assert_eq!(
+            Duration::zero(),
+            work_time(&[], &Utc, Duration::hours(1), false, now)
+        );
+    }
+
+    #[test]
+    fn test_work_time_of_day() {
+        let now = on(2, 12, 0);
+        let intervals = [(on(1, 8, 0), on(1, 12, 0)), (on(1, 12, 15), on(1, 16, 0))];
+        assert_eq!(
+            Duration::minutes(7 * 60 + 30),
+            work_time_of_day(&intervals, Duration::minutes(30), now)
+        );
+        assert_eq!(
+            Duration::minutes(7 * 60 + 45),
+            work_time_of_day(&intervals, Duration::zero(), now)
+        );
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        let time = |hour, minute| Some(NaiveTime::from_hms(hour, minute, 0));
+        assert_eq!(time(9, 5), parse_time_of_day("9:5"));
+        assert_eq!(time(9, 5), parse_time_of_day("09:05"));
+        assert_eq!(time(9, 0), parse_time_of_day("9am"));
+        assert_eq!(time(14, 30), parse_time_of_day("2:30pm"));
+        assert_eq!(time(14, 30), parse_time_of_day("2:30 pm"));
+        assert_eq!(time(0, 15), parse_time_of_day("12:15am"));
+        assert_eq!(time(12, 0), parse_time_of_day("12pm"));
+        assert_eq!(time(14, 0), parse_time_of_day("14h"));
+        assert_eq!(time(14, 30), parse_time_of_day("14h30"));
+        assert_eq!(
+            Some(NaiveTime::from_hms(14, 30, 15)),
+            parse_time_of_day("14:30:15")
+        );
+        for invalid in [
+            "", "13pm", "0am", "9:", ":30", "9:5:1:0", "123", "9:60", "nine", "+9",
+        ]
+        .iter()
+        {
+            assert_eq!(None, parse_time_of_day(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        let today = NaiveDate::from_ymd(2021, 4, 1);
+        let east = FixedOffset::east(2 * 3600);
+        assert_eq!(
+            Some(Utc.ymd(2021, 4, 1).and_hms(7, 30, 0)),
+            parse_date_time("9:30", today, &east)
+        );
+        assert_eq!(
+            Some(Utc.ymd(2021, 3, 31).and_hms(22, 0, 0)),
+            parse_date_time("2021-04-01 0:00", today, &east)
+        );
+        assert_eq!(None, parse_date_time("2021-04-01", today, &Utc));
+        assert_eq!(
+            (26, 5, 9),
+            split_duration(Duration::seconds(26 * 3600 + 309))
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// errors of the tracking operations, so callers can react to them without parsing messages
+#[derive(Debug)]
+pub enum Error {
+    /// user input, like a date or an import file, could not be parsed
+    Parse(String),
+    /// reading or writing a file failed
+    Io(std::io::Error),
+    /// the stored data can't be used
+    InvalidState(String),
+    /// the requested change contradicts the existing events
+    Conflict(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(message) | Self::InvalidState(message) | Self::Conflict(message) => {
+                write!(f, "{}", message)
+            }
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
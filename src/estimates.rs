@@ -0,0 +1,114 @@
+//! the estimates of `tt start --estimate` compared with the time which was actually tracked. The
+//! intervals of a task are added up, so a task which was continued or worked on over several
+//! days is compared as a whole, with the last estimate given for it.
+
+use chrono::Duration;
+
+/// how far off an estimate may be to count as accurate
+pub const TOLERANCE_PERCENT: i64 = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    /// the ticket or, without one, the description
+    pub name: String,
+    pub estimate: Option<Duration>,
+    pub actual: Duration,
+}
+
+impl Task {
+    /// by how many percent the actual time exceeds the estimate, negative if it took less
+    pub fn deviation_percent(&self) -> Option<i64> {
+        let estimate = self.estimate?.num_seconds();
+        (estimate > 0).then(|| (self.actual.num_seconds() - estimate) * 100 / estimate)
+    }
+}
+
+/// adds the tracked time of an interval to its task
+pub fn add(tasks: &mut Vec<Task>, name: String, estimate: Option<Duration>, time: Duration) {
+    match tasks.iter_mut().find(|task| task.name == name) {
+        Some(task) => {
+            task.estimate = estimate.or(task.estimate);
+            task.actual = task.actual + time;
+        }
+        None => tasks.push(Task {
+            name,
+            estimate,
+            actual: time,
+        }),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Accuracy {
+    pub estimated: Duration,
+    pub actual: Duration,
+    /// the tasks which took at most the tolerance more or less than estimated
+    pub accurate: usize,
+    pub tasks: usize,
+}
+
+/// the accuracy over the tasks with an estimate
+pub fn accuracy(tasks: &[Task]) -> Accuracy {
+    let estimated = tasks.iter().filter(|task| task.estimate.is_some());
+    Accuracy {
+        estimated: estimated.clone().fold(Duration::zero(), |total, task| {
+            total + task.estimate.unwrap()
+        }),
+        actual: estimated
+            .clone()
+            .fold(Duration::zero(), |total, task| total + task.actual),
+        accurate: estimated
+            .clone()
+            .filter(|task| {
+                task.deviation_percent()
+                    .is_some_and(|deviation| deviation.abs() <= TOLERANCE_PERCENT)
+            })
+            .count(),
+        tasks: estimated.count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy() {
+        let minutes = Duration::minutes;
+        let mut tasks = Vec::new();
+        add(
+            &mut tasks,
+            "PROJ-1".to_string(),
+            Some(minutes(60)),
+            minutes(50),
+        );
+        add(&mut tasks, "review".to_string(), None, minutes(30));
+        add(&mut tasks, "PROJ-1".to_string(), None, minutes(40));
+        add(
+            &mut tasks,
+            "PROJ-2".to_string(),
+            Some(minutes(60)),
+            minutes(30),
+        );
+        add(
+            &mut tasks,
+            "PROJ-2".to_string(),
+            Some(minutes(40)),
+            minutes(15),
+        );
+        assert_eq!(3, tasks.len());
+        assert_eq!(Some(50), tasks[0].deviation_percent());
+        assert_eq!(None, tasks[1].deviation_percent());
+        // the last estimate counts
+        assert_eq!(Some(12), tasks[2].deviation_percent());
+        assert_eq!(
+            Accuracy {
+                estimated: minutes(100),
+                actual: minutes(135),
+                accurate: 1,
+                tasks: 2,
+            },
+            accuracy(&tasks)
+        );
+    }
+}
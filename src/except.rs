@@ -0,0 +1,202 @@
+//! the time windows which `--except` cuts out of the intervals of `tt show` and `tt report`, e.g.
+//! to see how much time was tracked without the all-hands meeting
+
+use crate::error::Error;
+use crate::{TrackingData, TrackingEvent};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    /// every day without a date
+    date: Option<NaiveDate>,
+    from: NaiveTime,
+    to: NaiveTime,
+}
+
+/// parses a window like "12:00-13:00", or "2021-04-01 12:00-13:00" for a single day
+pub fn parse(s: &str) -> Result<Window, Error> {
+    let invalid = || {
+        Error::Parse(format!(
+            "invalid window \"{}\", expected e.g. \"12:00-13:00\" or \"2021-04-01 12:00-13:00\"",
+            s
+        ))
+    };
+    let (date, times) = match s.trim().split_once(' ') {
+        Some((date, times)) => (
+            Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid())?),
+            times,
+        ),
+        None => (None, s.trim()),
+    };
+    let (from, to) = times.split_once('-').ok_or_else(invalid)?;
+    let from = crate::engine::parse_time_of_day(from).ok_or_else(invalid)?;
+    let to = crate::engine::parse_time_of_day(to).ok_or_else(invalid)?;
+    if from >= to {
+        return Err(invalid());
+    }
+    Ok(Window { date, from, to })
+}
+
+/// the windows between the two times in the zone, sorted
+fn windows_between<Tz: TimeZone>(
+    windows: &[Window],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    zone: &Tz,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let first = from.with_timezone(zone).date().naive_local();
+    let last = to.with_timezone(zone).date().naive_local();
+    let mut between = Vec::new();
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        for window in windows
+            .iter()
+            .filter(|window| window.date.unwrap_or(date) == date)
+        {
+            let time = |time| {
+                zone.from_local_datetime(&date.and_time(time))
+                    .earliest()
+                    .map(|time| time.with_timezone(&Utc))
+            };
+            if let (Some(from), Some(to)) = (time(window.from), time(window.to)) {
+                between.push((from, to));
+            }
+        }
+    }
+    between.sort();
+    between
+}
+
+/// the parts of the interval outside of the windows
+fn remaining(
+    interval: (DateTime<Utc>, DateTime<Utc>),
+    windows: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let (mut from, to) = interval;
+    let mut parts = Vec::new();
+    for (start, end) in windows {
+        if *end <= from || *start >= to {
+            continue;
+        }
+        if *start > from {
+            parts.push((from, *start));
+        }
+        from = from.max(*end);
+    }
+    if from < to {
+        parts.push((from, to));
+    }
+    parts
+}
+
+/// splits the intervals at the windows and leaves out the time within them. A running interval
+/// stays running, unless a window covers now.
+pub fn cut<Tz: TimeZone>(
+    data: &[TrackingEvent],
+    windows: &[Window],
+    zone: &Tz,
+    now: DateTime<Utc>,
+) -> Vec<TrackingEvent> {
+    let mut cut = Vec::new();
+    let mut index = 0;
+    while index < data.len() {
+        let (start, stop) = match (&data[index], data.get(index + 1)) {
+            (TrackingEvent::Start(start), Some(TrackingEvent::Stop(stop))) => (start, Some(stop)),
+            (TrackingEvent::Start(start), None) if start.time < now => (start, None),
+            // the other events don't make an interval
+            (event, _) => {
+                cut.push(event.clone());
+                index += 1;
+                continue;
+            }
+        };
+        let end = stop.map_or(now, |stop| stop.time);
+        let parts = remaining(
+            (start.time, end),
+            &windows_between(windows, start.time, end, zone),
+        );
+        let count = parts.len();
+        for (part, (from, to)) in parts.into_iter().enumerate() {
+            cut.push(TrackingEvent::Start(TrackingData {
+                time: from,
+                ..start.clone()
+            }));
+            let is_last = part + 1 == count;
+            match stop {
+                Some(stop) if is_last => cut.push(TrackingEvent::Stop(TrackingData {
+                    time: to,
+                    ..stop.clone()
+                })),
+                None if is_last && to == now => {}
+                _ => cut.push(TrackingEvent::Stop(TrackingData {
+                    description: None,
+                    time: to,
+                    meta: Default::default(),
+                })),
+            }
+        }
+        index += iif::iif!(stop.is_some(), 2, 1);
+    }
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{event, on};
+
+    #[test]
+    fn test_cut() {
+        let lunch = parse("12:00-13:00").unwrap();
+        let meeting = parse("2021-04-02 9:30-10:00").unwrap();
+        let data = vec![
+            event(true, on(1, 9, 0)),
+            event(false, on(1, 17, 0)),
+            event(true, on(2, 9, 0)),
+            event(false, on(2, 12, 30)),
+        ];
+        assert_eq!(
+            vec![
+                event(true, on(1, 9, 0)),
+                event(false, on(1, 12, 0)),
+                event(true, on(1, 13, 0)),
+                event(false, on(1, 17, 0)),
+                event(true, on(2, 9, 0)),
+                event(false, on(2, 9, 30)),
+                event(true, on(2, 10, 0)),
+                event(false, on(2, 12, 0)),
+            ],
+            cut(&data, &[lunch, meeting], &Utc, on(2, 16, 0))
+        );
+    }
+
+    #[test]
+    fn test_cut_running() {
+        let lunch = parse("12:00-13:00").unwrap();
+        // the running interval is cut up to now, so it doesn't end in the excluded time
+        let data = vec![event(true, on(2, 11, 0))];
+        assert_eq!(
+            vec![event(true, on(2, 11, 0)), event(false, on(2, 12, 0))],
+            cut(&data, &[lunch], &Utc, on(2, 12, 30))
+        );
+        assert_eq!(
+            vec![
+                event(true, on(2, 11, 0)),
+                event(false, on(2, 12, 0)),
+                event(true, on(2, 13, 0)),
+            ],
+            cut(&data, &[lunch], &Utc, on(2, 16, 0))
+        );
+    }
+
+    #[test]
+    fn test_cut_empty() {
+        let lunch = parse("12:00-13:00").unwrap();
+        assert!(cut(&[], &[lunch], &Utc, on(2, 16, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(parse("13:00-12:00").is_err());
+        assert!(parse("lunch").is_err());
+    }
+}
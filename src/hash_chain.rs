@@ -0,0 +1,79 @@
+//! the hash chain of `tt export --audit`: one json record per line, each with the sha-256 hash of
+//! the previous record, so a submitted timesheet can later be checked for changes with `tt check
+//! --audit`. The hash of a record covers its fields with the keys sorted, including `prev`.
+
+use crate::error::Error;
+use crate::sha256::sha256;
+use serde_json::Value;
+
+/// the `prev` of the first record
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// adds `prev` and `hash` to the records, which must be json objects
+pub fn chain(records: Vec<Value>) -> Vec<Value> {
+    let mut prev = GENESIS.to_string();
+    records
+        .into_iter()
+        .map(|mut record| {
+            record["prev"] = Value::from(prev.clone());
+            let hash = sha256(record.to_string().as_bytes());
+            record["hash"] = Value::from(hash.clone());
+            prev = hash;
+            record
+        })
+        .collect()
+}
+
+/// checks the lines of an export and returns the number of records and the last hash
+pub fn verify(content: &str) -> Result<(usize, String), Error> {
+    let mut prev = GENESIS.to_string();
+    let mut count = 0;
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |reason: &str| Error::Conflict(format!("Line {} {}!", index + 1, reason));
+        let mut record: Value =
+            serde_json::from_str(line).map_err(|_| invalid("is not a json record"))?;
+        let hash = match record
+            .as_object_mut()
+            .and_then(|object| object.remove("hash"))
+        {
+            Some(Value::String(hash)) => hash,
+            _ => return Err(invalid("has no hash")),
+        };
+        if record["prev"] != prev.as_str() {
+            return Err(invalid("doesn't follow the previous line"));
+        }
+        if sha256(record.to_string().as_bytes()) != hash {
+            return Err(invalid("was changed"));
+        }
+        prev = hash;
+        count += 1;
+    }
+    Ok((count, prev))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let records = chain(vec![
+            serde_json::json!({ "type": "start", "time": "2021-04-01T08:00:00Z" }),
+            serde_json::json!({ "type": "stop", "time": "2021-04-01T12:00:00Z" }),
+        ]);
+        let content = records
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (count, last) = verify(&content).unwrap();
+        assert_eq!(2, count);
+        assert_eq!(records[1]["hash"], Value::from(last));
+        assert!(verify(&content.replace("12:00", "13:00")).is_err());
+        // a removed line breaks the chain
+        assert!(verify(content.lines().nth(1).unwrap()).is_err());
+    }
+}
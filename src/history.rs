@@ -0,0 +1,77 @@
+//! the last commands tt was called with, including the ones which failed, for `tt last`. Each
+//! line of the history file has the arguments of one command as a json array.
+
+use crate::error::Error;
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// how many commands are kept
+const SIZE: usize = 20;
+
+/// `$XDG_DATA_HOME/timetracking/history`, next to the default data file. The history belongs to
+/// the user, not to a data file.
+pub fn path() -> Option<PathBuf> {
+    crate::paths::data_dir().ok().map(|dir| dir.join("history"))
+}
+
+/// the commands, the oldest first. Lines which can't be decoded are skipped.
+pub fn read(path: &Path) -> Result<Vec<Vec<String>>, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// appends the arguments of a command and drops the oldest commands beyond the size
+pub fn record(path: &Path, args: &[String]) -> Result<(), Error> {
+    let line = serde_json::to_string(args).expect("could not serialize the arguments");
+    let mut commands = read(path)?;
+    if commands.len() < SIZE {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        return Ok(writeln!(file, "{}", line)?);
+    }
+    commands.drain(..=commands.len() - SIZE);
+    let mut content = commands
+        .iter()
+        .map(|command| serde_json::to_string(command).expect("could not serialize the arguments"))
+        .collect::<Vec<_>>();
+    content.push(line);
+    std::fs::write(path, content.join("\n") + "\n")?;
+    Ok(())
+}
+
+/// the arguments as they would be typed, quoting the ones with spaces
+pub fn display(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| iif::iif!(arg.contains(' '), format!("\"{}\"", arg), arg.clone()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let path = std::env::temp_dir().join("timetracking_test.history");
+        let _ = std::fs::remove_file(&path);
+        for index in 0..SIZE + 2 {
+            record(&path, &["start".to_string(), index.to_string()]).unwrap();
+        }
+        let commands = read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(SIZE, commands.len());
+        assert_eq!(vec!["start", "2"], commands[0]);
+        assert_eq!(vec!["start", "21"], commands[SIZE - 1]);
+    }
+}
@@ -0,0 +1,167 @@
+//! translations of the user-facing texts of the reports and of some errors. The texts are
+//! looked up by their english version, which is used when a language has no translation. The
+//! json output isn't translated, so scripts keep working in every language.
+
+use std::cell::Cell;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+    German,
+}
+
+thread_local! {
+    static LANGUAGE: Cell<Language> = const { Cell::new(Language::English) };
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    ("Work Time", "Arbeitszeit"),
+    ("Remaining Work Time", "Verbleibende Arbeitszeit"),
+    ("Work Time ({}): {}", "Arbeitszeit ({}): {}"),
+    ("no {}", "ohne {}"),
+    ("context", "Kontext"),
+    ("project", "Projekt"),
+    (" (billed {})", " (abgerechnet {})"),
+    ("Stopped \"{}\": {}", "Beendet \"{}\": {}"),
+    ("Stopped: {}", "Beendet: {}"),
+    ("Total", "Gesamt"),
+    ("total {}", "gesamt {}"),
+    ("On Call", "Bereitschaft"),
+    ("on call {}", "Bereitschaft {}"),
+    ("Carried Over", "Übertrag"),
+    ("lunch break -{}", "Mittagspause -{}"),
+    ("Deducted Lunch Breaks", "Abgezogene Mittagspausen"),
+    ("Tracked Days", "Erfasste Tage"),
+    ("Days", "Tage"),
+    ("Projects", "Projekte"),
+    ("Average per Day", "Durchschnitt pro Tag"),
+    ("Current Streak (Days)", "Aktuelle Serie (Tage)"),
+    ("Longest Streak (Days)", "Längste Serie (Tage)"),
+    (
+        "Days Reaching the Daily Goal",
+        "Tage mit erreichtem Tagesziel",
+    ),
+    ("Current Goal Streak (Days)", "Aktuelle Zielserie (Tage)"),
+    ("Longest Goal Streak (Days)", "Längste Zielserie (Tage)"),
+    (
+        "{} switches, focus blocks of {} on average",
+        "{} Wechsel, Fokusblöcke von durchschnittlich {}",
+    ),
+    ("Context Switches per Day", "Kontextwechsel pro Tag"),
+    ("Average Focus Block", "Durchschnittlicher Fokusblock"),
+    ("Active", "Aktiv"),
+    ("Description", "Beschreibung"),
+    ("Start Time", "Startzeit"),
+    ("End Time", "Endzeit"),
+    ("Next Break", "Nächste Pause"),
+    ("now (due since {})", "jetzt (fällig seit {})"),
+    ("No Events found!", "Keine Einträge gefunden!"),
+    (
+        "Remaining can't be combined with showing the work time per {}",
+        "Die verbleibende Zeit kann nicht mit der Arbeitszeit pro {} kombiniert werden",
+    ),
+    (
+        "Remaining only works when \"from\" and \"to\" are not set and with no filter or filter \
+         \"week\"",
+        "Die verbleibende Zeit gibt es nur ohne \"from\" und \"to\" und ohne Filter oder mit dem \
+         Filter \"week\"",
+    ),
+    (
+        "The entries before {} are closed. Use --unlock to change them!",
+        "Die Einträge vor dem {} sind abgeschlossen. Mit --unlock können sie geändert werden!",
+    ),
+    (
+        "Please specify the file to import!",
+        "Bitte gib die zu importierende Datei an!",
+    ),
+    (
+        "Please specify the file to export to!",
+        "Bitte gib die Datei für den Export an!",
+    ),
+];
+
+impl Language {
+    /// "en", "de" or "auto", which uses the locale of the environment
+    pub fn parse(language: &str) -> Option<Self> {
+        match language.to_lowercase().as_str() {
+            "auto" => Some(Self::from_locale(
+                ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .iter()
+                    .filter_map(|name| std::env::var(name).ok())
+                    .find(|locale| !locale.is_empty())
+                    .as_deref(),
+            )),
+            "en" => Some(Self::English),
+            "de" => Some(Self::German),
+            _ => None,
+        }
+    }
+
+    /// the language of a locale like "de_DE.UTF-8", english for unknown locales
+    fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            Some(locale) if locale.to_lowercase().starts_with("de") => Self::German,
+            _ => Self::English,
+        }
+    }
+
+    fn translate(self, text: &'static str) -> &'static str {
+        let table = match self {
+            Self::English => return text,
+            Self::German => GERMAN,
+        };
+        table
+            .iter()
+            .find(|(english, _)| *english == text)
+            .map_or(text, |(_, translated)| translated)
+    }
+}
+
+pub fn set(language: Language) {
+    LANGUAGE.with(|current| current.set(language));
+}
+
+/// the text in the current language
+pub fn text(text: &'static str) -> &'static str {
+    LANGUAGE.with(Cell::get).translate(text)
+}
+
+/// the text in the current language, with each "{}" replaced by the next argument
+pub fn format(text: &'static str, arguments: &[&dyn std::fmt::Display]) -> String {
+    let mut parts = self::text(text).split("{}");
+    let mut formatted = parts.next().unwrap_or_default().to_string();
+    for (part, argument) in parts.zip(arguments) {
+        formatted += &argument.to_string();
+        formatted += part;
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!("Arbeitszeit", Language::German.translate("Work Time"));
+        assert_eq!("Work Time", Language::English.translate("Work Time"));
+        // without a translation, the english text is used
+        assert_eq!("Streak", Language::German.translate("Streak"));
+        // the translations keep the arguments
+        for (english, german) in GERMAN {
+            assert_eq!(english.matches("{}").count(), german.matches("{}").count());
+        }
+        assert_eq!(Language::German, Language::from_locale(Some("de_AT.UTF-8")));
+        assert_eq!(Language::English, Language::from_locale(Some("C")));
+        assert_eq!(Language::English, Language::from_locale(None));
+        assert_eq!(None, Language::parse("fr"));
+    }
+
+    #[test]
+    fn test_format() {
+        set(Language::German);
+        assert_eq!("gesamt 08:00", format("total {}", &[&"08:00"]));
+        set(Language::English);
+        assert_eq!("total 08:00", format("total {}", &[&"08:00"]));
+    }
+}
@@ -0,0 +1,367 @@
+use anyhow::{bail, Result};
+use chrono::{prelude::*, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use iif::iif;
+use std::collections::HashSet;
+
+/// an event from a calendar, with recurring events already expanded into single occurrences
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub summary: Option<String>,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+    duration: Option<Duration>,
+    rrule: Option<String>,
+    exdates: Vec<NaiveDateTime>,
+    recurrence_id: Option<NaiveDateTime>,
+    cancelled: bool,
+    all_day: bool,
+}
+
+/// lines starting with a space or tab continue the previous line
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (
+            line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')),
+            lines.last_mut(),
+        ) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// splits "NAME;PARAM=VALUE:content" into the uppercased name, the parameters and the content
+fn split_property(line: &str) -> Option<(String, String, &str)> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.splitn(2, ';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts.next().unwrap_or_default().to_uppercase();
+    Some((name, params, value))
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(c) => result.push(c),
+                None => {}
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// parses a date time value into local time. Values with a TZID are assumed to be in the local
+/// time zone. Returns None for dates without time, which are all day events.
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let date_time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        Some(
+            Utc.from_utc_datetime(&date_time)
+                .with_timezone(&Local)
+                .naive_local(),
+        )
+    } else {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()
+    }
+}
+
+fn is_date(params: &str, value: &str) -> bool {
+    params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")
+        || NaiveDate::parse_from_str(value.trim(), "%Y%m%d").is_ok()
+}
+
+/// parses durations like "PT1H30M" or "P1DT2H"
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                duration = duration
+                    + match (unit, in_time) {
+                        ('W', false) => Duration::weeks(n),
+                        ('D', false) => Duration::days(n),
+                        ('H', true) => Duration::hours(n),
+                        ('M', true) => Duration::minutes(n),
+                        ('S', true) => Duration::seconds(n),
+                        _ => return None,
+                    };
+            }
+        }
+    }
+    Some(if negative { -duration } else { duration })
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    // by day values can have an ordinal prefix like "1MO", which is only valid for monthly rules
+    match day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// expands the start times of a recurring event up to the given time. Only daily and weekly
+/// rules are supported.
+fn occurrences(
+    start: NaiveDateTime,
+    rrule: &str,
+    until: NaiveDateTime,
+) -> Result<Vec<NaiveDateTime>> {
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut rule_until = None;
+    let mut by_day = Vec::new();
+    for part in rrule.split(';') {
+        let mut kv = part.splitn(2, '=');
+        let (key, value) = (kv.next().unwrap_or_default(), kv.next().unwrap_or_default());
+        match key.to_uppercase().as_str() {
+            "FREQ" => frequency = Some(value.to_uppercase()),
+            "INTERVAL" => interval = value.parse::<i64>()?.max(1),
+            "COUNT" => count = Some(value.parse::<usize>()?),
+            "UNTIL" => {
+                rule_until = parse_date_time(value).or_else(|| {
+                    NaiveDate::parse_from_str(value, "%Y%m%d")
+                        .ok()
+                        .map(|date| date.and_hms(23, 59, 59))
+                })
+            }
+            "BYDAY" => by_day = value.split(',').filter_map(parse_weekday).collect(),
+            _ => {}
+        }
+    }
+    let until = rule_until.map_or(until, |rule_until| rule_until.min(until));
+    let mut result = Vec::new();
+    let limit_reached = |result: &Vec<NaiveDateTime>| count.is_some_and(|c| result.len() >= c);
+    match frequency.as_deref() {
+        Some("DAILY") => {
+            let mut current = start;
+            while current <= until && !limit_reached(&result) {
+                result.push(current);
+                current += Duration::days(interval);
+            }
+        }
+        Some("WEEKLY") => {
+            if by_day.is_empty() {
+                by_day.push(start.weekday());
+            }
+            let offset = start.weekday().num_days_from_monday();
+            let mut week = start.date() - Duration::days(i64::from(offset));
+            'weeks: while week.and_time(start.time()) <= until {
+                for day in 0..7 {
+                    let current = (week + Duration::days(day)).and_time(start.time());
+                    if current < start || !by_day.contains(&current.weekday()) {
+                        continue;
+                    }
+                    if current > until || limit_reached(&result) {
+                        break 'weeks;
+                    }
+                    result.push(current);
+                }
+                week += Duration::weeks(interval);
+            }
+        }
+        Some(frequency) => bail!("unsupported recurrence frequency \"{}\"", frequency),
+        None => bail!("recurrence rule without frequency"),
+    }
+    Ok(result)
+}
+
+fn to_local(date_time: NaiveDateTime) -> Option<DateTime<Local>> {
+    match Local.from_local_datetime(&date_time) {
+        LocalResult::Single(date_time) | LocalResult::Ambiguous(date_time, _) => Some(date_time),
+        LocalResult::None => None,
+    }
+}
+
+fn parse_events(content: &str) -> Vec<RawEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    for line in unfold(content) {
+        let (name, params, value) = match split_property(&line) {
+            Some(property) => property,
+            None => continue,
+        };
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(RawEvent::default())
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                events.extend(current.take())
+            }
+            ("UID", Some(event)) => event.uid = Some(value.to_string()),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("DTSTART", Some(event)) => {
+                event.all_day |= is_date(&params, value);
+                event.start = parse_date_time(value);
+            }
+            ("DTEND", Some(event)) => event.end = parse_date_time(value),
+            ("DURATION", Some(event)) => event.duration = parse_duration(value),
+            ("RRULE", Some(event)) => event.rrule = Some(value.to_string()),
+            ("EXDATE", Some(event)) => event
+                .exdates
+                .extend(value.split(',').filter_map(parse_date_time)),
+            ("RECURRENCE-ID", Some(event)) => event.recurrence_id = parse_date_time(value),
+            ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// parses all timed events of an ics calendar which start before `until`. All day events are
+/// skipped, as they can't be tracked as intervals.
+pub fn parse(content: &str, until: DateTime<Local>) -> Result<Vec<CalendarEvent>> {
+    let until = until.naive_local();
+    let events = parse_events(content);
+
+    // occurrences of recurring events which were moved or changed are separate events
+    let overrides = events
+        .iter()
+        .filter_map(|event| Some((event.uid.clone()?, event.recurrence_id?)))
+        .collect::<HashSet<_>>();
+
+    let mut result = Vec::new();
+    for event in events {
+        let start = match event.start {
+            Some(start) if !event.all_day => start,
+            _ => continue,
+        };
+        let duration = match (event.end, event.duration) {
+            (Some(end), _) => end - start,
+            (None, Some(duration)) => duration,
+            (None, None) => Duration::zero(),
+        };
+        if event.cancelled || duration <= Duration::zero() {
+            continue;
+        }
+        let starts = match (&event.rrule, event.recurrence_id) {
+            (Some(rrule), None) => occurrences(start, rrule, until)?,
+            _ => iif!(start <= until, vec![start], vec![]),
+        };
+        for start in starts {
+            let overridden = match (&event.uid, event.recurrence_id) {
+                (Some(uid), None) => overrides.contains(&(uid.clone(), start)),
+                _ => false,
+            };
+            if overridden || event.exdates.contains(&start) {
+                continue;
+            }
+            if let (Some(local_start), Some(local_end)) =
+                (to_local(start), to_local(start + duration))
+            {
+                result.push(CalendarEvent {
+                    summary: event.summary.clone(),
+                    start: local_start,
+                    end: local_end,
+                });
+            }
+        }
+    }
+    result.sort_by_key(|event| event.start);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.ymd(2021, 4, day).and_hms(hour, minute, 0)
+    }
+
+    #[test]
+    fn test_parse() {
+        let calendar = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+SUMMARY:Planning\\, long\r
+  version\r
+DTSTART:20210401T100000\r
+DTEND:20210401T113000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20210402\r
+DTEND;VALUE=DATE:20210403\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:3\r
+SUMMARY:Standup\r
+DTSTART;TZID=Europe/Berlin:20210405T093000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=5\r
+EXDATE;TZID=Europe/Berlin:20210407T093000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:3\r
+SUMMARY:Standup\r
+RECURRENCE-ID;TZID=Europe/Berlin:20210409T093000\r
+DTSTART;TZID=Europe/Berlin:20210409T100000\r
+DTEND;TZID=Europe/Berlin:20210409T101500\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let event = |summary: &str, start, end| CalendarEvent {
+            summary: Some(summary.to_string()),
+            start,
+            end,
+        };
+        assert_eq!(
+            vec![
+                event("Planning, long version", at(1, 10, 0), at(1, 11, 30)),
+                event("Standup", at(5, 9, 30), at(5, 9, 45)),
+                event("Standup", at(9, 10, 0), at(9, 10, 15)),
+                event("Standup", at(12, 9, 30), at(12, 9, 45)),
+            ],
+            parse(calendar, at(13, 0, 0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(Some(Duration::minutes(90)), parse_duration("PT1H30M"));
+        assert_eq!(
+            Some(Duration::days(1) + Duration::hours(2)),
+            parse_duration("P1DT2H")
+        );
+        assert_eq!(Some(Duration::weeks(-1)), parse_duration("-P1W"));
+        assert_eq!(None, parse_duration("1H"));
+    }
+}
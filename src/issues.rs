@@ -0,0 +1,85 @@
+//! checks the ticket of `tt start --task` in the issue tracker, so no time is booked on tickets
+//! which are closed or assigned to someone else. tt doesn't talk to the trackers itself, the
+//! command from the config is run with the ticket as last argument and prints its state as json,
+//! like `{"closed": false, "assignee": "jdoe"}`.
+
+use crate::error::Error;
+use serde::Deserialize;
+
+use std::process::Command;
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Status {
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub assignee: Option<String>,
+}
+
+/// runs the command, which may have arguments like "jira-status --json", for the ticket
+pub fn status(command: &str, task: &str) -> Result<Status, Error> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| Error::Parse("the issue command is empty".to_string()))?;
+    let output = Command::new(program).args(words).arg(task).output()?;
+    if !output.status.success() {
+        return Err(Error::InvalidState(format!(
+            "\"{}\" failed with {}",
+            command, output.status
+        )));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|_| {
+        Error::Parse(format!(
+            "could not read the state of {} from the output of \"{}\"",
+            task, command
+        ))
+    })
+}
+
+/// a warning if the ticket is closed or, if the user is known, assigned to someone else
+pub fn warning(task: &str, status: &Status, user: Option<&str>) -> Option<String> {
+    if status.closed {
+        return Some(format!("The ticket {} is closed!", task));
+    }
+    match (user, status.assignee.as_deref()) {
+        (Some(_), None) => Some(format!("The ticket {} is not assigned!", task)),
+        (Some(user), Some(assignee)) if !user.eq_ignore_ascii_case(assignee) => {
+            Some(format!("The ticket {} is assigned to {}!", task, assignee))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning() {
+        let status = |closed, assignee: Option<&str>| Status {
+            closed,
+            assignee: assignee.map(String::from),
+        };
+        assert_eq!(
+            None,
+            warning("PROJ-1", &status(false, Some("JDoe")), Some("jdoe"))
+        );
+        assert_eq!(None, warning("PROJ-1", &status(false, None), None));
+        assert!(warning("PROJ-1", &status(true, Some("jdoe")), Some("jdoe"))
+            .unwrap()
+            .contains("closed"));
+        assert!(
+            warning("PROJ-1", &status(false, Some("alice")), Some("jdoe"))
+                .unwrap()
+                .contains("assigned to alice")
+        );
+        assert!(warning("PROJ-1", &status(false, None), Some("jdoe"))
+            .unwrap()
+            .contains("not assigned"));
+        assert_eq!(
+            status(false, None),
+            serde_json::from_str::<Status>("{}").unwrap()
+        );
+    }
+}
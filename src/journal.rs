@@ -0,0 +1,199 @@
+use crate::error::Error;
+use crate::TrackingEvent;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// how many operations are kept for undo
+const MAX_OPERATIONS: usize = 100;
+
+/// a command which modified the data. It is reverted by removing `added` and adding `removed`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    pub command: String,
+    pub time: DateTime<Utc>,
+    pub added: Vec<TrackingEvent>,
+    pub removed: Vec<TrackingEvent>,
+}
+
+impl Operation {
+    fn inverse(&self) -> Self {
+        Self {
+            added: self.removed.clone(),
+            removed: self.added.clone(),
+            ..self.clone()
+        }
+    }
+
+    fn apply(&self, data: &mut Vec<TrackingEvent>) -> Result<(), Error> {
+        if let Some(missing) = self.removed.iter().find(|event| !data.contains(event)) {
+            return Err(Error::Conflict(format!(
+                "\"{}\" can't be applied, because the event at {} was changed since then",
+                self.command,
+                missing.time(true).with_timezone(&chrono::Local)
+            )));
+        }
+        data.retain(|event| !self.removed.contains(event));
+        data.extend(self.added.iter().cloned());
+        data.sort_by_key(|e| e.time(true));
+        Ok(())
+    }
+}
+
+/// the operations which can be undone and redone, saved next to the data file
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    done: Vec<Operation>,
+    undone: Vec<Operation>,
+}
+
+impl Journal {
+    pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+        let mut path = data_file.as_ref().as_os_str().to_owned();
+        path.push(".journal");
+        path.into()
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        match std::fs::read_to_string(&path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+            Ok(content) => serde_json::from_str(&content).map_err(|_| {
+                Error::InvalidState(format!(
+                    "could not decode the journal {}",
+                    path.as_ref().display()
+                ))
+            }),
+        }
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let content = serde_json::to_string(self).expect("could not serialize journal");
+        Ok(std::fs::write(path, content)?)
+    }
+
+    /// records a new operation. Operations which were undone can't be redone after this.
+    pub fn record(&mut self, operation: Operation) {
+        if operation.added.is_empty() && operation.removed.is_empty() {
+            return;
+        }
+        self.done.push(operation);
+        if self.done.len() > MAX_OPERATIONS {
+            self.done.remove(0);
+        }
+        self.undone.clear();
+    }
+
+    /// reverts the last `steps` operations and returns them, the latest first
+    pub fn undo(
+        &mut self,
+        data: &mut Vec<TrackingEvent>,
+        steps: usize,
+    ) -> Result<Vec<Operation>, Error> {
+        let mut undone = Vec::new();
+        for _ in 0..steps {
+            match self.done.pop() {
+                None => break,
+                Some(operation) => {
+                    if let Err(e) = operation.inverse().apply(data) {
+                        self.done.push(operation);
+                        return Err(e);
+                    }
+                    undone.push(operation.clone());
+                    self.undone.push(operation);
+                }
+            }
+        }
+        Ok(undone)
+    }
+
+    /// applies the last `steps` undone operations again and returns them
+    pub fn redo(
+        &mut self,
+        data: &mut Vec<TrackingEvent>,
+        steps: usize,
+    ) -> Result<Vec<Operation>, Error> {
+        let mut redone = Vec::new();
+        for _ in 0..steps {
+            match self.undone.pop() {
+                None => break,
+                Some(operation) => {
+                    if let Err(e) = operation.apply(data) {
+                        self.undone.push(operation);
+                        return Err(e);
+                    }
+                    redone.push(operation.clone());
+                    self.done.push(operation);
+                }
+            }
+        }
+        Ok(redone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, event};
+
+    fn start(hour: u32) -> TrackingEvent {
+        event(true, at(hour, 0))
+    }
+
+    fn operation(added: Vec<TrackingEvent>, removed: Vec<TrackingEvent>) -> Operation {
+        Operation {
+            command: "test".to_string(),
+            time: at(12, 0),
+            added,
+            removed,
+        }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut journal = Journal::default();
+        let mut data = vec![start(8), start(9)];
+        journal.record(operation(vec![start(8)], vec![]));
+        journal.record(operation(vec![start(9)], vec![]));
+
+        assert_eq!(2, journal.undo(&mut data, 5).unwrap().len());
+        assert!(data.is_empty());
+        assert_eq!(1, journal.redo(&mut data, 1).unwrap().len());
+        assert_eq!(vec![start(8)], data);
+    }
+
+    #[test]
+    fn test_record_drops_undone() {
+        let mut journal = Journal::default();
+        journal.record(operation(vec![start(8)], vec![]));
+        let mut data = vec![start(8)];
+        journal.undo(&mut data, 1).unwrap();
+        journal.record(operation(vec![start(10)], vec![]));
+        data = vec![start(10)];
+        assert!(journal.redo(&mut data, 1).unwrap().is_empty());
+        journal.undo(&mut data, 1).unwrap();
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_undo_empty() {
+        let mut journal = Journal::default();
+        let mut data = vec![start(8)];
+        assert!(journal.undo(&mut data, 1).unwrap().is_empty());
+        assert_eq!(vec![start(8)], data);
+    }
+
+    #[test]
+    fn test_undo_conflict() {
+        // events which were changed since can't be undone
+        let mut journal = Journal::default();
+        journal.record(operation(vec![start(10)], vec![start(8)]));
+        let mut data = vec![];
+        assert!(matches!(
+            journal.undo(&mut data, 1),
+            Err(Error::Conflict(_))
+        ));
+    }
+}
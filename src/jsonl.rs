@@ -0,0 +1,119 @@
+//! writes one json object per interval and line, e.g. for `jq` or data pipelines
+
+use crate::after_hours::Flags;
+use crate::error::Error;
+use crate::TrackingEvent;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Serialize)]
+struct IntervalLine<'a> {
+    start: DateTime<Utc>,
+    /// none while the interval is running
+    end: Option<DateTime<Utc>>,
+    /// in seconds
+    duration: Option<i64>,
+    description: Option<String>,
+    context: Option<&'a str>,
+    project: Option<&'a str>,
+    tags: &'a [String],
+    /// in seconds, if the project is rounded for billing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    billed: Option<i64>,
+    /// on a day off or outside of the working hours
+    weekend: bool,
+    after_hours: bool,
+    /// in seconds, on the last interval of a day whose lunch break is deducted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lunch_deduction: Option<i64>,
+}
+
+/// writes the intervals one at a time as they are found, without collecting them first.
+/// Returns the number of written lines. `billed` has the billed time, `flags` the flags and
+/// `deductions` the deducted lunch break of each interval.
+pub fn write<W: Write>(
+    mut writer: W,
+    data: &[TrackingEvent],
+    billed: &[Option<Duration>],
+    flags: &[Flags],
+    deductions: &[Option<Duration>],
+) -> Result<usize, Error> {
+    let mut events = data.iter();
+    let mut count = 0;
+    while let Some(start) = events.find(|e| e.is_start()) {
+        let end = events.find(|e| e.is_stop()).map(|stop| stop.time(true));
+        let meta = start.meta();
+        let line = IntervalLine {
+            start: start.time(true),
+            end,
+            duration: end.map(|end| (end - start.time(true)).num_seconds()),
+            description: start.description(),
+            context: meta.context.as_deref(),
+            project: meta.project.as_deref(),
+            tags: &meta.tags,
+            billed: end
+                .and(billed.get(count).copied().flatten())
+                .map(|billed| billed.num_seconds()),
+            weekend: flags.get(count).is_some_and(|flags| flags.weekend),
+            after_hours: flags.get(count).is_some_and(|flags| flags.after_hours),
+            lunch_deduction: deductions
+                .get(count)
+                .copied()
+                .flatten()
+                .map(|deduction| deduction.num_seconds()),
+        };
+        serde_json::to_writer(&mut writer, &line).expect("could not serialize interval");
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, event, start};
+
+    #[test]
+    fn test_write() {
+        let mut buffer = Vec::new();
+        let count = write(
+            &mut buffer,
+            &[
+                start(at(8, 0), "coding"),
+                event(false, at(10, 0)),
+                start(at(11, 0), "coding"),
+            ],
+            &[Some(Duration::hours(3)), None],
+            &[
+                Flags::default(),
+                Flags {
+                    weekend: true,
+                    after_hours: false,
+                },
+            ],
+            &[Some(Duration::minutes(30))],
+        )
+        .unwrap();
+
+        assert_eq!(2, count);
+        assert_eq!(
+            "{\"start\":\"2021-04-01T08:00:00Z\",\"end\":\"2021-04-01T10:00:00Z\",\"duration\":7200,\
+             \"description\":\"coding\",\"context\":null,\"project\":null,\"tags\":[],\
+             \"billed\":10800,\"weekend\":false,\"after_hours\":false,\"lunch_deduction\":1800}\n\
+             {\"start\":\"2021-04-01T11:00:00Z\",\"end\":null,\"duration\":null,\
+             \"description\":\"coding\",\"context\":null,\"project\":null,\"tags\":[],\
+             \"weekend\":true,\"after_hours\":false}\n",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_empty() {
+        let mut buffer = Vec::new();
+        assert_eq!(0, write(&mut buffer, &[], &[], &[], &[]).unwrap());
+        assert!(buffer.is_empty());
+    }
+}
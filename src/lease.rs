@@ -0,0 +1,198 @@
+//! the lease on the data file, so two tt processes which change the data don't overwrite each
+//! other's changes. The process which changes the data creates the lease file next to the data
+//! file before reading it and removes it after writing. Others wait for it, and take over leases
+//! whose process isn't running anymore. Only one process at a time takes over a lease, and it
+//! replaces the lease file with its own one by renaming, so the lease is never missing meanwhile.
+
+use crate::error::Error;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// how long to wait for another process
+const WAIT: Duration = Duration::from_secs(5);
+
+/// when the process can't be checked, the lease is taken over after this many seconds
+const STALE_SECONDS: i64 = 600;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Holder {
+    pub pid: u32,
+    pub since: DateTime<Utc>,
+}
+
+impl Holder {
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        match running(self.pid) {
+            Some(running) => !running,
+            None => (now - self.since).num_seconds() > STALE_SECONDS,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn running(pid: u32) -> Option<bool> {
+    Some(Path::new("/proc").join(pid.to_string()).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn running(_pid: u32) -> Option<bool> {
+    None
+}
+
+pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    with_suffix(data_file.as_ref(), ".lease")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// removes the lease file when dropped
+#[derive(Debug)]
+pub struct Lease {
+    path: PathBuf,
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn try_acquire(path: &Path, holder: &Holder) -> io::Result<Lease> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    let lease = Lease {
+        path: path.to_path_buf(),
+    };
+    let content = serde_json::to_string(holder).expect("could not serialize the lease");
+    file.write_all(content.as_bytes())?;
+    Ok(lease)
+}
+
+/// replaces the stale lease with a freshly written one. Returns none if another process is
+/// taking it over or took it over already.
+fn take_over(path: &Path, stale: &Holder, own: &Holder) -> io::Result<Option<Lease>> {
+    let guard_path = with_suffix(path, ".takeover");
+    let _guard = match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&guard_path)
+    {
+        Ok(_) => Lease { path: guard_path },
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            // a takeover only takes a moment, so the process which started it is gone
+            let age = std::fs::metadata(&guard_path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok());
+            if age.is_some_and(|age| age >= WAIT) {
+                let _ = std::fs::remove_file(&guard_path);
+            }
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    if holder(path).as_ref() != Some(stale) {
+        return Ok(None);
+    }
+    let fresh = with_suffix(path, &format!(".{}", own.pid));
+    let content = serde_json::to_string(own).expect("could not serialize the lease");
+    let renamed = std::fs::write(&fresh, content).and_then(|_| std::fs::rename(&fresh, path));
+    if let Err(e) = renamed {
+        let _ = std::fs::remove_file(&fresh);
+        return Err(e);
+    }
+    Ok(Some(Lease {
+        path: path.to_path_buf(),
+    }))
+}
+
+/// the holder of the lease, none while it is being written
+pub fn holder(path: &Path) -> Option<Holder> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// takes the lease, after waiting for the process which holds it
+pub fn acquire(path: &Path) -> Result<Lease, Error> {
+    let started = Instant::now();
+    loop {
+        let now = Utc::now();
+        let own = Holder {
+            pid: std::process::id(),
+            since: now,
+        };
+        match try_acquire(path, &own) {
+            Ok(lease) => return Ok(lease),
+            Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e.into()),
+            Err(_) => {}
+        }
+        let holder = holder(path);
+        if let Some(stale) = holder.as_ref().filter(|holder| holder.is_stale(now)) {
+            if let Some(lease) = take_over(path, stale, &own)? {
+                return Ok(lease);
+            }
+        }
+        if started.elapsed() >= WAIT {
+            let by = holder.map_or_else(String::new, |holder| {
+                format!(
+                    " by tt (pid {}) since {}",
+                    holder.pid,
+                    holder
+                        .since
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                )
+            });
+            return Err(Error::Conflict(format!(
+                "The data file is being changed{}! If that process doesn't run anymore, remove {}",
+                by,
+                path.display()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire() {
+        let path = std::env::temp_dir().join("timetracking_test.lease");
+        let _ = std::fs::remove_file(&path);
+        let lease = acquire(&path).unwrap();
+        assert_eq!(Some(std::process::id()), holder(&path).map(|h| h.pid));
+        drop(lease);
+        assert!(!path.exists());
+
+        // the lease of a process which is gone is taken over
+        let stale = Holder {
+            pid: u32::MAX,
+            since: Utc::now() - chrono::Duration::hours(1),
+        };
+        std::fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        let lease = acquire(&path).unwrap();
+        assert_eq!(Some(std::process::id()), holder(&path).map(|h| h.pid));
+        assert!(!with_suffix(&path, ".takeover").exists());
+
+        // a lease which was taken over already isn't replaced
+        let other = Holder {
+            pid: 1,
+            since: Utc::now(),
+        };
+        assert!(take_over(&path, &other, &stale).unwrap().is_none());
+        drop(lease);
+    }
+}
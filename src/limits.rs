@@ -0,0 +1,158 @@
+//! daily limits for descriptions and projects, like at most 2h of email a day
+
+use crate::duration_parse;
+use crate::error::Error;
+use crate::project;
+use crate::settings::LimitSettings;
+use crate::TrackingEvent;
+use chrono::Duration;
+use serde::Serialize;
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Description,
+    Project,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Usage {
+    pub kind: Kind,
+    /// the lowercase description or project of the limit
+    pub name: String,
+    pub limit: Duration,
+    /// the time tracked today for the description or project, including the running interval
+    pub tracked: Duration,
+}
+
+/// the limit for the lowercase name, the keys can be written in any case
+fn find<'a>(limits: &'a HashMap<String, String>, name: &str) -> Option<&'a String> {
+    limits
+        .iter()
+        .find(|(key, _)| key.to_lowercase() == name)
+        .map(|(_, limit)| limit)
+}
+
+fn parse(limit: &str) -> Result<Duration, Error> {
+    duration_parse::parse(limit.trim()).map_err(|_| {
+        Error::Parse(format!(
+            "invalid limit \"{}\", expected a duration like \"2h\"",
+            limit
+        ))
+    })
+}
+
+/// the limits of the running interval, with the time tracked for them today. `today` has the
+/// start event of each interval of today with its duration today.
+pub fn usage(
+    limits: &LimitSettings,
+    running: &TrackingEvent,
+    today: &[(&TrackingEvent, Duration)],
+) -> Result<Vec<Usage>, Error> {
+    let tracked = |matches: &dyn Fn(&TrackingEvent) -> bool| {
+        today
+            .iter()
+            .filter(|(start, _)| matches(start))
+            .fold(Duration::zero(), |sum, (_, duration)| sum + *duration)
+    };
+    let mut usage = Vec::new();
+    if let Some(description) = running.description().map(|d| d.to_lowercase()) {
+        if let Some(limit) = find(&limits.descriptions, &description) {
+            usage.push(Usage {
+                kind: Kind::Description,
+                limit: parse(limit)?,
+                tracked: tracked(&|start| {
+                    start.description().map(|d| d.to_lowercase()).as_ref() == Some(&description)
+                }),
+                name: description,
+            });
+        }
+    }
+    if let Some(project) = running.project().map(|p| p.to_lowercase()) {
+        // the limit of the closest project which has one
+        let limited = project::ancestors(&project)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .find_map(|ancestor| Some((ancestor, find(&limits.projects, ancestor)?)));
+        if let Some((limited, limit)) = limited {
+            usage.push(Usage {
+                kind: Kind::Project,
+                limit: parse(limit)?,
+                tracked: tracked(&|start| {
+                    start
+                        .project()
+                        .is_some_and(|project| project::is_within(&project.to_lowercase(), limited))
+                }),
+                name: limited.to_string(),
+            });
+        }
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, start, with_meta};
+    use crate::Metadata;
+
+    #[test]
+    fn test_usage() {
+        let strings = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        let limits = LimitSettings {
+            descriptions: strings(&[("email", "2h")]),
+            projects: strings(&[("Client-A", "6h"), ("client-a/support", "1h")]),
+        };
+        let start = |description: &str, project: &str| {
+            let meta = Metadata {
+                project: Some(project.to_string()),
+                ..Metadata::default()
+            };
+            with_meta(start(at(8, 0), description), meta)
+        };
+        let (email, support, website) = (
+            start("Email", "client-a/support/tickets"),
+            start("email", "internal"),
+            start("website", "client-a/website"),
+        );
+        let minutes = Duration::minutes;
+        let today = [
+            (&email, minutes(70)),
+            (&support, minutes(30)),
+            (&website, minutes(45)),
+        ];
+        assert_eq!(
+            vec![
+                Usage {
+                    kind: Kind::Description,
+                    name: "email".to_string(),
+                    limit: Duration::hours(2),
+                    tracked: minutes(100),
+                },
+                Usage {
+                    kind: Kind::Project,
+                    name: "client-a/support".to_string(),
+                    limit: Duration::hours(1),
+                    tracked: minutes(70),
+                },
+            ],
+            usage(&limits, &email, &today).unwrap()
+        );
+        assert_eq!(
+            vec!["client-a".to_string()],
+            usage(&limits, &website, &today)
+                .unwrap()
+                .into_iter()
+                .map(|usage| usage.name)
+                .collect::<Vec<_>>()
+        );
+    }
+}
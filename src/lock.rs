@@ -0,0 +1,85 @@
+//! closed months. Entries before the cutoff can only be changed with `--unlock`, which is
+//! recorded in the audit log next to the data file.
+
+use crate::error::Error;
+use crate::journal::Operation;
+use chrono::{Datelike, NaiveDate};
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+fn with_suffix<P: AsRef<Path>>(data_file: P, suffix: &str) -> PathBuf {
+    let mut path = data_file.as_ref().as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// the file holding the first day which is not closed
+pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    with_suffix(data_file, ".lock")
+}
+
+/// the file listing the changes of closed entries, one json object per line
+pub fn audit_path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    with_suffix(data_file, ".audit")
+}
+
+/// the entries before this day are closed
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Option<NaiveDate>, Error> {
+    match std::fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+        Ok(content) => NaiveDate::parse_from_str(content.trim(), "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| {
+                Error::InvalidState(format!("could not read {}", path.as_ref().display()))
+            }),
+    }
+}
+
+pub fn write<P: AsRef<Path>>(path: P, closed_until: NaiveDate) -> Result<(), Error> {
+    Ok(std::fs::write(
+        path,
+        closed_until.format("%Y-%m-%d").to_string(),
+    )?)
+}
+
+/// the first day after the month, e.g. 2024-05-01 for "2024-04"
+pub fn end_of_month(month: &str) -> Result<NaiveDate, Error> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map_err(|_| Error::Parse(format!("invalid month \"{}\", expected YYYY-MM", month)))?;
+    let (year, month) = iif::iif!(
+        first.month() == 12,
+        (first.year() + 1, 1),
+        (first.year(), first.month() + 1)
+    );
+    Ok(NaiveDate::from_ymd(year, month, 1))
+}
+
+pub fn audit<P: AsRef<Path>>(path: P, operation: &Operation) -> Result<(), Error> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let line = serde_json::to_string(operation).expect("could not serialize operation");
+    Ok(writeln!(file, "{}", line)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_of_month() {
+        assert_eq!(
+            NaiveDate::from_ymd(2024, 5, 1),
+            end_of_month("2024-04").unwrap()
+        );
+        assert_eq!(
+            NaiveDate::from_ymd(2025, 1, 1),
+            end_of_month("2024-12").unwrap()
+        );
+        assert!(end_of_month("2024-13").is_err());
+        assert!(end_of_month("april").is_err());
+    }
+}
@@ -2,31 +2,129 @@ use anyhow::{Context, Result};
 use chrono::{prelude::*, serde::ts_seconds, Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use iif::iif;
 use serde::{Deserialize, Serialize};
-use std::io;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
-use structopt::StructOpt;
+use structopt::{clap::AppSettings, StructOpt};
 
+mod activity;
+mod after_hours;
+mod alias;
+mod allocation;
+mod anonymize;
+mod billing;
+mod breaks;
+mod calc;
+mod changes;
+mod clock;
+mod context;
+#[cfg(feature = "credentials")]
+mod credentials;
+mod csv;
+mod day_index;
+mod diagram;
+mod diff;
+mod doctor;
+mod duration_parse;
+mod email;
+mod engine;
+mod error;
+mod estimates;
+mod except;
+mod hash_chain;
+mod history;
+mod i18n;
+mod ics;
+mod issues;
+mod journal;
+mod jsonl;
+mod lease;
+mod limits;
+mod lock;
+mod normalize;
+mod offline;
+mod outcome;
+mod output;
+mod overlaps;
+mod overtime;
+mod pager;
+mod paths;
+mod pattern;
+mod plan;
+mod planned;
+mod predict;
+mod project;
+mod quick;
+mod report;
+mod rules;
+#[cfg(feature = "binary")]
+mod segments;
 mod settings;
+mod setup;
+mod sha256;
+mod similarity;
+mod storage;
+mod streaks;
+mod suggest;
+mod switches;
+#[cfg(test)]
+mod test_data;
+mod time_range;
+mod timeline;
+mod toggl;
+mod trash;
+#[cfg(feature = "window-title")]
+mod window;
+mod zone;
 
+use day_index::DayIndex;
+use engine::split_duration;
+use error::Error;
+use journal::{Journal, Operation};
+use outcome::{ContinueOutcome, StartOutcome, StopOutcome};
+use output::{ChangesOutput, DurationOutput, Output, OutputFormat};
 use settings::Settings;
+use trash::Trash;
 
 #[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::InferSubcommands])]
 struct Options {
     #[cfg(feature = "binary")]
-    /// which data file to use. [default: ~/timetracking.bin]
+    /// which data file to use. [default: $XDG_DATA_HOME/timetracking/timetracking.bin]
     #[structopt(short, long)]
     data_file: Option<PathBuf>,
 
     #[cfg(not(feature = "binary"))]
-    /// which data file to use. [default: ~/timetracking.json]
+    /// which data file to use. [default: $XDG_DATA_HOME/timetracking/timetracking.json]
     #[structopt(short, long)]
     data_file: Option<PathBuf>,
 
+    /// how the results of commands are printed. "plain" prints one fact per line with its
+    /// label, like "total: 08:00", without colors, tables or charts, e.g. for screen readers
+    #[structopt(long, default_value = "text", possible_values = &["text", "json", "plain"])]
+    output: OutputFormat,
+
+    /// use this time instead of the current time. format: "YY-mm-dd HH:MM:SS"
+    #[structopt(long, hidden = true)]
+    now: Option<String>,
+
+    /// fail instead of running commands which would change the data
+    #[structopt(long)]
+    read_only: bool,
+
+    /// allow changing entries of closed months. The changes are recorded in the audit log
+    #[structopt(long)]
+    unlock: bool,
+
+    /// don't show long output of list and report in $PAGER
+    #[structopt(long)]
+    no_pager: bool,
+
     #[structopt(subcommand)]
     command: Option<Command>,
 }
 
-#[derive(Default, Debug, StructOpt)]
+#[derive(Clone, Default, Debug, StructOpt)]
 struct FilterData {
     /// show all entries after this point in time [defaults to current day 00:00:00]
     /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S"
@@ -38,8 +136,38 @@ struct FilterData {
     #[structopt(short, long)]
     to: Option<String>,
 
+    /// show all entries within this time span. possible values: "today", "yesterday",
+    /// "this-week", "last-week", "this-month", "last-month", "this-year", "last-year" or two
+    /// dates like "2024-04-01..2024-04-12". Can be repeated to combine several time spans
+    #[structopt(long, number_of_values = 1, conflicts_with_all = &["from", "to"])]
+    range: Vec<String>,
+
+    /// only include intervals that were started in this context
+    #[structopt(long)]
+    context: Option<String>,
+
+    /// only include intervals of this project and its sub projects
+    #[structopt(long)]
+    project: Option<String>,
+
+    /// only include intervals that were started on these days, e.g. "mon-fri" or "mon,wed"
+    #[structopt(long, conflicts_with = "weekend")]
+    weekdays: Option<String>,
+
+    /// only include intervals that were started on saturday or sunday
+    #[structopt(long)]
+    weekend: bool,
+
+    /// only include intervals with this approval state, see `tt approve`
+    #[structopt(long, possible_values = &["approved", "rejected", "pending"])]
+    approval: Option<String>,
+
     /// filter entries. possible filter values: "week", "all" or part of the description
     filter: Option<String>,
+
+    /// the windows which are cut out of the intervals, from `--except` of show and report
+    #[structopt(skip)]
+    except: Vec<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -50,24 +178,117 @@ enum Command {
     #[cfg(not(feature = "binary"))]
     /// export data to file
     Export {
+        /// write one json object per interval and line. Use "-" as path to write to stdout
+        #[structopt(long)]
+        json_lines: bool,
+        /// replace descriptions, projects, contexts, tags and notes with placeholders like
+        /// "description 1", e.g. to attach the data to a bug report. The times are kept
+        #[structopt(long)]
+        anonymize: bool,
+        /// write a diagram of the time per project instead of the data, to paste into a wiki: a
+        /// Mermaid pie chart ("mermaid-pie") or gantt chart ("mermaid-gantt"), or a Graphviz
+        /// graph of the project tree ("dot"). Use "-" as path to write to stdout
+        #[structopt(
+            long,
+            possible_values = &["mermaid-pie", "mermaid-gantt", "dot"],
+            conflicts_with = "json-lines"
+        )]
+        diagram: Option<String>,
+        /// write the events as json lines with a hash chain, so the export can later be checked
+        /// for changes with `tt check --audit`. Use "-" as path to write to stdout
+        #[structopt(long, conflicts_with_all = &["json-lines", "diagram"])]
+        audit: bool,
+        /// the time spans of the diagram or the audit export, see `tt show --help` [default:
+        /// everything]
+        #[structopt(long, number_of_values = 1)]
+        range: Vec<String>,
         /// where to write the output file
-        path: PathBuf,
+        path: Option<PathBuf>,
+
+        #[structopt(subcommand)]
+        target: Option<ExportTarget>,
     },
 
     /// show info from the latest entry. Returns the exit code 0, if the time tracking is currently
     /// active and -1 if not.
-    Status,
+    Status {
+        /// don't print anything and exit with 1 instead of -1 if the time tracking is not active
+        #[structopt(short, long)]
+        quiet: bool,
+    },
 
     /// starts an interactive cleanup session
     Cleanup,
 
+    /// list duplicate events, overlapping intervals and events in the future, e.g. after merging
+    /// the data of two machines whose clocks differ
+    Check {
+        /// merge each group of overlapping intervals into one interval ("union") or ask for each
+        /// group ("interactive"). The removed entries are moved to the trash
+        #[structopt(long, possible_values = &["union", "interactive"])]
+        fix_overlaps: Option<String>,
+
+        /// collapse the duplicate events into the first one, before looking for overlaps. The
+        /// removed entries are moved to the trash
+        #[structopt(long)]
+        fix_duplicates: bool,
+
+        /// check the hash chain of this file from `tt export --audit` instead of the data
+        #[structopt(long, conflicts_with_all = &["fix-overlaps", "fix-duplicates"])]
+        audit: Option<PathBuf>,
+    },
+
+    /// check the config, the data file and its storage, the keyring, the lease and the data
+    /// itself, and show how to fix the problems found. Exits with 1 if there are errors
+    Doctor,
+
     /// start time tracking
     Start {
         /// a description for the event
         description: Option<String>,
 
+        /// the context to record for the event (e.g. "office" or "home") [defaults to the
+        /// detected context]
+        #[structopt(long)]
+        context: Option<String>,
+
+        /// the project the time is tracked for. Projects can be nested with "/", e.g.
+        /// "client-a/website/frontend"
+        #[structopt(long)]
+        project: Option<String>,
+
+        /// suggest a description based on the descriptions used around this time in the past,
+        /// if none is given
+        #[structopt(long)]
+        suggest: bool,
+
+        /// never suggest a description
+        #[structopt(long, conflicts_with = "suggest")]
+        no_suggest: bool,
+
+        /// use the description, project, context and tags of this template from the config,
+        /// unless they are given
+        #[structopt(long)]
+        template: Option<String>,
+
+        /// start the next placeholder of `tt record --planned`, with its description, project,
+        /// context and tags unless they are given
+        #[structopt(long, conflicts_with = "template")]
+        from_plan: bool,
+
+        /// the ticket in the issue tracker the time is booked on, e.g. "PROJ-123". With a
+        /// command in the issues table of the config, warns if the ticket is closed or assigned
+        /// to someone else
+        #[structopt(long)]
+        task: Option<String>,
+
+        /// how long the task is expected to take, e.g. "2h" or "1h30m", to compare it with the
+        /// tracked time in `tt report --estimates`
+        #[structopt(long)]
+        estimate: Option<String>,
+
         /// the time at which the event happend.
-        /// format: "HH:MM:SS" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS" [defaults to current time]
         #[structopt(short, long)]
         at: Option<String>,
     },
@@ -78,23 +299,284 @@ enum Command {
         description: Option<String>,
 
         /// the time at which the event happend.
-        /// format: "HH:MM:SS" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        #[structopt(short, long)]
+        at: Option<String>,
+
+        /// don't print the stopped interval and the work time of its day
+        #[structopt(short, long)]
+        quiet: bool,
+    },
+
+    /// (experimental) read a single line from stdin and run it: "s <description>" starts, "x"
+    /// stops and "c" continues the time tracking, e.g. from a terminal popup bound to a hotkey
+    Quick,
+
+    /// continue time tracking with the description, project and tags of the last interval
+    Continue {
+        /// use this description instead of the last one
+        #[structopt(long)]
+        description: Option<String>,
+
+        /// use this project instead of the last one, its tags are added
+        #[structopt(long)]
+        project: Option<String>,
+
+        /// the context to record for the event [defaults to the detected context]
+        #[structopt(long)]
+        context: Option<String>,
+
+        /// use these tags instead of the last ones. Can be given multiple times
+        #[structopt(long = "tag", number_of_values = 1)]
+        tags: Vec<String>,
+    },
+
+    /// record a finished interval from a time range like "9-17:30", "yesterday 9:15-12" or
+    /// "2021-04-01 22-2". The command can be left out, e.g. `tt 9-17:30 "client work"`
+    Record {
+        /// the start and end, optionally after "today", "yesterday", "tomorrow" (with --planned) or
+        /// a date
+        range: String,
+
+        /// a description for the interval
+        description: Option<String>,
+
+        /// the context to record for the interval [defaults to the detected context]
+        #[structopt(long)]
+        context: Option<String>,
+
+        /// the project the time is tracked for
+        #[structopt(long)]
+        project: Option<String>,
+
+        /// a tag for the interval. Can be given multiple times
+        #[structopt(long = "tag", number_of_values = 1)]
+        tags: Vec<String>,
+
+        /// add a placeholder for planned work instead, which doesn't count as tracked time and
+        /// is started with `tt start --from-plan`, e.g. `tt record --planned "tomorrow 9-12"
+        /// review`
+        #[structopt(long)]
+        planned: bool,
+    },
+
+    /// record an interruption of the running interval, e.g. `tt interrupt "phone call"`
+    Interrupt {
+        /// what interrupted the work
+        note: Option<String>,
+
+        /// the time of the interruption.
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS" [defaults to current time]
         #[structopt(short, long)]
         at: Option<String>,
     },
 
-    /// continue time tracking with last description
-    Continue,
+    /// revert the last commands which changed the data
+    Undo {
+        /// how many commands to revert
+        #[structopt(default_value = "1")]
+        steps: usize,
+    },
+
+    /// apply the last reverted commands again
+    Redo {
+        /// how many commands to apply again
+        #[structopt(default_value = "1")]
+        steps: usize,
+    },
 
     /// list all entries
     List {
         #[structopt(flatten)]
         filter: FilterData,
+
+        /// show the times in the time zone in which each entry was recorded
+        #[structopt(long)]
+        event_local: bool,
+
+        /// show the work time of the day up to the end of each interval
+        #[structopt(long)]
+        cumulative: bool,
+
+        /// keep watching the data file and show new entries as they are added, until stopped
+        #[structopt(long)]
+        follow: bool,
+    },
+
+    /// show or override the time zone used to record and show entries, e.g. while traveling
+    Tz {
+        #[structopt(subcommand)]
+        command: Option<TzCommand>,
+    },
+
+    /// calculate with times, e.g. "17:30 - 08:45 - 0:30"
+    Calc {
+        /// the times to add up or subtract. Allowed formats are: "HH:MM:SS", "HH:MM", "HH" and
+        /// durations with units like "1h30m", "90m", "1.5h" or "2d4h"
+        expression: String,
+
+        /// how to format the result [default: "{hh}:{mm}", or "{hh}:{mm}:{ss}" with seconds]
+        #[structopt(long)]
+        format: Option<String>,
+    },
+
+    /// add tags and projects to entries according to the rules in the config
+    Classify {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// change the entries. Without this, the changes are only shown
+        #[structopt(long)]
+        apply: bool,
+    },
+
+    /// find descriptions which only differ by typos and rename them to the most used one
+    DedupeDescriptions {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// the maximum number of changed characters between similar descriptions
+        #[structopt(long, default_value = "2")]
+        max_distance: usize,
+
+        /// rename the descriptions of all groups without asking
+        #[structopt(long)]
+        yes: bool,
+    },
+
+    /// send the report of a week as html mail, e.g. `tt email-report --week last --to
+    /// me@example.com`. The mail is handed to the sendmail command of the config
+    EmailReport {
+        /// the week to report
+        #[structopt(long, default_value = "last", possible_values = &["this", "last"])]
+        week: String,
+
+        /// the recipient [defaults to "to" in the email table of the config]
+        #[structopt(long)]
+        to: Option<String>,
+
+        /// print the mail instead of sending it
+        #[structopt(long)]
+        dry_run: bool,
+    },
+
+    /// close the entries up to the end of a month, so they can only be changed with --unlock
+    CloseMonth {
+        /// the last month to close, e.g. "2024-04". Without it, shows what is closed
+        month: Option<String>,
+    },
+
+    /// mark the intervals as submitted, e.g. `tt mark-submitted --range last-week`. Status and
+    /// report warn about earlier weeks which are not submitted.
+    MarkSubmitted {
+        #[structopt(flatten)]
+        filter: FilterData,
+    },
+
+    /// approve the intervals, e.g. when a lead reviews a shared data file: `tt approve --range
+    /// last-week`. Check warns about earlier weeks which are not approved.
+    Approve {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// reject the intervals instead
+        #[structopt(long)]
+        reject: bool,
+    },
+
+    /// move entries to the trash
+    Delete {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+
+    /// move entries by a duration, e.g. `tt shift --range 2024-05-01..2024-05-03 --by -1h` for
+    /// entries recorded with a wrong clock or time zone
+    Shift {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// how far to move the entries, like "-1h" or "+30m"
+        #[structopt(long, allow_hyphen_values = true)]
+        by: String,
+
+        /// only show the changes
+        #[structopt(long)]
+        dry_run: bool,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+
+    /// split the time of an interval across projects by percentage, e.g. `tt allocate 9:00
+    /// client-a=60 client-b=40` for work which is billed to two clients at the same time. The
+    /// work time per project counts the shares instead of the project of the interval
+    Allocate {
+        /// the start of the interval or "last" for the latest one.
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS"
+        start: String,
+
+        /// the shares like "client-a=60", which add up to 100. Without shares, the interval
+        /// isn't split anymore
+        shares: Vec<String>,
+    },
+
+    /// list the changes to the data after the given sequence number, e.g. for sync tools. Each
+    /// change to the data gets the next sequence number, starting with the first change after
+    /// updating to a version with the change feed
+    Changes {
+        /// the sequence number of the last change which was seen already
+        #[structopt(long, default_value = "0")]
+        since: u64,
+    },
+
+    /// list the last commands, also the ones which failed
+    Last {
+        /// run the last start or stop again, with the same flags
+        #[structopt(long)]
+        repeat: bool,
+    },
+
+    /// mark the periods in which time is tracked on another machine, e.g. while this one has no
+    /// connection for syncing. Gaps in them are intentional, the import and the report point
+    /// them out.
+    Offline {
+        #[structopt(subcommand)]
+        command: OfflineCommand,
+    },
+
+    /// show, restore or remove deleted entries
+    Trash {
+        #[structopt(subcommand)]
+        command: TrashCommand,
     },
 
     /// show path to data file
     Path,
 
+    /// show all files which are read or written, and the flags and environment variables which
+    /// decided where they are
+    Paths,
+
+    /// create the global config by answering a few questions
+    Init {
+        /// replace an existing config
+        #[structopt(long)]
+        force: bool,
+    },
+
+    /// open the global config or the data in $VISUAL or $EDITOR. The changes are only saved if
+    /// they are valid, the data is edited as json. Removed entries are moved to the trash
+    Open {
+        #[structopt(possible_values = &["config", "data"])]
+        target: String,
+    },
+
     /// show work time for given timespan
     Show {
         #[structopt(flatten)]
@@ -115,6 +597,119 @@ enum Command {
         /// show only the time with no additional text. [default: "{hh}:{mm}:{ss}"]
         #[structopt(long)]
         format: Option<String>,
+
+        /// show the work time per context
+        #[structopt(long)]
+        by_context: bool,
+
+        /// show the work time per project
+        #[structopt(long, conflicts_with = "by-context")]
+        by_project: bool,
+
+        /// with --by-project, add up the work time of sub projects below this level
+        #[structopt(long, requires = "by-project")]
+        depth: Option<usize>,
+
+        /// show an hour by hour timeline of the tracked time for each day
+        #[structopt(long)]
+        timeline: bool,
+
+        /// leave out the time within this window, e.g. "12:00-13:00" on every day or
+        /// "2021-04-01 12:00-13:00" on one day. Can be repeated
+        #[structopt(long, number_of_values = 1)]
+        except: Vec<String>,
+    },
+    /// estimate when the time goals of today and this week are reached, based on the usual
+    /// arrival time and breaks
+    Predict,
+    /// show the number of tracked days and the average time per day
+    Stats {
+        /// show the current and longest streaks of work days with tracked time and of days
+        /// which reached the daily goal. Days off don't end a streak
+        #[structopt(long)]
+        streaks: bool,
+
+        /// show how often the description or project changed per day, and how long the focus
+        /// blocks of work on the same description and project were on average
+        #[structopt(long)]
+        switches: bool,
+    },
+    /// show the work time per day for given timespan
+    Report {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// list the time within the configured working hours which wasn't tracked
+        #[structopt(long)]
+        gaps: bool,
+
+        /// list the tracked time within the configured on-call windows
+        #[structopt(long, conflicts_with = "gaps")]
+        on_call: bool,
+
+        /// show a chart with a row per project or description and a column per day
+        #[structopt(long, conflicts_with_all = &["gaps", "on-call"])]
+        gantt: bool,
+
+        /// show the number of interruptions per day and description
+        #[structopt(long, conflicts_with_all = &["gaps", "on-call", "gantt"])]
+        interruptions: bool,
+
+        /// show the work time up to each day
+        #[structopt(long)]
+        cumulative: bool,
+
+        /// list the entries which were added or removed since the data was exported to this json
+        /// file, e.g. with `tt export submitted.json`
+        #[structopt(long, conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions"])]
+        diff_against: Option<PathBuf>,
+
+        /// list the commits of the user in these git repositories which were made outside of the
+        /// tracked intervals
+        #[structopt(
+            long,
+            number_of_values = 1,
+            conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions"]
+        )]
+        commits: Vec<PathBuf>,
+
+        /// run this program with the intervals as json lines on stdin, like `tt export
+        /// --json-lines`, and show its output. Names without a path are looked up in the scripts
+        /// directory next to the global config
+        #[structopt(
+            long,
+            conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions", "diff-against", "commits"]
+        )]
+        script: Option<PathBuf>,
+
+        /// compare the tracked time with the time which is planned per day and per project in
+        /// this toml file
+        #[structopt(
+            long,
+            conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions", "diff-against", "commits", "script"]
+        )]
+        plan: Option<PathBuf>,
+
+        /// compare the tracked time of each task with its estimate from `tt start --estimate`,
+        /// and how accurate the estimates were
+        #[structopt(
+            long,
+            conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions", "diff-against", "commits", "script", "plan"]
+        )]
+        estimates: bool,
+
+        /// list the placeholders of `tt record --planned` per day, with the planned and the
+        /// tracked time
+        #[structopt(
+            long,
+            conflicts_with_all = &["gaps", "on-call", "gantt", "interruptions", "diff-against", "commits", "script", "plan", "estimates"]
+        )]
+        plan_view: bool,
+
+        /// leave out the time within this window, e.g. "12:00-13:00" on every day or
+        /// "2021-04-01 12:00-13:00" on one day. Can be repeated
+        #[structopt(long, number_of_values = 1)]
+        except: Vec<String>,
     },
     #[cfg(feature = "binary")]
     /// export data to file
@@ -126,14 +721,225 @@ enum Command {
         /// pretty print json
         #[structopt(short, long)]
         pretty: bool,
+        /// write one json object per interval and line. Use "-" as path to write to stdout
+        #[structopt(long, conflicts_with_all = &["readable", "pretty"])]
+        json_lines: bool,
+        /// replace descriptions, projects, contexts, tags and notes with placeholders like
+        /// "description 1", e.g. to attach the data to a bug report. The times are kept
+        #[structopt(long)]
+        anonymize: bool,
+        /// write a diagram of the time per project instead of the data, to paste into a wiki: a
+        /// Mermaid pie chart ("mermaid-pie") or gantt chart ("mermaid-gantt"), or a Graphviz
+        /// graph of the project tree ("dot"). Use "-" as path to write to stdout
+        #[structopt(
+            long,
+            possible_values = &["mermaid-pie", "mermaid-gantt", "dot"],
+            conflicts_with = "json-lines"
+        )]
+        diagram: Option<String>,
+        /// write the events as json lines with a hash chain, so the export can later be checked
+        /// for changes with `tt check --audit`. Use "-" as path to write to stdout
+        #[structopt(long, conflicts_with_all = &["json-lines", "diagram"])]
+        audit: bool,
+        /// the time spans of the diagram or the audit export, see `tt show --help` [default:
+        /// everything]
+        #[structopt(long, number_of_values = 1)]
+        range: Vec<String>,
         /// where to write the output file
-        path: PathBuf,
+        path: Option<PathBuf>,
+
+        #[structopt(subcommand)]
+        target: Option<ExportTarget>,
     },
-    #[cfg(feature = "binary")]
     /// import data from json file
     Import {
+        /// which file to import. Its entries replace all entries
+        path: Option<PathBuf>,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+
+        #[structopt(subcommand)]
+        source: Option<ImportSource>,
+    },
+    #[cfg(feature = "credentials")]
+    /// manage credentials for integrations. Credentials are stored in the keyring of the os
+    Auth {
+        #[structopt(subcommand)]
+        command: AuthCommand,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum ImportSource {
+    /// import events from an ics calendar file
+    Ics {
+        /// which file to import
+        path: PathBuf,
+
+        /// import calendar events after this point in time [defaults to current day 00:00:00]
+        /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S"
+        #[structopt(short, long)]
+        from: Option<String>,
+
+        /// import calendar events before this point in time [defaults to start day 23:59:59]
+        /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S"
+        #[structopt(short, long)]
+        to: Option<String>,
+
+        /// filter calendar events. possible filter values: "week", "all" or part of the summary
+        filter: Option<String>,
+
+        /// add the calendar events as tracked intervals. Without this, the events which would be
+        /// imported are only shown
+        #[structopt(long)]
+        as_intervals: bool,
+
+        /// tag to add to the imported events. Can be given multiple times
+        #[structopt(long = "tag")]
+        tags: Vec<String>,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+    /// import intervals from a csv file, e.g. the export of another time tracking tool
+    Csv {
         /// which file to import
         path: PathBuf,
+
+        /// which columns of the header row hold the fields of the intervals, e.g.
+        /// "start=Start Time,stop=End Time,desc=Task". Possible fields: start, stop, desc, context,
+        /// project
+        #[structopt(short, long)]
+        map: String,
+
+        /// the format of the dates in the file, e.g. "%d.%m.%Y %H:%M" [defaults to the formats of
+        /// --at]
+        #[structopt(long)]
+        date_format: Option<String>,
+
+        /// the character between the columns
+        #[structopt(long, default_value = ",")]
+        delimiter: char,
+
+        /// only show the intervals which would be imported
+        #[structopt(long)]
+        dry_run: bool,
+
+        /// tag to add to the imported events. Can be given multiple times
+        #[structopt(long = "tag")]
+        tags: Vec<String>,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+    /// pull the time entries from Toggl Track. The api token is read from the keyring, see `tt
+    /// auth set toggl`, or from $TOGGL_API_TOKEN, and the toggl table of the config maps the
+    /// projects and tags. Entries which overlap with other local intervals are listed as
+    /// conflicts and not imported
+    TogglApi {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// only show the intervals which would be imported
+        #[structopt(long)]
+        dry_run: bool,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum ExportTarget {
+    /// push the intervals to Toggl Track, unless they are in toggl already. Intervals which
+    /// overlap with other toggl entries are listed as conflicts and not pushed
+    TogglApi {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        /// only show the intervals which would be pushed
+        #[structopt(long)]
+        dry_run: bool,
+
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum TzCommand {
+    /// use this zone instead of the zone of the system for all following commands
+    Set {
+        /// an IANA time zone, e.g. "Europe/Berlin"
+        zone: String,
+    },
+    /// use the zone of the system again
+    Unset,
+}
+
+#[derive(Debug, StructOpt)]
+enum OfflineCommand {
+    /// start an offline period
+    Start {
+        /// the time at which the period starts.
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        #[structopt(short, long)]
+        at: Option<String>,
+    },
+    /// end the offline period
+    End {
+        /// the time at which the period ends.
+        /// format: "HH:MM:SS", "9:05", "2:30pm" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        #[structopt(short, long)]
+        at: Option<String>,
+    },
+    /// list the offline periods
+    List,
+}
+
+#[derive(Debug, StructOpt)]
+enum TrashCommand {
+    /// list the deleted entries, the latest first
+    List,
+    /// add deleted entries back to the data
+    Restore {
+        /// which of the listed deletions to restore
+        #[structopt(default_value = "0")]
+        index: usize,
+    },
+    /// remove the deleted entries permanently
+    Empty {
+        /// don't ask for confirmation
+        #[structopt(short, long)]
+        yes: bool,
+    },
+}
+
+#[cfg(feature = "credentials")]
+#[derive(Debug, StructOpt)]
+enum AuthCommand {
+    /// store a credential for a service. The credential is read from stdin
+    Set {
+        /// the service the credential is used for
+        service: String,
+    },
+
+    /// remove the stored credential for a service
+    Remove {
+        /// the service the credential is used for
+        service: String,
+    },
+
+    /// check if a credential is stored for a service
+    Check {
+        /// the service the credential is used for
+        service: String,
     },
 }
 
@@ -145,6 +951,99 @@ impl Default for Command {
             include_seconds: false,
             plain: false,
             remaining: false,
+            by_context: false,
+            by_project: false,
+            depth: None,
+            timeline: false,
+            except: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    interruptions: Vec<Interruption>,
+
+    /// the local time zone when the event was recorded, e.g. "Europe/Berlin"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    zone: Option<String>,
+
+    /// the offset of the local time zone to utc in seconds, when the event was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<i32>,
+
+    /// whether the interval was submitted, e.g. to a time sheet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    submitted: bool,
+
+    /// the title of the active window when the interval was started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    window_title: Option<String>,
+
+    /// whether the interval was reviewed, none while it is pending
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    approval: Option<Approval>,
+
+    /// the ticket in the issue tracker, e.g. "PROJ-123"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    task: Option<String>,
+
+    /// the expected duration of the task in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimate: Option<i64>,
+
+    /// the projects the time of the interval is split across, instead of its project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allocation: Vec<allocation::Share>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Approval {
+    Approved,
+    Rejected,
+}
+
+/// a moment in which the work on the interval was interrupted, e.g. by a phone call
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Interruption {
+    time: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+/// Metadata is stored as a json string in binary formats, so new fields can be added without
+/// breaking existing data files. Human readable formats store it as a regular object.
+mod metadata_format {
+    use super::Metadata;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(meta: &Metadata, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            meta.serialize(serializer)
+        } else {
+            serde_json::to_string(meta)
+                .map_err(ser::Error::custom)?
+                .serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Metadata, D::Error> {
+        if deserializer.is_human_readable() {
+            Metadata::deserialize(deserializer)
+        } else {
+            let json = String::deserialize(deserializer)?;
+            serde_json::from_str(&json).map_err(de::Error::custom)
         }
     }
 }
@@ -155,6 +1054,9 @@ struct TrackingData {
 
     #[serde(with = "ts_seconds")]
     time: DateTime<Utc>,
+
+    #[serde(default, with = "metadata_format")]
+    meta: Metadata,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -184,6 +1086,20 @@ impl TrackingEvent {
         }
     }
 
+    fn meta(&self) -> &Metadata {
+        match self {
+            Self::Start(TrackingData { meta, .. }) | Self::Stop(TrackingData { meta, .. }) => meta,
+        }
+    }
+
+    fn context(&self) -> Option<String> {
+        self.meta().context.clone()
+    }
+
+    fn project(&self) -> Option<String> {
+        self.meta().project.clone()
+    }
+
     fn is_start(&self) -> bool {
         match self {
             Self::Start(_) => true,
@@ -218,153 +1134,850 @@ impl From<NaiveDateTime> for DateOrDateTime {
     }
 }
 
+/// binary layout of data files written before events had metadata
 #[cfg(feature = "binary")]
-fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    let data = std::fs::read(&path)?;
-    Ok(bincode::deserialize(&data)?)
-}
+mod legacy {
+    use super::{Metadata, TrackingData, TrackingEvent};
+    use chrono::{serde::ts_seconds, DateTime, Utc};
+    use serde::Deserialize;
 
-#[cfg(not(feature = "binary"))]
-fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    read_json_data(path)
-}
+    #[derive(Deserialize)]
+    pub struct LegacyTrackingData {
+        description: Option<String>,
 
-fn read_json_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    let data = std::fs::read_to_string(&path)?;
-    Ok(serde_json::from_str(&data)?)
+        #[serde(with = "ts_seconds")]
+        time: DateTime<Utc>,
+    }
+
+    #[derive(Deserialize)]
+    pub enum LegacyTrackingEvent {
+        Start(LegacyTrackingData),
+        Stop(LegacyTrackingData),
+    }
+
+    impl From<LegacyTrackingData> for TrackingData {
+        fn from(LegacyTrackingData { description, time }: LegacyTrackingData) -> Self {
+            Self {
+                description,
+                time,
+                meta: Metadata::default(),
+            }
+        }
+    }
+
+    impl From<LegacyTrackingEvent> for TrackingEvent {
+        fn from(event: LegacyTrackingEvent) -> Self {
+            match event {
+                LegacyTrackingEvent::Start(data) => Self::Start(data.into()),
+                LegacyTrackingEvent::Stop(data) => Self::Stop(data.into()),
+            }
+        }
+    }
 }
 
-#[cfg(feature = "binary")]
-fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent]) {
-    let data = bincode::serialize(data).expect("could not serialize data");
-    std::fs::write(path, data).expect("could not write data file");
+fn read_json_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>, Error> {
+    let data = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&data).map_err(|e| {
+        Error::Parse(format!(
+            "could not parse {}: {}",
+            path.as_ref().display(),
+            e
+        ))
+    })
 }
 
-fn write_json_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent], pretty: bool) {
+fn write_json_data<P: AsRef<Path>>(
+    path: P,
+    data: &[TrackingEvent],
+    pretty: bool,
+) -> Result<(), Error> {
     let data = iif!(
         pretty,
         serde_json::to_string_pretty(data),
         serde_json::to_string(data)
     )
     .expect("could not serialize data");
-    std::fs::write(path, data).expect("could not write data file");
+    Ok(std::fs::write(path, data)?)
+}
+
+/// whether the command can change the data, the journal or the trash
+fn changes_data(command: &Command) -> bool {
+    match command {
+        Command::Show { .. }
+        | Command::Report { .. }
+        | Command::Status { .. }
+        | Command::List { .. }
+        | Command::Tz { .. }
+        | Command::CloseMonth { month: None }
+        | Command::Check {
+            fix_overlaps: None,
+            fix_duplicates: false,
+            ..
+        }
+        | Command::Path
+        | Command::Paths
+        | Command::Calc { .. }
+        | Command::EmailReport { .. }
+        | Command::Shift { dry_run: true, .. }
+        | Command::Predict
+        | Command::Stats { .. }
+        | Command::Export { .. }
+        | Command::Changes { .. }
+        | Command::Last { .. }
+        | Command::Doctor
+        | Command::Trash {
+            command: TrashCommand::List,
+        }
+        | Command::Offline {
+            command: OfflineCommand::List,
+        } => false,
+        #[cfg(feature = "credentials")]
+        Command::Auth { .. } => false,
+        Command::Classify { apply, .. } => *apply,
+        Command::Init { .. } => false,
+        Command::Open { target } => target == "data",
+        Command::Import {
+            source: Some(ImportSource::Csv { dry_run, .. } | ImportSource::TogglApi { dry_run, .. }),
+            ..
+        } => !dry_run,
+        _ => true,
+    }
+}
+
+/// the data file and, with per year files, the files of the years
+fn data_files(path: &str, per_year: bool) -> Result<Vec<PathBuf>> {
+    let mut files = vec![PathBuf::from(path)];
+    if per_year {
+        files.extend(
+            storage::shard_paths(path)?
+                .into_iter()
+                .map(|(_, path)| path),
+        );
+    }
+    Ok(files)
+}
+
+/// which events the command needs. Commands which don't change the data only read the events
+/// they show.
+fn data_selection(command: &Command, indexed: bool) -> Result<storage::Selection> {
+    let range = match command {
+        // the warning about unsubmitted weeks needs the earlier events
+        Command::Status { quiet: true } => return Ok(storage::Selection::Latest),
+        Command::Changes { .. } => return Ok(storage::Selection::Latest),
+        Command::Check { audit: Some(_), .. } => return Ok(storage::Selection::Latest),
+        // the earlier days are added up from the day index
+        Command::Show { filter, .. } if indexed => {
+            let today = start_of_day(clock::today().naive_local());
+            // without a time span, events in the future are included as well
+            let to = get_filter_range(filter)?.map_or(today + Duration::weeks(5200), |(_, to)| to);
+            Some((today.min(to), to))
+        }
+        // the remaining time also depends on the time of the week
+        Command::Show {
+            remaining: true, ..
+        } => get_range(&None, &None, &Some("week".to_string()))?,
+        Command::Show { filter, .. } | Command::List { filter, .. } => get_filter_range(filter)?,
+        Command::Export {
+            diagram,
+            audit,
+            range,
+            ..
+        } if (diagram.is_some() || *audit) && !range.is_empty() => get_filter_range(&FilterData {
+            range: range.clone(),
+            ..FilterData::default()
+        })?,
+        Command::EmailReport { week, .. } => {
+            let (from, to) = named_range(&format!("{}-week", week), clock::today().naive_local())?;
+            Some((start_of_day(from), end_of_day(to)))
+        }
+        // the report warns about earlier weeks which are not submitted and carries over the
+        // overtime of the earlier weeks, so it needs all events
+        _ => None,
+    };
+    Ok(range.map_or(storage::Selection::All, |(from, to)| {
+        storage::Selection::Range(from.with_timezone(&Utc), to.with_timezone(&Utc))
+    }))
+}
+
+/// the indexed days of the time span of `tt show`, if it can be added up from the day index.
+/// That's the case for whole days without other filters, if no interval before today continues
+/// on the next day.
+fn indexed_days(command: &Command, index: &DayIndex) -> Result<Option<Vec<day_index::Day>>> {
+    let filter = match command {
+        Command::Show {
+            filter,
+            remaining: false,
+            by_context: false,
+            by_project: false,
+            timeline: false,
+            except,
+            ..
+        } if except.is_empty() => filter,
+        _ => return Ok(None),
+    };
+    let only_time_span = filter.range.len() <= 1
+        && filter.context.is_none()
+        && filter.project.is_none()
+        && filter.weekdays.is_none()
+        && !filter.weekend
+        && filter.approval.is_none()
+        && matches!(filter.filter.as_deref(), None | Some("all") | Some("week"));
+    if !only_time_span {
+        return Ok(None);
+    }
+    let yesterday = clock::today().naive_local().pred();
+    let (from, to) = match get_filter_range(filter)? {
+        None => (NaiveDate::from_ymd(1, 1, 1), yesterday),
+        Some((from, to)) => {
+            let (from_date, to_date) = (from.date().naive_local(), to.date().naive_local());
+            if from != start_of_day(from_date) || to != end_of_day(to_date) {
+                return Ok(None);
+            }
+            (from_date, to_date.min(yesterday))
+        }
+    };
+    Ok(index
+        .days(from, to)
+        .map(|days| days.into_iter().cloned().collect()))
+}
+
+/// checks the ticket with the command from the config. The time is tracked anyway, so failing to
+/// check it is only a warning as well.
+fn check_task(settings: &Settings, task: Option<&str>) -> Option<String> {
+    let (task, command) = (task?, settings.issues.command.as_deref()?);
+    match issues::status(command, task) {
+        Ok(status) => issues::warning(task, &status, settings.issues.user.as_deref()),
+        Err(e) => Some(format!("Could not check the ticket {}: {}", task, e)),
+    }
+}
+
+/// the command for the line which `tt quick` reads from stdin
+fn quick_command() -> Result<Command> {
+    if io::stdin().is_terminal() {
+        eprint!("tt> ");
+    }
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    let args = std::iter::once("tt".to_string()).chain(quick::args(&line)?);
+    Command::from_iter_safe(args).map_err(|e| Error::Parse(e.message).into())
+}
+
+/// the time given with `--at`, or now
+fn event_time(at: Option<String>) -> Result<DateTime<Utc>, Error> {
+    at.map_or_else(|| Ok(clock::now()), |at| parse_date_time(&at))
+}
+
+/// whether the time tracking was running at the time
+fn running_at(data: &[TrackingEvent], time: DateTime<Utc>) -> bool {
+    insertion_index(data, time)
+        .checked_sub(1)
+        .is_some_and(|index| data[index].is_start())
+}
+
+/// the time given with `--at`, which is moved to the afternoon if a time like "5:00" is
+/// typed at 17:05 and only 17:00 fits the entries, i.e. the time tracking is running then or
+/// not as needed. `ambiguous_time` in the config decides whether that happens ("snap"), is
+/// asked for ("ask") or not ("off").
+fn resolve_at(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    at: Option<String>,
+    running: bool,
+) -> Result<Option<String>, Error> {
+    let afternoon = match at.as_deref().and_then(engine::afternoon) {
+        Some(afternoon) => afternoon,
+        None => return Ok(at),
+    };
+    let typed = event_time(at.clone())?;
+    let afternoon = Local
+        .from_local_datetime(&clock::today().naive_local().and_time(afternoon))
+        .single()
+        .map(|time| time.with_timezone(&Utc));
+    let afternoon = match afternoon {
+        Some(afternoon)
+            if afternoon <= clock::now()
+                && running_at(data, typed) != running
+                && running_at(data, afternoon) == running =>
+        {
+            afternoon
+        }
+        _ => return Ok(at),
+    };
+    let snap = match settings.ambiguous_time.as_str() {
+        "snap" => {
+            if !output.is_json() {
+                eprintln!(
+                    "Using {} for \"{}\", write the time with a leading zero to mean the morning",
+                    afternoon.with_timezone(&Local).format("%H:%M"),
+                    at.as_deref().unwrap_or_default()
+                );
+            }
+            true
+        }
+        "ask" => confirm(
+            &format!(
+                "Did you mean {}?",
+                afternoon.with_timezone(&Local).format("%H:%M")
+            ),
+            true,
+        ),
+        _ => false,
+    };
+    Ok(iif!(snap, Some(format_date_time(afternoon)), at))
+}
+
+/// checks the description of a new interval against the template from the config. Returns a
+/// warning if it doesn't match, or an error if the template is strict.
+fn check_description(
+    settings: &Settings,
+    description: Option<&str>,
+) -> Result<Option<String>, Error> {
+    let template = match &settings.description.template {
+        Some(template) => template,
+        None => return Ok(None),
+    };
+    let description = description.unwrap_or_default();
+    if pattern::Pattern::new(template)?.is_match(description)? {
+        return Ok(None);
+    }
+    let message = format!(
+        "The description \"{}\" doesn't match the template \"{}\"!",
+        description, template
+    );
+    iif!(
+        settings.description.strict,
+        Err(Error::Parse(message)),
+        Ok(Some(message))
+    )
+}
+
+#[cfg(feature = "window-title")]
+fn window_title() -> Option<String> {
+    window::active_title()
+}
+
+#[cfg(not(feature = "window-title"))]
+fn window_title() -> Option<String> {
+    None
 }
 
-#[cfg(not(feature = "binary"))]
-fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent]) {
-    write_json_data(path, data, false);
+/// the metadata of a start event, with the tags of the project and the matching rules
+fn start_metadata(
+    settings: &Settings,
+    description: Option<&str>,
+    context: Option<String>,
+    project: Option<String>,
+    mut tags: Vec<String>,
+) -> Result<Metadata, Error> {
+    if let Some(project) = &project {
+        for tag in project::inherited_tags(&settings.projects, project) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    let mut meta = Metadata {
+        context: context::resolve_context(&settings.context, context),
+        tags,
+        project,
+        ..Metadata::default()
+    };
+    if let Some(description) = description {
+        rules::classify(
+            settings,
+            &rules::parse(&settings.rules)?,
+            description,
+            &mut meta,
+        )?;
+    }
+    Ok(meta)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn start_tracking(
     settings: &Settings,
     data: &mut Vec<TrackingEvent>,
     description: Option<String>,
+    context: Option<String>,
+    project: Option<String>,
+    tags: Vec<String>,
+    task: Option<String>,
+    estimate: Option<Duration>,
     at: Option<String>,
-) -> Result<()> {
-    let (should_add, last_description) = match data.last() {
-        None => (true, None),
-        Some(event) => (event.is_stop(), event.description()),
-    };
-    if should_add || at.is_some() {
-        data.push(TrackingEvent::Start(TrackingData {
-            description,
-            time: at.map_or_else(|| Ok(Local::now().into()), |at| parse_date_time(&at))?,
-        }));
-    } else if settings.auto_insert_stop && at.is_none() {
-        match (description, last_description) {
-            (Some(description), Some(last_description)) if description == last_description => {
-                eprintln!(
-                    "Timetracking with the description \"{}\" is already running!",
-                    description
-                )
+) -> Result<StartOutcome, Error> {
+    let running = data
+        .last()
+        .filter(|event| event.is_start())
+        .map(|event| (event.time(true), event.description()));
+    let mut meta = start_metadata(settings, description.as_deref(), context, project, tags)?;
+    meta.task = task;
+    meta.estimate = estimate.map(|estimate| estimate.num_seconds());
+    if at.is_none() && settings.capture_window_title {
+        meta.window_title = window_title();
+    }
+    if let Some(at) = at {
+        let time = parse_date_time(&at)?;
+        zone::record(&mut meta, time);
+        let index = insertion_index(data, time);
+        if let Some(running) = index
+            .checked_sub(1)
+            .map(|index| &data[index])
+            .filter(|event| event.is_start())
+        {
+            return Err(Error::Conflict(format!(
+                "Can't start at {}, because the time tracking was already started at {}!",
+                format_date_time(time),
+                format_date_time(running.time(true))
+            )));
+        }
+        // a start without a stop before the next start would run until the next stop
+        if let Some(next) = data.get(index).filter(|event| event.is_start()) {
+            return Err(Error::Conflict(format!(
+                "Can't start at {}, because the time tracking is started again at {}! Use `tt \
+                 record` to add a finished interval",
+                format_date_time(time),
+                format_date_time(next.time(true))
+            )));
+        }
+        data.insert(
+            index,
+            TrackingEvent::Start(TrackingData {
+                description,
+                time,
+                meta,
+            }),
+        );
+        return Ok(StartOutcome::Started { at: time });
+    }
+    match running {
+        None => {}
+        Some((since, last_description)) => {
+            let already_running = StartOutcome::AlreadyRunning {
+                since,
+                description: last_description.clone(),
+            };
+            if !settings.auto_insert_stop
+                || (description.is_some() && description == last_description)
+            {
+                return Ok(already_running);
             }
-            (description, _) => {
-                data.push(TrackingEvent::Stop(TrackingData {
+            let now = clock::now();
+            zone::record(&mut meta, now);
+            insert_sorted(
+                data,
+                TrackingEvent::Stop(TrackingData {
                     description: None,
-                    time: Local::now().into(),
-                }));
-                data.push(TrackingEvent::Start(TrackingData {
+                    time: now,
+                    meta: zone::recorded(now),
+                }),
+            );
+            insert_sorted(
+                data,
+                TrackingEvent::Start(TrackingData {
                     description,
-                    time: Local::now().into(),
-                }));
-            }
+                    time: now,
+                    meta,
+                }),
+            );
+            return Ok(StartOutcome::Restarted { at: now });
         }
-    } else {
-        eprintln!("Time tracking is already running!");
     }
+    let time = clock::now();
+    zone::record(&mut meta, time);
+    insert_sorted(
+        data,
+        TrackingEvent::Start(TrackingData {
+            description,
+            time,
+            meta,
+        }),
+    );
+    Ok(StartOutcome::Started { at: time })
+}
+
+/// the start and end of a time range like "9-17:30"
+fn record_range(range: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+    let range = time_range::parse(range).ok_or_else(|| {
+        Error::Parse(format!(
+            "Could not parse \"{}\" as a time range like \"9-17:30\"",
+            range
+        ))
+    })?;
+    let (start, stop) = range.resolve(clock::today().naive_local());
+    let local = |time: NaiveDateTime| {
+        Local
+            .from_local_datetime(&time)
+            .single()
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or_else(|| Error::Parse(format!("{} doesn't exist in the local time zone", time)))
+    };
+    Ok((local(start)?, local(stop)?))
+}
+
+/// adds a finished interval, which must not overlap with the tracked time. Returns its start and
+/// stop.
+fn record_interval(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    range: &str,
+    description: Option<String>,
+    context: Option<String>,
+    project: Option<String>,
+    tags: Vec<String>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+    let (start, stop) = record_range(range)?;
+    if stop > clock::now() {
+        return Err(Error::Conflict(format!(
+            "Can't record an interval which ends in the future at {}!",
+            format_date_time(stop)
+        )));
+    }
+    let tracked = get_intervals(data, true);
+    if let Some((tracked_start, tracked_stop)) = tracked
+        .iter()
+        .find(|(tracked_start, tracked_stop)| start < *tracked_stop && stop > *tracked_start)
+    {
+        return Err(Error::Conflict(format!(
+            "Can't record the interval, because it overlaps with the interval from {} to {}!",
+            format_date_time(*tracked_start),
+            format_date_time(*tracked_stop)
+        )));
+    }
+    let mut meta = start_metadata(settings, description.as_deref(), context, project, tags)?;
+    zone::record(&mut meta, start);
+    insert_sorted(
+        data,
+        TrackingEvent::Start(TrackingData {
+            description,
+            time: start,
+            meta,
+        }),
+    );
+    insert_sorted(
+        data,
+        TrackingEvent::Stop(TrackingData {
+            description: None,
+            time: stop,
+            meta: zone::recorded(stop),
+        }),
+    );
+    Ok((start, stop))
+}
 
-    Ok(())
+/// the interval which was stopped by the stop at the index, the work time of its day and the
+/// time which remains to reach the daily and weekly goal
+fn stop_summary<Tz: TimeZone>(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    index: usize,
+    zone: &Tz,
+) -> Result<Option<(Vec<String>, serde_json::Value)>> {
+    let at = match data.get(index) {
+        Some(stop) if stop.is_stop() => stop.time(true),
+        _ => return Ok(None),
+    };
+    let start = match data[..index].iter().rev().find(|e| e.is_start()) {
+        Some(start) => start,
+        None => return Ok(None),
+    };
+    let format = |time: Duration| {
+        let (hours, minutes, _) = split_duration(time);
+        duration_output("{hh}:{mm}", hours, minutes, 0)
+    };
+    let local = |time: DateTime<Utc>| time.with_timezone(&Local);
+    let date = local(at).date().naive_local();
+    let day = filter_events(data, Some((start_of_day(date), end_of_day(date))), &None);
+    let work_time = get_time_from_events(settings, &day, false, zone);
+    let (week_start, _) = named_range("this-week", date)?;
+    let week = filter_events(
+        data,
+        Some((start_of_day(week_start), end_of_day(date))),
+        &None,
+    );
+    let (hours, minutes, _) = split_duration(work_time);
+    let (week_hours, week_minutes, _) =
+        split_duration(get_time_from_events(settings, &week, false, zone));
+    let remaining = get_remaining_minutes(settings, "", hours, minutes)
+        .min(get_remaining_minutes(
+            settings,
+            "week",
+            week_hours,
+            week_minutes,
+        ))
+        .max(0);
+
+    let duration = format(at - start.time(true));
+    let interval = format!(
+        "{} - {} ({})",
+        local(start.time(true)).format("%H:%M"),
+        local(at).format("%H:%M"),
+        duration.formatted
+    );
+    let (work_time, remaining) = (format(work_time), format(Duration::minutes(remaining)));
+    let lines = vec![
+        match start.description() {
+            Some(description) => i18n::format("Stopped \"{}\": {}", &[&description, &interval]),
+            None => i18n::format("Stopped: {}", &[&interval]),
+        },
+        format!("{}: {}", i18n::text("Work Time"), work_time.formatted),
+        format!(
+            "{}: {}",
+            i18n::text("Remaining Work Time"),
+            remaining.formatted
+        ),
+    ];
+    let json = serde_json::json!({
+        "start": local(start.time(true)),
+        "stop": local(at),
+        "duration": duration,
+        "description": start.description(),
+        "work_time": work_time,
+        "remaining": remaining,
+    });
+    Ok(Some((lines, json)))
 }
 
 fn stop_tracking(
     data: &mut Vec<TrackingEvent>,
     description: Option<String>,
     at: Option<String>,
-) -> Result<()> {
-    let should_add = match data.last() {
-        None => true,
-        Some(event) => event.is_start(),
-    };
-    if should_add || at.is_some() {
-        data.push(TrackingEvent::Stop(TrackingData {
+) -> Result<StopOutcome, Error> {
+    let time = event_time(at)?;
+    let index = insertion_index(data, time);
+    let previous = index.checked_sub(1).map(|index| &data[index]);
+    let next = data.get(index);
+    match (previous, next) {
+        (Some(previous), Some(next)) if previous.is_start() && next.is_stop() => {
+            return Err(Error::Conflict(format!(
+                "Can't stop at {}, because the time tracking was already stopped at {}!",
+                format_date_time(time),
+                format_date_time(next.time(true))
+            )))
+        }
+        (Some(previous), _) if previous.is_start() => {}
+        (None, Some(next)) if next.is_start() => {
+            return Err(Error::Conflict(format!(
+                "Can't stop at {}, because the time tracking was started later at {}!",
+                format_date_time(time),
+                format_date_time(next.time(true))
+            )))
+        }
+        (previous, _) => {
+            return Ok(StopOutcome::NotRunning {
+                since: previous.map(|previous| previous.time(true)),
+            })
+        }
+    }
+    data.insert(
+        index,
+        TrackingEvent::Stop(TrackingData {
             description,
-            time: at.map_or_else(|| Ok(Local::now().into()), |at| parse_date_time(&at))?,
-        }))
-    } else {
-        eprintln!("Time tracking is already stopped!");
+            time,
+            meta: zone::recorded(time),
+        }),
+    );
+    Ok(StopOutcome::Stopped { at: time, index })
+}
+
+/// adds the interruption to the interval which was running at that time
+fn interrupt(
+    data: &mut [TrackingEvent],
+    note: Option<String>,
+    at: Option<String>,
+) -> Result<(), Error> {
+    let time = event_time(at)?;
+    let index = insertion_index(data, time);
+    match index.checked_sub(1).map(|index| &mut data[index]) {
+        Some(TrackingEvent::Start(TrackingData { meta, .. })) => {
+            meta.interruptions.push(Interruption { time, note });
+            Ok(())
+        }
+        _ => Err(Error::Conflict(format!(
+            "Can't record an interruption at {}, because the time tracking wasn't running!",
+            format_date_time(time)
+        ))),
     }
+}
 
-    Ok(())
+/// the index at which an event at the given time keeps the events sorted. Events at the same
+/// time stay in the order they were added.
+fn insertion_index(data: &[TrackingEvent], time: DateTime<Utc>) -> usize {
+    data.partition_point(|event| event.time(true) <= time)
 }
 
-fn continue_tracking(data: &mut Vec<TrackingEvent>) {
-    if let Some(TrackingEvent::Stop { .. }) = data.last() {
-        if let Some(TrackingEvent::Start(TrackingData { description, .. })) =
-            data.iter().rev().find(|t| t.is_start()).cloned()
-        {
-            data.push(TrackingEvent::Start(TrackingData {
+fn insert_sorted(data: &mut Vec<TrackingEvent>, event: TrackingEvent) {
+    data.insert(insertion_index(data, event.time(true)), event);
+}
+
+fn format_date_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// the fields of the continued interval which are given instead of the ones of the last interval
+#[derive(Debug, Default)]
+struct ContinueOverrides {
+    description: Option<String>,
+    project: Option<String>,
+    context: Option<String>,
+    tags: Vec<String>,
+}
+
+fn continue_tracking(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    overrides: ContinueOverrides,
+) -> ContinueOutcome {
+    match data.last() {
+        Some(TrackingEvent::Stop { .. }) => {}
+        Some(event) => {
+            return ContinueOutcome::AlreadyRunning {
+                since: event.time(true),
+            }
+        }
+        None => return ContinueOutcome::NothingToContinue,
+    }
+    match data.iter().rev().find(|t| t.is_start()).cloned() {
+        Some(TrackingEvent::Start(TrackingData {
+            description, meta, ..
+        })) => {
+            let time = clock::now();
+            let same_task = overrides.description.is_none();
+            let description = overrides.description.or(description);
+            let mut tags = iif!(overrides.tags.is_empty(), meta.tags, overrides.tags);
+            if let Some(project) = &overrides.project {
+                for tag in project::inherited_tags(&settings.projects, project) {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+            }
+            // the new interval isn't submitted or reviewed yet. It stays on the same ticket and
+            // estimate, unless it gets another description.
+            let mut meta = Metadata {
+                context: context::resolve_context(&settings.context, overrides.context),
+                tags,
+                project: overrides.project.or(meta.project),
+                task: meta.task.filter(|_| same_task),
+                estimate: meta.estimate.filter(|_| same_task),
+                ..Metadata::default()
+            };
+            if settings.capture_window_title {
+                meta.window_title = window_title();
+            }
+            zone::record(&mut meta, time);
+            insert_sorted(
+                data,
+                TrackingEvent::Start(TrackingData {
+                    description: description.clone(),
+                    time,
+                    meta,
+                }),
+            );
+            ContinueOutcome::Continued {
+                at: time,
                 description,
-                time: Local::now().into(),
-            }))
+            }
         }
-    } else {
-        eprintln!("Time tracking couldn't be continued, because there are no entries. Use the start command instead!");
+        _ => ContinueOutcome::NothingToContinue,
     }
 }
 
-fn split_duration(duration: Duration) -> (i64, i64, i64) {
-    let hours = duration.num_hours();
-    let hours_in_minutes = hours * 60;
-    let hours_in_seconds = hours_in_minutes * 60;
-    let minutes = duration.num_minutes() - hours_in_minutes;
-    let minutes_in_seconds = minutes * 60;
-    let seconds = duration.num_seconds() - hours_in_seconds - minutes_in_seconds;
-    (hours, minutes, seconds)
+fn start_of_day(date: NaiveDate) -> DateTime<Local> {
+    TimeZone::from_local_date(&Local, &date)
+        .unwrap()
+        .and_time(NaiveTime::from_hms(0, 0, 0))
+        .expect("Failed to add time from date")
 }
 
-fn filter_events(
-    data: &[TrackingEvent],
+fn end_of_day(date: NaiveDate) -> DateTime<Local> {
+    TimeZone::from_local_date(&Local, &date)
+        .unwrap()
+        .and_time(NaiveTime::from_hms(23, 59, 59))
+        .expect("Failed to add time from date")
+}
+
+/// resolves the time range selected by the filter options. There is no range for the filter
+/// "all".
+/// the first and the last day of a time span like "this-week", relative to the given day
+fn named_range(name: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), Error> {
+    if let Some((from, to)) = name.split_once("..") {
+        let parse = |date: &str| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| Error::Parse(format!("invalid date \"{}\" in range", date)))
+        };
+        return Ok((parse(from)?, parse(to)?));
+    }
+    let monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+    let first_of_month = |year, month| NaiveDate::from_ymd(year, month, 1);
+    let this_month = first_of_month(today.year(), today.month());
+    let next_month = match today.month() {
+        12 => first_of_month(today.year() + 1, 1),
+        month => first_of_month(today.year(), month + 1),
+    };
+    let last_month = (this_month - Duration::days(1)).with_day(1).unwrap();
+    Ok(match name {
+        "today" => (today, today),
+        "yesterday" => (today.pred(), today.pred()),
+        "this-week" => (monday, monday + Duration::days(6)),
+        "last-week" => (monday - Duration::days(7), monday - Duration::days(1)),
+        "this-month" => (this_month, next_month.pred()),
+        "last-month" => (last_month, this_month.pred()),
+        "this-year" => (
+            NaiveDate::from_ymd(today.year(), 1, 1),
+            NaiveDate::from_ymd(today.year(), 12, 31),
+        ),
+        "last-year" => (
+            NaiveDate::from_ymd(today.year() - 1, 1, 1),
+            NaiveDate::from_ymd(today.year() - 1, 12, 31),
+        ),
+        _ => return Err(Error::Parse(format!("unknown range \"{}\"", name))),
+    })
+}
+
+/// the time spans selected by the filter options. `None` means everything.
+fn get_filter_ranges(filter: &FilterData) -> Result<Vec<Option<timeline::Interval>>> {
+    if filter.range.is_empty() {
+        return Ok(vec![get_range(&filter.from, &filter.to, &filter.filter)?]);
+    }
+    let today = clock::today().naive_local();
+    filter
+        .range
+        .iter()
+        .map(|range| {
+            let (from, to) = named_range(range, today)?;
+            Ok(Some((start_of_day(from), end_of_day(to))))
+        })
+        .collect()
+}
+
+/// the time span covering all time spans selected by the filter options. `None` means
+/// everything.
+fn get_filter_range(filter: &FilterData) -> Result<Option<(DateTime<Local>, DateTime<Local>)>> {
+    let ranges = get_filter_ranges(filter)?;
+    Ok(ranges
+        .iter()
+        .copied()
+        .reduce(|a, b| Some((a?.0.min(b?.0), a?.1.max(b?.1))))
+        .flatten())
+}
+
+fn get_range(
     from: &Option<String>,
     to: &Option<String>,
     filter: &Option<String>,
-) -> Result<Vec<TrackingEvent>> {
-    let (filter, from, to) = match filter {
-        Some(from) if from == "week" => {
-            let now = Local::today();
-            let weekday = now.weekday();
-            let offset = weekday.num_days_from_monday();
-            let (monday_offset, sunday_offset) = (offset, 6 - offset);
-            let from = DateOrDateTime::Date(
-                (now - Duration::days(i64::from(monday_offset))).naive_local(),
-            );
-            let to = DateOrDateTime::Date(
-                (now + Duration::days(i64::from(sunday_offset))).naive_local(),
-            );
-            (None, Some(from), Some(to))
+) -> Result<Option<(DateTime<Local>, DateTime<Local>)>> {
+    match filter.as_deref() {
+        Some("all") => Ok(None),
+        Some("week") => {
+            let (from, to) = named_range("this-week", clock::today().naive_local())?;
+            Ok(Some((start_of_day(from), end_of_day(to))))
         }
-        f => {
+        _ => {
             let from = from.as_deref().map_or_else(
-                || Ok(DateOrDateTime::Date(Local::today().naive_local())),
+                || Ok(DateOrDateTime::Date(clock::today().naive_local())),
                 parse_date_or_date_time,
             )?;
 
@@ -377,173 +1990,320 @@ fn filter_events(
                         from @ DateOrDateTime::Date(..) => from,
                     })
                 })?;
-            (f.clone(), Some(from), Some(to))
+
+            let from = match from {
+                DateOrDateTime::Date(from) => start_of_day(from),
+                DateOrDateTime::DateTime(from) => {
+                    TimeZone::from_local_datetime(&Local, &from).unwrap()
+                }
+            };
+            let to = match to {
+                DateOrDateTime::Date(to) => end_of_day(to),
+                DateOrDateTime::DateTime(to) => TimeZone::from_local_datetime(&Local, &to).unwrap(),
+            };
+            Ok(Some((from, to)))
         }
-    };
+    }
+}
+
+/// checks if the description matches the filter. The filters "week" and "all" match everything,
+/// other filters have to be part of the description.
+fn matches_description(filter: &Option<String>, description: Option<&str>) -> bool {
+    match (filter.as_deref(), description) {
+        (None, _) | (Some("week"), _) | (Some("all"), _) => true,
+        (Some(filter), Some(description)) => description.contains(filter),
+        (Some(_), None) => false,
+    }
+}
+
+fn filter_events(
+    data: &[TrackingEvent],
+    range: Option<(DateTime<Local>, DateTime<Local>)>,
+    filter: &Option<String>,
+) -> Vec<TrackingEvent> {
     let data_iterator = data
         .iter()
-        .filter(|entry| {
-            iif!(
-                filter.clone().unwrap_or_default() == "all",
-                true,
-                match from {
-                    None => true,
-                    Some(DateOrDateTime::Date(from)) => {
-                        entry.time(true).timestamp_millis()
-                            >= TimeZone::from_local_date(&Local, &from)
-                                .unwrap()
-                                .and_time(NaiveTime::from_hms(0, 0, 0))
-                                .expect("Failed to add time from date")
-                                .timestamp_millis()
-                    }
-                    Some(DateOrDateTime::DateTime(from)) => {
-                        entry.time(true).timestamp_millis()
-                            >= TimeZone::from_local_datetime(&Local, &from)
-                                .unwrap()
-                                .timestamp_millis()
-                    }
-                }
-            )
-        })
-        .filter(|entry| {
-            iif!(
-                filter.clone().unwrap_or_default() == "all",
-                true,
-                match to {
-                    None => true,
-                    Some(DateOrDateTime::Date(to)) => {
-                        entry.time(true).timestamp_millis()
-                            <= TimeZone::from_local_date(&Local, &to)
-                                .unwrap()
-                                .and_time(NaiveTime::from_hms(23, 59, 59))
-                                .expect("Failed to add time from date")
-                                .timestamp_millis()
-                    }
-                    Some(DateOrDateTime::DateTime(to)) => {
-                        entry.time(true).timestamp_millis()
-                            <= TimeZone::from_local_datetime(&Local, &to)
-                                .unwrap()
-                                .timestamp_millis()
-                    }
-                }
-            )
-        })
-        .filter(|entry| match entry {
-            TrackingEvent::Start(TrackingData { description, .. })
-            | TrackingEvent::Stop(TrackingData { description, .. }) => match (&filter, description)
-            {
-                (Some(filter), Some(description)) => {
-                    filter == "all" || description.contains(filter)
-                }
-                (Some(filter), None) => filter == "all",
-                (None, _) => true,
-            },
+        .filter(|entry| match range {
+            None => true,
+            Some((from, to)) => entry.time(true) >= from && entry.time(true) <= to,
         })
+        .filter(|entry| matches_description(filter, entry.description().as_deref()))
         .skip_while(|entry| TrackingEvent::is_stop(entry));
 
-    Ok(data_iterator.cloned().collect())
+    data_iterator.cloned().collect()
 }
 
-fn get_data_as_days(data: &[TrackingEvent]) -> Vec<Vec<TrackingEvent>> {
-    if data.is_empty() {
-        return vec![];
-    }
+/// keeps only the intervals which were started in the given context. Stop events are kept if
+/// the start event they belong to was kept.
+fn filter_by_context(data: &[TrackingEvent], context: Option<&str>) -> Vec<TrackingEvent> {
+    filter_intervals(data, |start| start.context().as_deref() == context)
+}
 
-    let mut current_day = data
-        .first()
-        .expect("Tracking event is empty")
-        .time(true)
-        .date();
-    let mut result = Vec::new();
-    let mut current = Vec::new();
-    for d in data {
-        let date = d.time(true).date();
-        if current_day == date {
-            current.push(d.clone());
-        } else {
-            result.push(current);
-            current = Vec::new();
-            current.push(d.clone());
-            current_day = date;
-        }
-    }
-    if !current.is_empty() {
-        result.push(current);
-    }
-    return result;
+/// keeps only the intervals whose start event matches. Stop events are kept if the start event
+/// they belong to was kept.
+fn filter_intervals<F: Fn(&TrackingEvent) -> bool>(
+    data: &[TrackingEvent],
+    matches: F,
+) -> Vec<TrackingEvent> {
+    let mut keep = false;
+    data.iter()
+        .filter(|event| {
+            if event.is_start() {
+                keep = matches(event);
+            }
+            keep
+        })
+        .cloned()
+        .collect()
 }
 
-const CHECKED_ADD_DURATION_ERROR: &str = "couldn't add up durations";
+/// which events belong to the intervals selected by the filter. The stop of a selected start is
+/// selected too, so the commands which change the selection change whole intervals.
+fn select_intervals(data: &[TrackingEvent], filter: &FilterData) -> Result<Vec<bool>> {
+    let mut starts = apply_filter(data, filter)?
+        .into_iter()
+        .filter(TrackingEvent::is_start)
+        .peekable();
+    let mut selected = false;
+    Ok(data
+        .iter()
+        .map(|event| {
+            if event.is_start() {
+                selected = starts.peek() == Some(event);
+                if selected {
+                    starts.next();
+                }
+            }
+            selected
+        })
+        .collect())
+}
 
-fn get_time_from_day(
-    settings: &Settings,
+fn parse_weekdays(s: &str) -> Result<Vec<Weekday>, Error> {
+    let parse = |day: &str| {
+        day.trim()
+            .parse::<Weekday>()
+            .map_err(|_| Error::Parse(format!("invalid weekday \"{}\"", day)))
+    };
+    let mut weekdays = Vec::new();
+    for part in s.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (mut day, last) = (parse(first)?, parse(last)?);
+                while day != last {
+                    weekdays.push(day);
+                    day = day.succ();
+                }
+                weekdays.push(last);
+            }
+            None => weekdays.push(parse(part)?),
+        }
+    }
+    Ok(weekdays)
+}
+
+fn filter_by_weekdays(data: &[TrackingEvent], weekdays: &[Weekday]) -> Vec<TrackingEvent> {
+    filter_intervals(data, |start| {
+        weekdays.contains(&start.time(true).with_timezone(&Local).weekday())
+    })
+}
+
+fn apply_filter(data: &[TrackingEvent], filter_data: &FilterData) -> Result<Vec<TrackingEvent>> {
+    let ranges = get_filter_ranges(filter_data)?;
+    let FilterData {
+        filter,
+        context,
+        project,
+        weekdays,
+        weekend,
+        approval,
+        ..
+    } = filter_data;
+    let mut filtered_data = Vec::new();
+    for range in &ranges {
+        filtered_data.extend(filter_events(data, *range, filter));
+    }
+    // the time spans may overlap
+    if ranges.len() > 1 {
+        filtered_data.sort_by_key(|event| event.time(true));
+        filtered_data.dedup();
+    }
+    let filtered_data = match context {
+        Some(context) => filter_by_context(&filtered_data, Some(context)),
+        None => filtered_data,
+    };
+    let filtered_data = match project {
+        Some(parent) => filter_intervals(&filtered_data, |start| {
+            start
+                .project()
+                .is_some_and(|project| project::is_within(&project, parent))
+        }),
+        None => filtered_data,
+    };
+    let filtered_data = match approval.as_deref() {
+        Some(approval) => filter_intervals(&filtered_data, |start| {
+            let state = start.meta().approval;
+            match approval {
+                "approved" => state == Some(Approval::Approved),
+                "rejected" => state == Some(Approval::Rejected),
+                _ => state.is_none(),
+            }
+        }),
+        None => filtered_data,
+    };
+    let weekdays = match weekdays {
+        Some(weekdays) => Some(parse_weekdays(weekdays)?),
+        None if *weekend => Some(vec![Weekday::Sat, Weekday::Sun]),
+        None => None,
+    };
+    let filtered_data = match weekdays {
+        Some(weekdays) => filter_by_weekdays(&filtered_data, &weekdays),
+        None => filtered_data,
+    };
+    if filter_data.except.is_empty() {
+        return Ok(filtered_data);
+    }
+    let windows = filter_data
+        .except
+        .iter()
+        .map(|window| except::parse(window))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(except::cut(&filtered_data, &windows, &Local, clock::now()))
+}
+
+/// splits the events into the days of the zone. The commands pass the local zone, like the dates
+/// they are shown with
+fn get_data_as_days<Tz: TimeZone>(data: &[TrackingEvent], zone: &Tz) -> Vec<Vec<TrackingEvent>> {
+    engine::days(data, zone)
+}
+
+const CHECKED_ADD_DURATION_ERROR: &str = "couldn't add up durations";
+
+/// pairs start and stop events into intervals. A running interval ends now.
+fn get_intervals(
     data: &[TrackingEvent],
     include_seconds: bool,
-) -> Duration {
-    let mut data_iterator = data.iter();
-    let mut work_day = Duration::zero();
-    let mut first = None;
-    let mut last = None;
-    loop {
-        let start = data_iterator.find(|e| e.is_start());
-        let stop = data_iterator.find(|e| e.is_stop());
-        match (start, stop) {
-            (Some(start), Some(stop)) => {
-                if let None = first {
-                    first = Some(start.time(include_seconds));
-                }
-                last = Some(stop.time(include_seconds));
-                let duration = stop.time(include_seconds) - start.time(include_seconds);
-                work_day = work_day
-                    .checked_add(&duration)
-                    .expect(CHECKED_ADD_DURATION_ERROR);
-            }
-            (Some(start), None) => {
-                if let None = first {
-                    first = Some(start.time(include_seconds));
-                }
-                let now = if include_seconds {
-                    Utc::now()
-                } else {
-                    Utc::now().with_second(0).unwrap()
-                };
-                last = Some(now);
-                let duration = now - start.time(include_seconds);
-                work_day = work_day
-                    .checked_add(&duration)
-                    .expect(CHECKED_ADD_DURATION_ERROR);
-                break;
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    engine::intervals(data, include_seconds, clock::now())
+}
+
+/// the lines and the json of `tt list`
+fn list_lines(
+    data: &[TrackingEvent],
+    event_local: bool,
+    cumulative: bool,
+) -> (Vec<String>, Vec<serde_json::Value>) {
+    let mut lines = get_human_readable_in(data, event_local);
+    let mut events = data
+        .iter()
+        .map(|event| serde_json::json!(output::EventOutput::from(event)))
+        .collect::<Vec<_>>();
+    if cumulative {
+        let totals = cumulative_times(data);
+        for ((line, json), total) in lines.iter_mut().zip(&mut events).zip(totals) {
+            if let Some(total) = total {
+                let (hours, minutes, seconds) = split_duration(total);
+                let total = duration_output("{hh}:{mm}:{ss}", hours, minutes, seconds);
+                line.push_str(&format!("  (day {})", total.formatted));
+                json["cumulative"] = serde_json::json!(total);
             }
-            (_, _) => break,
         }
     }
-    if settings.min_daily_break > 0 {
-        let now = Utc::now();
-        let total = last.unwrap_or(now) - first.unwrap_or(now);
-        let pause = total - work_day;
-        let min_break_duration = Duration::minutes(i64::from(settings.min_daily_break));
-        if pause > Duration::zero() && pause < min_break_duration {
-            let difference = min_break_duration - pause;
-            work_day = work_day - difference;
+    (lines, events)
+}
+
+/// the length and modification time of the data files, to notice when they are changed
+fn file_states(files: &[PathBuf]) -> Vec<Option<(u64, std::time::SystemTime)>> {
+    files
+        .iter()
+        .map(|file| {
+            let metadata = std::fs::metadata(file).ok()?;
+            Some((metadata.len(), metadata.modified().ok()?))
+        })
+        .collect()
+}
+
+/// the tracked time of the day up to the end of each interval, at the index of its stop event
+fn cumulative_times(data: &[TrackingEvent]) -> Vec<Option<Duration>> {
+    let mut totals = Vec::with_capacity(data.len());
+    let mut day = None;
+    let mut total = Duration::zero();
+    let mut start = None;
+    for event in data {
+        let time = event.time(true);
+        let date = time.with_timezone(&Local).date();
+        match (event.is_start(), start) {
+            (true, _) => {
+                if day != Some(date) {
+                    day = Some(date);
+                    total = Duration::zero();
+                }
+                start = Some(time);
+                totals.push(None);
+            }
+            (false, Some(started)) => {
+                total = total + (time - started);
+                start = None;
+                totals.push(Some(total));
+            }
+            (false, None) => totals.push(None),
         }
     }
-    work_day.max(Duration::zero())
+    totals
 }
 
-fn get_time_from_events(
+fn get_time_from_day(
     settings: &Settings,
     data: &[TrackingEvent],
     include_seconds: bool,
 ) -> Duration {
-    let days = get_data_as_days(data);
-    let mut time = Duration::zero();
-    for day in days {
-        let time_for_day = get_time_from_day(&settings, &day, include_seconds);
-        time = time
-            .checked_add(&time_for_day)
-            .expect(CHECKED_ADD_DURATION_ERROR);
-    }
-    time
+    engine::work_time_of_day(
+        &get_intervals(data, include_seconds),
+        min_daily_break(settings),
+        clock::now(),
+    )
+}
+
+/// the work time of a day from the day index, like `get_time_from_day`
+fn get_time_from_indexed_day(
+    settings: &Settings,
+    day: &day_index::Day,
+    include_seconds: bool,
+) -> Duration {
+    let time = |time: DateTime<Utc>| {
+        iif!(
+            include_seconds,
+            time,
+            time.with_second(0).expect("could not set seconds to zero")
+        )
+    };
+    let tracked = iif!(include_seconds, day.tracked, day.tracked_minutes);
+    engine::with_min_daily_break(
+        Duration::seconds(tracked),
+        min_daily_break(settings),
+        Some(time(day.first)),
+        Some(time(day.last)),
+        clock::now(),
+    )
+}
+
+fn min_daily_break(settings: &Settings) -> Duration {
+    Duration::minutes(i64::from(settings.min_daily_break))
+}
+
+fn get_time_from_events<Tz: TimeZone>(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    include_seconds: bool,
+    zone: &Tz,
+) -> Duration {
+    engine::work_time(
+        data,
+        zone,
+        min_daily_break(settings),
+        include_seconds,
+        clock::now(),
+    )
 }
 
 fn get_remaining_minutes(settings: &Settings, filter: &str, hours: i64, minutes: i64) -> i64 {
@@ -557,34 +2317,275 @@ fn get_remaining_minutes(settings: &Settings, filter: &str, hours: i64, minutes:
     required - total
 }
 
-fn show(
+fn format_time(format: &str, hours: i64, minutes: i64, seconds: i64) -> String {
+    format
+        .replace("{hh}", &format!("{:02}", hours))
+        .replace("{mm}", &format!("{:02}", minutes))
+        .replace("{ss}", &format!("{:02}", seconds))
+        .replace("{h}", &format!("{}", hours))
+        .replace("{m}", &format!("{}", minutes))
+        .replace("{s}", &format!("{}", seconds))
+}
+
+fn duration_output(format: &str, hours: i64, minutes: i64, seconds: i64) -> DurationOutput {
+    DurationOutput {
+        hours,
+        minutes,
+        seconds,
+        formatted: format_time(format, hours, minutes, seconds),
+    }
+}
+
+/// how `show` splits up the work time
+#[derive(Debug, Clone, Copy)]
+enum Grouping {
+    Context,
+    /// per project, optionally adding up sub projects below the given level
+    Project(Option<usize>),
+}
+
+impl Grouping {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Context => "context",
+            Self::Project(_) => "project",
+        }
+    }
+
+    fn key(&self, start: &TrackingEvent) -> Option<String> {
+        match self {
+            Self::Context => start.context(),
+            Self::Project(_) => start.project().map(|project| self.project_key(&project)),
+        }
+    }
+
+    fn project_key(&self, project: &str) -> String {
+        match self {
+            Self::Project(Some(depth)) => project::at_depth(project, *depth).to_string(),
+            _ => project.to_string(),
+        }
+    }
+
+    /// whether the shares of the interval are grouped instead of the interval
+    fn splits(&self, start: &TrackingEvent) -> bool {
+        matches!(self, Self::Project(_)) && !start.meta().allocation.is_empty()
+    }
+}
+
+/// the events and the work time of each group, sorted by the group. The shares of an allocated
+/// interval count for their projects, its events aren't part of any group.
+fn group_work_times<Tz: TimeZone>(
     settings: &Settings,
     data: &[TrackingEvent],
-    filter: &FilterData,
+    grouping: &Grouping,
+    include_seconds: bool,
+    zone: &Tz,
+) -> Vec<(Option<String>, Vec<TrackingEvent>, Duration)> {
+    let mut shares = Vec::new();
+    for (index, start) in data.iter().enumerate() {
+        if !start.is_start() || !grouping.splits(start) {
+            continue;
+        }
+        let stop = data[index + 1..]
+            .iter()
+            .find(|event| event.is_stop())
+            .map_or_else(clock::now, |stop| stop.time(include_seconds));
+        let time = stop - start.time(include_seconds);
+        for (project, time) in allocation::split(time, &start.meta().allocation) {
+            shares.push((Some(grouping.project_key(project)), time));
+        }
+    }
+    let mut groups = data
+        .iter()
+        .filter(|event| event.is_start() && !grouping.splits(event))
+        .map(|start| grouping.key(start))
+        .chain(shares.iter().map(|(group, _)| group.clone()))
+        .collect::<Vec<_>>();
+    groups.sort();
+    groups.dedup();
+    groups
+        .into_iter()
+        .map(|group| {
+            let events = filter_intervals(data, |start| {
+                !grouping.splits(start) && grouping.key(start) == group
+            });
+            let work_time = shares.iter().filter(|(share, _)| *share == group).fold(
+                get_time_from_events(settings, &events, include_seconds, zone),
+                |total, (_, time)| total + *time,
+            );
+            (group, events, work_time)
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_grouped<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    grouping: Grouping,
+    format: &str,
+    include_seconds: bool,
+    plain: bool,
+    zone: &Tz,
+) -> Result<()> {
+    let mut lines = Vec::new();
+    let mut values = Vec::new();
+    for (group, events, work_time) in
+        group_work_times(settings, data, &grouping, include_seconds, zone)
+    {
+        let (hours, minutes, seconds) = split_duration(work_time);
+        let work_time = duration_output(format, hours, minutes, iif!(include_seconds, seconds, 0));
+        let billed = billing::billed(&settings.projects, &events, clock::now())?
+            .into_iter()
+            .flatten()
+            .reduce(|total, billed| total + billed)
+            .map(|billed| {
+                let (hours, minutes, seconds) = split_duration(billed);
+                duration_output(format, hours, minutes, iif!(include_seconds, seconds, 0))
+            });
+        let label = group
+            .clone()
+            .unwrap_or_else(|| i18n::format("no {}", &[&i18n::text(grouping.name())]));
+        let mut line = if plain {
+            format!("{}: {}", label, work_time.formatted)
+        } else {
+            i18n::format("Work Time ({}): {}", &[&label, &work_time.formatted])
+        };
+        if let Some(billed) = &billed {
+            line.push_str(&i18n::format(" (billed {})", &[&billed.formatted]));
+        }
+        lines.push(line);
+        values.push(serde_json::json!({
+            grouping.name(): group,
+            "work_time": work_time,
+            "billed": billed,
+        }));
+    }
+    output.print_lines(&lines, &values);
+    Ok(())
+}
+
+fn show_timeline<Tz: TimeZone>(
+    data: &[TrackingEvent],
+    include_seconds: bool,
+    plain: bool,
+    zone: &Tz,
+) {
+    for day in get_data_as_days(data, zone) {
+        let intervals = get_intervals(&day, include_seconds)
+            .into_iter()
+            .map(|(start, stop)| (start.with_timezone(&Local), stop.with_timezone(&Local)))
+            .collect::<Vec<_>>();
+        if plain {
+            for (start, stop) in &intervals {
+                println!(
+                    "interval: {} to {}",
+                    start.format("%Y-%m-%d %H:%M"),
+                    stop.format("%H:%M")
+                );
+            }
+            continue;
+        }
+        if let Some((start, _)) = intervals.first() {
+            println!("{}", start.format("%Y-%m-%d"));
+            for line in timeline::render(&intervals) {
+                println!("{}", line);
+            }
+            println!();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter_data: &FilterData,
     format: Option<String>,
     include_seconds: bool,
     plain: bool,
     remaining: bool,
+    grouping: Option<Grouping>,
+    timeline: bool,
+    indexed: Option<Vec<day_index::Day>>,
+    zone: &Tz,
 ) -> Result<()> {
-    let FilterData { from, to, filter } = filter;
-    let filtered_data = filter_events(data, &from, &to, &filter)?;
-    let work_time = get_time_from_events(&settings, &filtered_data, include_seconds);
+    let FilterData {
+        from,
+        to,
+        range,
+        filter,
+        ..
+    } = filter_data;
+    let filtered_data = apply_filter(data, filter_data)?;
+    if timeline && !output.is_json() {
+        show_timeline(&filtered_data, include_seconds, output.is_plain(), zone);
+    }
+    let format = format.unwrap_or_else(|| "{hh}:{mm}:{ss}".to_string());
+    if let Some(grouping) = grouping {
+        if remaining {
+            eprintln!(
+                "{}",
+                i18n::format(
+                    "Remaining can't be combined with showing the work time per {}",
+                    &[&grouping.name()]
+                )
+            );
+        } else {
+            show_grouped(
+                output,
+                settings,
+                &filtered_data,
+                grouping,
+                &format,
+                include_seconds,
+                plain,
+                zone,
+            )?;
+        }
+        return Ok(());
+    }
+    let work_time = match indexed {
+        // the data may contain earlier events than today's, which are part of the index
+        Some(days) => {
+            let today = start_of_day(clock::today().naive_local());
+            let since_today = filtered_data.partition_point(|event| event.time(true) < today);
+            days.iter()
+                .map(|day| get_time_from_indexed_day(settings, day, include_seconds))
+                .fold(
+                    get_time_from_events(
+                        settings,
+                        &filtered_data[since_today..],
+                        include_seconds,
+                        zone,
+                    ),
+                    |total, day| total + day,
+                )
+        }
+        None => get_time_from_events(settings, &filtered_data, include_seconds, zone),
+    };
     let (mut hours, mut minutes, mut seconds) = split_duration(work_time);
 
     let filter = filter.clone().unwrap_or_default();
     if remaining {
-        if (filter == "week" || filter.is_empty()) && from.is_none() && to.is_none() {
+        if (filter == "week" || filter.is_empty())
+            && from.is_none()
+            && to.is_none()
+            && range.is_empty()
+        {
             seconds = 0;
-            let mut remaining_minutes = get_remaining_minutes(&settings, &filter, hours, minutes);
+            let mut remaining_minutes = get_remaining_minutes(settings, &filter, hours, minutes);
 
             if filter != "week" {
-                let filtered_data_week =
-                    filter_events(&data, &None, &None, &Some("week".to_string()))?;
+                let week = get_range(&None, &None, &Some("week".to_string()))?;
+                let filtered_data_week = filter_events(data, week, &None);
                 let week_work_time =
-                    get_time_from_events(&settings, &filtered_data_week, include_seconds);
+                    get_time_from_events(settings, &filtered_data_week, include_seconds, zone);
                 let (week_hours, week_minutes, _) = split_duration(week_work_time);
                 let remaining_minutes_week =
-                    get_remaining_minutes(&settings, "week", week_hours, week_minutes);
+                    get_remaining_minutes(settings, "week", week_hours, week_minutes);
                 remaining_minutes = remaining_minutes.min(remaining_minutes_week);
             }
 
@@ -593,104 +2594,1716 @@ fn show(
             hours = remaining_minutes / 60;
             minutes = remaining_minutes - (hours * 60);
         } else {
-            eprintln!("Remaining only works when \"from\" and \"to\" are not set and with no filter or filter \"week\"");
+            eprintln!(
+                "{}",
+                i18n::text(
+                    "Remaining only works when \"from\" and \"to\" are not set and with no \
+                     filter or filter \"week\""
+                )
+            );
             return Ok(());
         }
     }
     let seconds_final = if include_seconds { seconds } else { 0 };
-    let format = format.unwrap_or_else(|| "{hh}:{mm}:{ss}".to_string());
-    let time = format
-        .replace("{hh}", &format!("{:02}", hours))
-        .replace("{mm}", &format!("{:02}", minutes))
-        .replace("{ss}", &format!("{:02}", seconds_final))
-        .replace("{h}", &format!("{}", hours))
-        .replace("{m}", &format!("{}", minutes))
-        .replace("{s}", &format!("{}", seconds_final));
-    if plain {
-        println!("{}", time);
+    let work_time = duration_output(&format, hours, minutes, seconds_final);
+    let text = if plain {
+        work_time.formatted.clone()
     } else if remaining {
-        println!("Remaining Work Time: {}", time);
+        format!(
+            "{}: {}",
+            i18n::text("Remaining Work Time"),
+            work_time.formatted
+        )
     } else {
-        println!("Work Time: {}", time);
+        format!("{}: {}", i18n::text("Work Time"), work_time.formatted)
+    };
+    if range.len() > 1 {
+        // the work time of each time span, followed by the total
+        let mut lines = Vec::new();
+        let mut ranges = Vec::new();
+        for range in range {
+            let filter = FilterData {
+                range: vec![range.clone()],
+                ..filter_data.clone()
+            };
+            let filtered_data = apply_filter(data, &filter)?;
+            let work_time = get_time_from_events(settings, &filtered_data, include_seconds, zone);
+            let (hours, minutes, seconds) = split_duration(work_time);
+            let work_time =
+                duration_output(&format, hours, minutes, iif!(include_seconds, seconds, 0));
+            if !plain {
+                lines.push(format!("{}: {}", range, work_time.formatted));
+            }
+            ranges.push(serde_json::json!({ "range": range, "work_time": work_time }));
+        }
+        lines.push(text);
+        output.print_lines(
+            &lines,
+            &serde_json::json!({
+                "remaining": remaining,
+                "work_time": work_time,
+                "ranges": ranges,
+            }),
+        );
+        return Ok(());
     }
+    output.print(
+        text,
+        &serde_json::json!({ "remaining": remaining, "work_time": work_time }),
+    );
 
     Ok(())
 }
 
-fn cleanup(data: &[TrackingEvent]) -> Vec<TrackingEvent> {
-    let mut cleaned = Vec::with_capacity(data.len());
+fn tracked_time(intervals: &[(DateTime<Utc>, DateTime<Utc>)]) -> Duration {
+    intervals
+        .iter()
+        .fold(Duration::zero(), |total, (start, stop)| {
+            total + (*stop - *start)
+        })
+}
 
-    let mut data_iter = data.iter();
-    let mut conflicting = Vec::new();
+fn stats<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    with_streaks: bool,
+    with_switches: bool,
+    zone: &Tz,
+) -> Result<()> {
+    let days = get_data_as_days(data, zone)
+        .iter()
+        .map(|day| {
+            let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+            (date, get_time_from_day(settings, day, false))
+        })
+        .filter(|(_, time)| *time > Duration::zero())
+        .collect::<Vec<_>>();
+    let total = days
+        .iter()
+        .fold(Duration::zero(), |total, (_, time)| total + *time);
+    let average = total / (days.len().max(1) as i32);
+    let format = |duration: Duration| {
+        let (hours, minutes, _) = split_duration(duration);
+        duration_output("{hh}:{mm}", hours, minutes, 0)
+    };
+    let (total, average) = (format(total), format(average));
+    let mut lines = vec![
+        format!("{}: {}", i18n::text("Tracked Days"), days.len()),
+        format!("{}: {}", i18n::text("Total"), total.formatted),
+        format!("{}: {}", i18n::text("Average per Day"), average.formatted),
+    ];
+    let mut json = serde_json::json!({
+        "days": days.len(),
+        "total": total,
+        "average": average,
+    });
+    if with_streaks {
+        let work_days = work_days(settings)?;
+        let today = clock::local_now().date().naive_local();
+        let goal = &settings.time_goal.daily;
+        let goal =
+            Duration::hours(i64::from(goal.hours)) + Duration::minutes(i64::from(goal.minutes));
+        let tracked = days.iter().map(|(date, _)| *date).collect::<Vec<_>>();
+        let reached = days
+            .iter()
+            .filter(|(_, time)| *time >= goal)
+            .map(|(date, _)| *date)
+            .collect::<Vec<_>>();
+        let tracking = streaks::streaks(&tracked, today, &work_days);
+        let goal = streaks::streaks(&reached, today, &work_days);
+        lines.extend(vec![
+            format!(
+                "{}: {}",
+                i18n::text("Current Streak (Days)"),
+                tracking.current
+            ),
+            format!(
+                "{}: {}",
+                i18n::text("Longest Streak (Days)"),
+                tracking.longest
+            ),
+            format!(
+                "{}: {}",
+                i18n::text("Days Reaching the Daily Goal"),
+                reached.len()
+            ),
+            format!(
+                "{}: {}",
+                i18n::text("Current Goal Streak (Days)"),
+                goal.current
+            ),
+            format!(
+                "{}: {}",
+                i18n::text("Longest Goal Streak (Days)"),
+                goal.longest
+            ),
+        ]);
+        json["streak"] = serde_json::json!(tracking);
+        json["goal_days"] = serde_json::json!(reached.len());
+        json["goal_streak"] = serde_json::json!(goal);
+    }
+    if with_switches {
+        let mut switch_days = Vec::new();
+        let mut all_blocks = Vec::new();
+        for day in get_data_as_days(data, zone) {
+            let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+            let intervals = get_intervals(&day, false)
+                .into_iter()
+                .map(|(start, stop)| {
+                    let event = day.iter().find(|e| e.is_start() && e.time(false) == start);
+                    let key = event.map(|event| (event.description(), event.project()));
+                    (key, stop - start)
+                })
+                .collect::<Vec<_>>();
+            let blocks = switches::focus_blocks(&intervals);
+            let switches = blocks.len().saturating_sub(1);
+            let average = format(switches::average(&blocks));
+            lines.push(format!(
+                "{}  {}",
+                date,
+                i18n::format(
+                    "{} switches, focus blocks of {} on average",
+                    &[&switches, &average.formatted]
+                )
+            ));
+            switch_days.push(serde_json::json!({
+                "date": date,
+                "switches": switches,
+                "focus_blocks": blocks.len(),
+                "average_focus_block": average,
+            }));
+            all_blocks.extend(blocks);
+        }
+        // each day starts with a block which doesn't follow a switch
+        let per_day = all_blocks.len().saturating_sub(switch_days.len()) as f64
+            / switch_days.len().max(1) as f64;
+        let average = format(switches::average(&all_blocks));
+        lines.push(format!(
+            "{}: {:.1}",
+            i18n::text("Context Switches per Day"),
+            per_day
+        ));
+        lines.push(format!(
+            "{}: {}",
+            i18n::text("Average Focus Block"),
+            average.formatted
+        ));
+        json["switches"] = serde_json::json!({
+            "days": switch_days,
+            "per_day": per_day,
+            "average_focus_block": average,
+        });
+    }
+    output.print_lines(&lines, &json);
+    Ok(())
+}
 
-    let mut is_start = None;
+fn predict<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    zone: &Tz,
+) -> Result<()> {
+    let now = clock::local_now();
+    let today = now.date().naive_local();
+    let history = get_data_as_days(data, zone)
+        .iter()
+        .filter_map(|day| {
+            let intervals = get_intervals(day, false);
+            let (first, _) = intervals.first()?;
+            let (_, last) = intervals.last()?;
+            let arrival = first.with_timezone(&Local);
+            (arrival.date().naive_local() < today).then(|| predict::Day {
+                arrival: arrival.time(),
+                pause: (*last - *first) - tracked_time(&intervals),
+            })
+        })
+        .collect::<Vec<_>>();
+    let typical = predict::typical_day(&history);
 
-    let mut all_conflicting = Vec::new();
+    // the raw tracked time is used, as the minimum break is planned below
+    let intervals = get_intervals(
+        &filter_events(data, get_range(&None, &None, &None)?, &None),
+        false,
+    );
+    let worked = tracked_time(&intervals);
+    let (week_start, _) = named_range("this-week", today)?;
+    let week_before_today = filter_events(
+        data,
+        Some((start_of_day(week_start), start_of_day(today))),
+        &None,
+    );
+    let to_minutes = |time: &settings::Time| i64::from(time.hours) * 60 + i64::from(time.minutes);
+    let remaining = Duration::minutes(to_minutes(&settings.time_goal.daily).min(
+        to_minutes(&settings.time_goal.weekly)
+            - get_time_from_events(settings, &week_before_today, false, zone).num_minutes(),
+    )) - worked;
+    let remaining = remaining.max(Duration::zero());
 
-    while let Some(e) = data_iter.next() {
-        match is_start {
+    let (arrival, pause_taken) = match (intervals.first(), intervals.last()) {
+        (Some((first, _)), Some((_, last))) => {
+            (first.with_timezone(&Local), (*last - *first) - worked)
+        }
+        _ => match typical {
+            Some(typical) => (
+                start_of_day(today) + (typical.arrival - NaiveTime::from_hms(0, 0, 0)),
+                Duration::zero(),
+            ),
             None => {
-                is_start = Some(e.is_start());
-                cleaned.push(e);
-            }
-            Some(true) => {
-                if e.is_start() {
-                    if conflicting.is_empty() {
-                        if let Some(last_cleaned_e) = cleaned.pop() {
-                            conflicting.push(last_cleaned_e);
-                        }
-                    }
-                    conflicting.push(e);
-                } else {
-                    if !conflicting.is_empty() {
-                        all_conflicting.push(conflicting);
-                        conflicting = Vec::new();
-                    }
-                    cleaned.push(e);
-                    is_start.replace(false);
-                }
-            }
-            Some(false) => {
-                if e.is_stop() {
-                    if conflicting.is_empty() {
-                        if let Some(last_cleaned_e) = cleaned.pop() {
-                            conflicting.push(last_cleaned_e);
-                        }
-                    }
-                    conflicting.push(e);
-                } else {
-                    if !conflicting.is_empty() {
-                        all_conflicting.push(conflicting);
-                        conflicting = Vec::new();
-                    }
-                    cleaned.push(e);
-                    is_start.replace(true);
-                }
+                eprintln!("There is no history to predict the arrival time from!");
+                return Ok(());
             }
+        },
+    };
+    let pause = typical
+        .map_or_else(Duration::zero, |typical| typical.pause)
+        .max(Duration::minutes(i64::from(settings.min_daily_break)));
+    let pause_left = iif!(
+        remaining > Duration::zero(),
+        pause - pause_taken,
+        Duration::zero()
+    )
+    .max(Duration::zero());
+    // assumes that the work continues now, if it has been interrupted
+    let leave = arrival.max(now) + remaining + pause_left;
+
+    let format = |duration: Duration| {
+        let (hours, minutes, _) = split_duration(duration);
+        duration_output("{hh}:{mm}", hours, minutes, 0)
+    };
+    let (remaining, pause_left) = (format(remaining), format(pause_left));
+    output.print_lines(
+        &[
+            format!("Arrival: {}", arrival.format("%H:%M")),
+            format!("Remaining Work Time: {}", remaining.formatted),
+            format!("Remaining Break: {}", pause_left.formatted),
+            format!("Leave: {}", leave.format("%H:%M")),
+        ],
+        &serde_json::json!({
+            "arrival": arrival,
+            "remaining": remaining,
+            "break": pause_left,
+            "leave": leave,
+        }),
+    );
+    Ok(())
+}
+
+/// the tracked time per task next to its estimate. The task is the ticket or the description.
+/// the placeholders of `tt record --planned` per day, with the planned and the tracked time
+fn report_plan_view(
+    output: &Output,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    placeholders: &[planned::Placeholder],
+) -> Result<()> {
+    let format = |time: Duration| {
+        let (hours, minutes, _) = split_duration(time);
+        duration_output("{hh}:{mm}", hours, minutes, 0)
+    };
+    let ranges = get_filter_ranges(filter)?;
+    let in_range = |time: DateTime<Utc>| {
+        ranges
+            .iter()
+            .any(|range| range.is_none_or(|(from, to)| time >= from && time <= to))
+    };
+    let day = |time: DateTime<Utc>| time.with_timezone(&Local).date().naive_local();
+    let mut days = std::collections::BTreeMap::new();
+    for placeholder in placeholders.iter().filter(|p| in_range(p.start)) {
+        days.entry(day(placeholder.start))
+            .or_insert_with(|| (Vec::new(), Duration::zero()))
+            .0
+            .push(placeholder);
+    }
+    for (start, stop) in get_intervals(&apply_filter(data, filter)?, false) {
+        let (_, tracked) = days
+            .entry(day(start))
+            .or_insert_with(|| (Vec::new(), Duration::zero()));
+        *tracked = *tracked + (stop - start);
+    }
+    let mut lines = Vec::new();
+    let mut json = Vec::new();
+    for (date, (placeholders, tracked)) in days {
+        let planned = placeholders
+            .iter()
+            .fold(Duration::zero(), |total, p| total + (p.stop - p.start));
+        lines.push(format!(
+            "{}  planned {}, tracked {}",
+            date,
+            format(planned).formatted,
+            format(tracked).formatted
+        ));
+        for placeholder in &placeholders {
+            let project = placeholder
+                .project
+                .as_ref()
+                .map_or(String::new(), |project| format!(" ({})", project));
+            lines.push(format!(
+                "  {} - {}  {}{}",
+                placeholder.start.with_timezone(&Local).format("%H:%M"),
+                placeholder.stop.with_timezone(&Local).format("%H:%M"),
+                placeholder
+                    .description
+                    .as_deref()
+                    .unwrap_or("no description"),
+                project
+            ));
         }
+        json.push(serde_json::json!({
+            "date": date,
+            "planned": format(planned),
+            "tracked": format(tracked),
+            "placeholders": placeholders,
+        }));
     }
+    if lines.is_empty() {
+        lines.push("There are no planned intervals in this time span!".to_string());
+    }
+    output.print_lines(&lines, &json);
+    Ok(())
+}
 
-    for mut conflicting in all_conflicting {
-        let event_type = iif!(
-            conflicting
-                .first()
-                .expect("Nothing first tracking event founded")
-                .is_start(),
-            "start",
-            "stop"
-        );
-        println!("Repeated {} events found:", event_type);
-        for (i, event) in conflicting.iter().enumerate() {
-            println!(
-                "({}) {}",
-                i,
-                to_human_readable(
-                    &format!("S{}", &event_type[1..]),
-                    &event.time(true).with_timezone(&Local),
-                    event.description()
-                )
-            );
-        }
+fn report_estimates(output: &Output, data: &[TrackingEvent], filter: &FilterData) -> Result<()> {
+    #[derive(Serialize)]
+    struct TaskOutput {
+        name: String,
+        estimate: DurationOutput,
+        actual: DurationOutput,
+        deviation_percent: Option<i64>,
+    }
+
+    let format = |time: Duration| {
+        let (hours, minutes, _) = split_duration(time);
+        duration_output("{hh}:{mm}", hours, minutes, 0)
+    };
+    let data = apply_filter(data, filter)?;
+    let mut tasks = Vec::new();
+    for (start, stop) in get_intervals(&data, false) {
+        let event = data.iter().find(|e| e.is_start() && e.time(false) == start);
+        let name = event
+            .and_then(|event| event.meta().task.clone().or_else(|| event.description()))
+            .unwrap_or_else(|| "no description".to_string());
+        let estimate = event
+            .and_then(|event| event.meta().estimate)
+            .map(Duration::seconds);
+        estimates::add(&mut tasks, name, estimate, stop - start);
+    }
+    tasks.retain(|task| task.estimate.is_some());
+    let width = tasks.iter().map(|task| task.name.len()).max().unwrap_or(0);
+    let deviation = |percent: Option<i64>| percent.map_or(String::new(), |p| format!("{:+}%", p));
+    let mut lines = tasks
+        .iter()
+        .map(|task| {
+            format!(
+                "{:width$}  {} of {}  {}",
+                task.name,
+                format(task.actual).formatted,
+                format(task.estimate.unwrap()).formatted,
+                deviation(task.deviation_percent()),
+                width = width
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect::<Vec<_>>();
+    let accuracy = estimates::accuracy(&tasks);
+    let total = estimates::Task {
+        name: String::new(),
+        estimate: Some(accuracy.estimated),
+        actual: accuracy.actual,
+    };
+    let line = format!(
+        "Estimated: {}, Tracked: {}",
+        format(accuracy.estimated).formatted,
+        format(accuracy.actual).formatted
+    );
+    lines.push(match total.deviation_percent() {
+        Some(_) => format!("{} ({})", line, deviation(total.deviation_percent())),
+        None => line,
+    });
+    lines.push(format!(
+        "Accurate Estimates: {} of {} (within {}%)",
+        accuracy.accurate,
+        accuracy.tasks,
+        estimates::TOLERANCE_PERCENT
+    ));
+    let json = serde_json::json!({
+        "tasks": tasks
+            .iter()
+            .map(|task| TaskOutput {
+                name: task.name.clone(),
+                estimate: format(task.estimate.unwrap()),
+                actual: format(task.actual),
+                deviation_percent: task.deviation_percent(),
+            })
+            .collect::<Vec<_>>(),
+        "estimated": format(accuracy.estimated),
+        "actual": format(accuracy.actual),
+        "deviation_percent": total.deviation_percent(),
+        "accurate": accuracy.accurate,
+        "tolerance_percent": estimates::TOLERANCE_PERCENT,
+    });
+    output.print_lines(&lines, &json);
+    Ok(())
+}
+
+fn report_interruptions<Tz: TimeZone>(
+    output: &Output,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    zone: &Tz,
+) -> Result<()> {
+    #[derive(Serialize)]
+    struct DayOutput {
+        date: NaiveDate,
+        interruptions: usize,
+        descriptions: Vec<DescriptionOutput>,
+    }
+    #[derive(Serialize)]
+    struct DescriptionOutput {
+        description: Option<String>,
+        interruptions: usize,
+    }
+
+    let data = apply_filter(data, filter)?;
+    let mut lines = Vec::new();
+    let mut days = Vec::new();
+    for day in get_data_as_days(&data, zone) {
+        let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+        let mut descriptions: Vec<DescriptionOutput> = Vec::new();
+        for start in day.iter().filter(|event| event.is_start()) {
+            let count = start.meta().interruptions.len();
+            let description = start.description();
+            match descriptions
+                .iter_mut()
+                .find(|known| known.description == description)
+            {
+                Some(known) => known.interruptions += count,
+                None if count > 0 => descriptions.push(DescriptionOutput {
+                    description,
+                    interruptions: count,
+                }),
+                None => {}
+            }
+        }
+        let interruptions = descriptions.iter().map(|d| d.interruptions).sum();
+        let per_description = descriptions
+            .iter()
+            .map(|d| {
+                format!(
+                    "{} ({})",
+                    d.description.as_deref().unwrap_or("no description"),
+                    d.interruptions
+                )
+            })
+            .collect::<Vec<_>>();
+        let line = format!(
+            "{}  {}  {}",
+            date,
+            interruptions,
+            per_description.join(", ")
+        );
+        lines.push(line.trim_end().to_string());
+        days.push(DayOutput {
+            date,
+            interruptions,
+            descriptions,
+        });
+    }
+    let total = days.iter().map(|day| day.interruptions).sum::<usize>();
+    lines.push(format!("Total: {}", total));
+    output.print_lines(&lines, &serde_json::json!({ "days": days, "total": total }));
+    Ok(())
+}
+
+/// runs the script of a custom report, which reads the intervals from stdin
+fn report_script<Tz: TimeZone>(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    script: &Path,
+    zone: &Tz,
+) -> Result<()> {
+    let data = apply_filter(data, filter)?;
+    let billed = billing::billed(&settings.projects, &data, clock::now())?;
+    let flags = interval_flags(settings, &data)?;
+    let deductions = lunch_deductions(settings, &data, zone)?;
+    let script = match (script.components().count(), paths::config_dir()) {
+        (1, Some(dir)) if !script.exists() => dir.join("scripts").join(script),
+        _ => script.to_path_buf(),
+    };
+    let mut child = std::process::Command::new(&script)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not run the report script {}", script.display()))?;
+    let stdin = child.stdin.take().expect("stdin of the script is piped");
+    // a script which doesn't read all intervals closes stdin early
+    match jsonl::write(
+        io::BufWriter::new(stdin),
+        &data,
+        &billed,
+        &flags,
+        &deductions,
+    ) {
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        result => {
+            result?;
+        }
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!(
+            "the report script {} failed with {}",
+            script.display(),
+            status
+        );
+    }
+    Ok(())
+}
+
+fn report_diff(
+    output: &Output,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    exported: &Path,
+) -> Result<()> {
+    let exported = apply_filter(&read_json_data(exported)?, filter)?;
+    let current = apply_filter(data, filter)?;
+    let (added, removed) = diff::changes(&exported, &current);
+    let color = output.use_color();
+    let mut lines = diff::render(&exported, &current, color);
+    lines.push(iif!(
+        lines.is_empty(),
+        "No changes since the export".to_string(),
+        format!(
+            "{} entries added and {} removed since the export",
+            added.len(),
+            removed.len()
+        )
+    ));
+    output.print_lines(
+        &lines,
+        &serde_json::json!({
+            "added": output::events(&added),
+            "removed": output::events(&removed),
+        }),
+    );
+    Ok(())
+}
+
+fn report_commits(
+    output: &Output,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    repositories: &[PathBuf],
+) -> Result<()> {
+    let range = get_filter_range(filter)?
+        .map(|(from, to)| (from.with_timezone(&Utc), to.with_timezone(&Utc)));
+    let mut commits = Vec::new();
+    for repository in repositories {
+        commits.extend(activity::commits(repository, range)?);
+    }
+    commits.sort_by_key(|commit| commit.time);
+    let intervals = get_intervals(data, true);
+    let untracked = activity::untracked(&commits, &intervals);
+    let mut lines = untracked
+        .iter()
+        .map(|commit| {
+            format!(
+                "{}  {}  {}",
+                commit.time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                commit.repository,
+                commit.summary
+            )
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!(
+        "{} of {} commits were made outside of the tracked intervals",
+        untracked.len(),
+        commits.len()
+    ));
+    output.print_lines(
+        &lines,
+        &serde_json::json!({ "commits": commits.len(), "untracked": untracked }),
+    );
+    Ok(())
+}
+
+fn report<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    cumulative: bool,
+    offline: &[offline::Period],
+    zone: &Tz,
+) -> Result<()> {
+    #[derive(Serialize)]
+    struct OfflineOutput {
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    }
+
+    #[derive(Serialize)]
+    struct DayOutput {
+        date: NaiveDate,
+        work_time: DurationOutput,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        on_call: Option<DurationOutput>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cumulative: Option<DurationOutput>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lunch_deduction: Option<DurationOutput>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        offline: Vec<OfflineOutput>,
+    }
+
+    let all_data = data;
+    let data = apply_filter(data, filter)?;
+    let until = data.last().map_or_else(Local::now, |last| {
+        end_of_day(last.time(true).with_timezone(&Local).date().naive_local())
+    });
+    let windows = on_call_windows(settings, until)?;
+    let lunch_rule = lunch_rule(settings)?;
+    let now = clock::now();
+    let format = "{hh}:{mm}";
+    let mut lines = Vec::new();
+    let mut days = Vec::new();
+    let mut total = Duration::zero();
+    let mut total_on_call = Duration::zero();
+    let mut total_deducted = Duration::zero();
+    for day in get_data_as_days(&data, zone) {
+        let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+        let lunch = lunch_rule.and_then(|rule| rule.deduction(&get_intervals(&day, false)));
+        let work_time = get_time_from_day(settings, &day, false);
+        let work_time = (work_time - lunch.unwrap_or_else(Duration::zero)).max(Duration::zero());
+        total_deducted = total_deducted + lunch.unwrap_or_else(Duration::zero);
+        total = total
+            .checked_add(&work_time)
+            .expect(CHECKED_ADD_DURATION_ERROR);
+        let (hours, minutes, _) = split_duration(work_time);
+        let work_time = duration_output(format, hours, minutes, 0);
+        let on_call = (!windows.is_empty()).then(|| {
+            let on_call = get_intervals(&day, false)
+                .into_iter()
+                .map(|(start, stop)| {
+                    report::overlap(
+                        (start.with_timezone(&Local), stop.with_timezone(&Local)),
+                        &windows,
+                    )
+                })
+                .fold(Duration::zero(), |sum, overlap| sum + overlap);
+            total_on_call = total_on_call + on_call;
+            let (hours, minutes, _) = split_duration(on_call);
+            duration_output(format, hours, minutes, 0)
+        });
+        let cumulative = cumulative.then(|| {
+            let (hours, minutes, _) = split_duration(total);
+            duration_output(format, hours, minutes, 0)
+        });
+        let mut line = format!("{}  {}", date, work_time.formatted);
+        if let Some(cumulative) = &cumulative {
+            line.push_str(&format!(
+                "  ({})",
+                i18n::format("total {}", &[&cumulative.formatted])
+            ));
+        }
+        let lunch_deduction = lunch.map(|lunch| {
+            let (hours, minutes, _) = split_duration(lunch);
+            duration_output(format, hours, minutes, 0)
+        });
+        if let Some(lunch) = &lunch_deduction {
+            line.push_str(&format!(
+                "  ({})",
+                i18n::format("lunch break -{}", &[&lunch.formatted])
+            ));
+        }
+        match &on_call {
+            Some(on_call) if on_call.formatted != "00:00" => line.push_str(&format!(
+                "  ({})",
+                i18n::format("on call {}", &[&on_call.formatted])
+            )),
+            _ => {}
+        }
+        let (from, to) = (start_of_day(date), start_of_day(date.succ()));
+        let offline = offline
+            .iter()
+            .filter_map(|period| {
+                period.overlap(from.with_timezone(&Utc), to.with_timezone(&Utc), now)
+            })
+            .map(|(start, end)| OfflineOutput {
+                start: start.with_timezone(&Local),
+                end: end.with_timezone(&Local),
+            })
+            .collect::<Vec<_>>();
+        if !offline.is_empty() {
+            let periods = offline
+                .iter()
+                .map(|period| {
+                    format!(
+                        "{}-{}",
+                        period.start.format("%H:%M"),
+                        period.end.format("%H:%M")
+                    )
+                })
+                .collect::<Vec<_>>();
+            line.push_str(&format!(
+                "  ({})",
+                i18n::format("offline {}", &[&periods.join(", ")])
+            ));
+        }
+        lines.push(line);
+        days.push(DayOutput {
+            date,
+            work_time,
+            on_call,
+            cumulative,
+            lunch_deduction,
+            offline,
+        });
+    }
+    let (hours, minutes, _) = split_duration(total);
+    let total = duration_output(format, hours, minutes, 0);
+    lines.push(format!("{}: {}", i18n::text("Total"), total.formatted));
+    let total_on_call = (!windows.is_empty()).then(|| {
+        let (hours, minutes, _) = split_duration(total_on_call);
+        duration_output(format, hours, minutes, 0)
+    });
+    if let Some(total_on_call) = &total_on_call {
+        lines.push(format!(
+            "{}: {}",
+            i18n::text("On Call"),
+            total_on_call.formatted
+        ));
+    }
+    let mut json = serde_json::json!({ "days": days, "total": total, "on_call": total_on_call });
+    if lunch_rule.is_some() {
+        let (hours, minutes, _) = split_duration(total_deducted);
+        let deducted = duration_output(format, hours, minutes, 0);
+        lines.push(format!(
+            "{}: {}",
+            i18n::text("Deducted Lunch Breaks"),
+            deducted.formatted
+        ));
+        json["lunch_deductions"] = serde_json::json!(deducted);
+    }
+    print_carried_overtime(
+        &mut lines,
+        &mut json,
+        carried_overtime(settings, all_data, zone)?,
+    );
+    output.print_lines(&lines, &json);
+    Ok(())
+}
+
+/// sends the work time per day and per project of the week as html mail
+fn email_report<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    week: &str,
+    to: Option<String>,
+    dry_run: bool,
+    zone: &Tz,
+) -> Result<()> {
+    let to = to.or_else(|| settings.email.to.clone()).ok_or_else(|| {
+        Error::Parse(
+            "Please specify the recipient with --to or \"to\" in the [email] table of the config!"
+                .to_string(),
+        )
+    })?;
+    let range = format!("{}-week", week);
+    let (from, until) = named_range(&range, clock::today().naive_local())?;
+    let filter = FilterData {
+        range: vec![range],
+        ..FilterData::default()
+    };
+    let data = apply_filter(data, &filter)?;
+    let format = |time| {
+        let (hours, minutes, _) = split_duration(time);
+        duration_output("{hh}:{mm}", hours, minutes, 0).formatted
+    };
+    let lunch_rule = lunch_rule(settings)?;
+    let mut days = Vec::new();
+    let mut total = Duration::zero();
+    for day in get_data_as_days(&data, zone) {
+        let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+        let lunch = lunch_rule.and_then(|rule| rule.deduction(&get_intervals(&day, false)));
+        let work_time =
+            get_time_from_day(settings, &day, false) - lunch.unwrap_or_else(Duration::zero);
+        let work_time = work_time.max(Duration::zero());
+        total = total + work_time;
+        days.push((date.format("%a %Y-%m-%d").to_string(), format(work_time)));
+    }
+    let grouping = Grouping::Project(None);
+    let projects = group_work_times(settings, &data, &grouping, false, zone)
+        .into_iter()
+        .map(|(project, _, work_time)| {
+            let label =
+                project.unwrap_or_else(|| i18n::format("no {}", &[&i18n::text(grouping.name())]));
+            (label, format(work_time))
+        })
+        .collect();
+    let title = format!("{} {} - {}", i18n::text("Work Time"), from, until);
+    let total = Some((i18n::text("Total").to_string(), format(total)));
+    let tables = [
+        email::Table {
+            heading: i18n::text("Days").to_string(),
+            rows: days,
+            total: total.clone(),
+        },
+        email::Table {
+            heading: i18n::text("Projects").to_string(),
+            rows: projects,
+            total,
+        },
+    ];
+    let message = email::message(
+        settings.email.from.as_deref(),
+        &to,
+        &title,
+        &email::html(&title, &tables),
+    );
+    if dry_run {
+        print!("{}", message.replace("\r\n", "\n"));
+        return Ok(());
+    }
+    let sendmail = settings.email.sendmail.as_deref().unwrap_or("sendmail -t");
+    email::send(sendmail, &message)?;
+    output.print(
+        format!("Sent the report of {} - {} to {}", from, until, to),
+        &serde_json::json!({ "sent_to": to, "from": from, "to": until }),
+    );
+    Ok(())
+}
+
+fn report_gantt(output: &Output, data: &[TrackingEvent], filter: &FilterData) -> Result<()> {
+    #[derive(Serialize)]
+    struct IntervalOutput {
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    }
+
+    let data = apply_filter(data, filter)?;
+    let (from, to) = match (get_filter_range(filter)?, data.first(), data.last()) {
+        (Some((from, to)), _, _) => (from.date().naive_local(), to.date().naive_local()),
+        (None, Some(first), Some(last)) => (
+            first.time(true).with_timezone(&Local).date().naive_local(),
+            last.time(true).with_timezone(&Local).date().naive_local(),
+        ),
+        _ => return Ok(()),
+    };
+    let mut rows: Vec<(String, Vec<timeline::Interval>)> = Vec::new();
+    for (start, stop) in get_intervals(&data, false) {
+        let event = data.iter().find(|e| e.is_start() && e.time(false) == start);
+        let label = event
+            .and_then(|event| event.project().or_else(|| event.description()))
+            .unwrap_or_else(|| "no description".to_string());
+        let interval = (start.with_timezone(&Local), stop.with_timezone(&Local));
+        match rows.iter_mut().find(|(row, _)| *row == label) {
+            Some((_, intervals)) => intervals.push(interval),
+            None => rows.push((label, vec![interval])),
+        }
+    }
+    rows.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut days = vec![from];
+    while let Some(day) = days.last().filter(|day| **day < to).map(NaiveDate::succ) {
+        days.push(day);
+    }
+    // one character per two hours for a week, fewer for longer time spans
+    let slots_per_day = match days.len() {
+        0..=7 => 12,
+        8..=14 => 6,
+        15..=31 => 3,
+        _ => 1,
+    };
+    let values = rows
+        .iter()
+        .map(|(label, intervals)| {
+            let intervals = intervals
+                .iter()
+                .map(|(start, end)| IntervalOutput {
+                    start: *start,
+                    end: *end,
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "label": label, "intervals": intervals })
+        })
+        .collect::<Vec<_>>();
+    output.print_lines(&timeline::gantt(&rows, &days, slots_per_day), &values);
+    Ok(())
+}
+
+/// the configured on-call windows and the events of the on-call calendar, until the given time
+fn on_call_windows(
+    settings: &Settings,
+    until: DateTime<Local>,
+) -> Result<Vec<(DateTime<Local>, DateTime<Local>)>> {
+    let mut windows = settings
+        .on_call
+        .windows
+        .iter()
+        .map(|window| {
+            let start = parse_date_time(&window.start)?.with_timezone(&Local);
+            let end = parse_date_time(&window.end)?.with_timezone(&Local);
+            if end <= start {
+                anyhow::bail!(
+                    "the on-call window from {} to {} ends before it starts",
+                    window.start,
+                    window.end
+                );
+            }
+            Ok((start, end))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(calendar) = &settings.on_call.calendar {
+        let path = shellexpand::full(calendar)
+            .context("could not expand the on-call calendar path")?
+            .to_string();
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("could not read the on-call calendar {}", path))?;
+        windows.extend(
+            ics::parse(&content, until)?
+                .into_iter()
+                .map(|event| (event.start, event.end)),
+        );
+    }
+    windows.sort();
+    Ok(windows)
+}
+
+fn report_on_call(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+) -> Result<()> {
+    #[derive(Serialize)]
+    struct OnCallOutput {
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        description: Option<String>,
+        on_call: DurationOutput,
+    }
+
+    let data = apply_filter(data, filter)?;
+    let windows = on_call_windows(settings, clock::local_now())?;
+    let format = "{hh}:{mm}";
+    let mut lines = Vec::new();
+    let mut intervals = Vec::new();
+    let mut total = Duration::zero();
+    for (start, stop) in get_intervals(&data, false) {
+        let description = data
+            .iter()
+            .find(|e| e.is_start() && e.time(false) == start)
+            .and_then(TrackingEvent::description);
+        let (start, end) = (start.with_timezone(&Local), stop.with_timezone(&Local));
+        let on_call = report::overlap((start, end), &windows);
+        if on_call < Duration::minutes(1) {
+            continue;
+        }
+        total = total + on_call;
+        let (hours, minutes, _) = split_duration(on_call);
+        let on_call = duration_output(format, hours, minutes, 0);
+        lines.push(format!(
+            "{} - {} ({}){}",
+            start.format("%Y-%m-%d %H:%M"),
+            end.format("%H:%M"),
+            on_call.formatted,
+            description
+                .as_ref()
+                .map_or_else(String::new, |d| format!(" \"{}\"", d))
+        ));
+        intervals.push(OnCallOutput {
+            start,
+            end,
+            description,
+            on_call,
+        });
+    }
+    let (hours, minutes, _) = split_duration(total);
+    let total = duration_output(format, hours, minutes, 0);
+    lines.push(format!("On Call: {}", total.formatted));
+    output.print_lines(
+        &lines,
+        &serde_json::json!({ "intervals": intervals, "total": total }),
+    );
+    Ok(())
+}
+
+fn report_plan<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    path: &Path,
+    zone: &Tz,
+) -> Result<()> {
+    let plan = plan::Plan::read(path)?;
+    let data = apply_filter(data, filter)?;
+    let mut tracked = HashMap::new();
+    for day in get_data_as_days(&data, zone) {
+        let date = day[0].time(true).with_timezone(&Local).date().naive_local();
+        tracked.insert(date, get_time_from_day(settings, &day, false));
+    }
+    // the days of the time span which are planned or tracked
+    let mut dates = tracked.keys().copied().collect::<Vec<_>>();
+    match get_filter_range(filter)? {
+        Some((from, to)) => {
+            let mut date = from.date().naive_local();
+            while date <= to.date().naive_local() {
+                dates.push(date);
+                date = date.succ();
+            }
+        }
+        None => dates.extend(plan.dates()),
+    }
+    dates.sort();
+    dates.dedup();
+    let rows = dates
+        .into_iter()
+        .filter_map(|date| {
+            let planned = plan.day(date);
+            let tracked = tracked.get(&date).copied();
+            (planned.is_some() || tracked.is_some()).then(|| {
+                let zero = Duration::zero();
+                (
+                    date.to_string(),
+                    planned.unwrap_or(zero),
+                    tracked.unwrap_or(zero),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    let projects = plan
+        .projects
+        .iter()
+        .map(|(project, planned)| {
+            let intervals = filter_intervals(&data, |start| {
+                start
+                    .project()
+                    .is_some_and(|tracked| project::is_within(&tracked.to_lowercase(), project))
+            });
+            let tracked = get_intervals(&intervals, false)
+                .into_iter()
+                .fold(Duration::zero(), |sum, (start, stop)| sum + (stop - start));
+            (project.clone(), *planned, tracked)
+        })
+        .collect::<Vec<_>>();
+
+    let width = rows
+        .iter()
+        .chain(&projects)
+        .map(|(name, _, _)| name.len())
+        .chain(Some("Project".len()))
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+    let days = plan_table(&mut lines, "Date", &rows, width, true);
+    // sub projects are part of their parents, so they aren't added up
+    let projects = plan_table(&mut lines, "Project", &projects, width, false);
+    output.print_lines(
+        &lines,
+        &serde_json::json!({ "days": days, "projects": projects }),
+    );
+    Ok(())
+}
+
+/// adds the rows of planned and tracked time with their variance, and optionally a total, to the
+/// lines
+fn plan_table(
+    lines: &mut Vec<String>,
+    heading: &str,
+    rows: &[(String, Duration, Duration)],
+    width: usize,
+    with_total: bool,
+) -> Vec<serde_json::Value> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(format!(
+        "{:width$}  Planned  Tracked  Variance",
+        heading,
+        width = width
+    ));
+    let sum = |durations: &mut dyn Iterator<Item = Duration>| {
+        durations.fold(Duration::zero(), |sum, duration| sum + duration)
+    };
+    let total = (
+        "Total".to_string(),
+        sum(&mut rows.iter().map(|row| row.1)),
+        sum(&mut rows.iter().map(|row| row.2)),
+    );
+    let format = "{hh}:{mm}";
+    let mut json = Vec::new();
+    for (name, planned, tracked) in rows.iter().chain(with_total.then_some(&total)) {
+        let (hours, minutes, _) = split_duration(*planned);
+        let planned_output = duration_output(format, hours, minutes, 0);
+        let (hours, minutes, _) = split_duration(*tracked);
+        let tracked_output = duration_output(format, hours, minutes, 0);
+        let (variance, variance_json) = signed_duration(*tracked - *planned);
+        lines.push(format!(
+            "{:width$}  {:7}  {:7}  {}",
+            name,
+            planned_output.formatted,
+            tracked_output.formatted,
+            variance,
+            width = width
+        ));
+        json.push(serde_json::json!({
+            heading.to_lowercase(): name,
+            "planned": planned_output,
+            "tracked": tracked_output,
+            "variance": variance_json,
+        }));
+    }
+    json
+}
+
+fn work_days(settings: &Settings) -> Result<Vec<Weekday>> {
+    settings
+        .working_hours
+        .days
+        .iter()
+        .map(|day| {
+            day.parse::<Weekday>()
+                .map_err(|_| anyhow::anyhow!("invalid work day \"{}\"", day))
+        })
+        .collect()
+}
+
+fn report_gaps(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+) -> Result<()> {
+    #[derive(Serialize)]
+    struct GapOutput {
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        duration: DurationOutput,
+    }
+
+    let now = clock::local_now();
+    let (from, to) = match get_filter_range(filter)? {
+        Some(range) => range,
+        None => match data.first() {
+            Some(first) => (first.time(true).with_timezone(&Local), now),
+            None => (now, now),
+        },
+    };
+    let to = to.min(now);
+    let working_hours = &settings.working_hours;
+    let work_days = work_days(settings)?;
+    let offset = |time: &settings::Time| {
+        Duration::hours(i64::from(time.hours)) + Duration::minutes(i64::from(time.minutes))
+    };
+    let tracked = get_intervals(data, true)
+        .into_iter()
+        .map(|(start, stop)| (start.with_timezone(&Local), stop.with_timezone(&Local)))
+        .collect::<Vec<_>>();
+
+    let format = "{hh}:{mm}";
+    let mut lines = Vec::new();
+    let mut gaps = Vec::new();
+    let mut total = Duration::zero();
+    let mut date = from.date().naive_local();
+    while date <= to.date().naive_local() {
+        if work_days.contains(&date.weekday()) {
+            let day_start = start_of_day(date);
+            let window = (
+                (day_start + offset(&working_hours.start)).max(from),
+                (day_start + offset(&working_hours.end)).min(to),
+            );
+            if window.0 < window.1 {
+                for (start, end) in report::gaps(window, &tracked) {
+                    let duration = end - start;
+                    if duration < Duration::minutes(1) {
+                        continue;
+                    }
+                    total = total + duration;
+                    let (hours, minutes, _) = split_duration(duration);
+                    let duration = duration_output(format, hours, minutes, 0);
+                    lines.push(format!(
+                        "{} - {} ({})",
+                        start.format("%Y-%m-%d %H:%M"),
+                        end.format("%H:%M"),
+                        duration.formatted
+                    ));
+                    gaps.push(GapOutput {
+                        start,
+                        end,
+                        duration,
+                    });
+                }
+            }
+        }
+        date = date.succ();
+    }
+    let (hours, minutes, _) = split_duration(total);
+    let total = duration_output(format, hours, minutes, 0);
+    lines.push(format!("Untracked Time: {}", total.formatted));
+    output.print_lines(&lines, &serde_json::json!({ "gaps": gaps, "total": total }));
+    Ok(())
+}
+
+/// lists the groups of overlapping intervals and merges them with the given strategy
+/// the events which are further in the future than the allowed clock skew. As the events are
+/// sorted, they are at the end.
+fn duplicate_window(settings: &Settings) -> Duration {
+    Duration::seconds(i64::from(settings.duplicate_window))
+}
+
+fn future_events(data: &[TrackingEvent], max_skew: Duration) -> &[TrackingEvent] {
+    &data[insertion_index(data, clock::now() + max_skew)..]
+}
+
+/// the findings about the config, the data file and the data, which is read even if the config is
+/// broken
+fn doctor(
+    output: &Output,
+    settings: Result<&Settings, &config::ConfigError>,
+    data_file: Option<PathBuf>,
+) -> Result<()> {
+    use doctor::{Finding, Status};
+
+    let mut findings = Vec::new();
+    match settings {
+        Ok(settings) => {
+            let files = vec![
+                paths::global_config_file().filter(|path| path.exists()),
+                iif!(
+                    settings.enable_project_settings,
+                    settings::project_config_file(),
+                    None
+                ),
+                settings::local_config_file(),
+            ];
+            let files = files
+                .into_iter()
+                .flatten()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>();
+            findings.push(Finding::ok(
+                "Config",
+                iif!(
+                    files.is_empty(),
+                    "no config file, the defaults are used".to_string(),
+                    format!("{} valid", files.join(", "))
+                ),
+            ));
+            if i18n::Language::parse(&settings.language).is_none() {
+                findings.push(Finding::problem(
+                    "Config",
+                    Status::Error,
+                    format!("unknown language \"{}\"", settings.language),
+                    "set language to \"en\", \"de\" or \"auto\" with `tt open config`".to_string(),
+                ));
+            }
+        }
+        Err(e) => findings.push(Finding::problem(
+            "Config",
+            Status::Error,
+            format!("could not be read: {}", e),
+            "fix it with `tt open config`, or replace it with `tt init --force`".to_string(),
+        )),
+    }
+
+    let configured = settings
+        .ok()
+        .and_then(|settings| settings.data_file.as_deref());
+    let (path, source) = paths::resolve_data_file(data_file, configured)?;
+    let path = shellexpand::full(&path.to_string_lossy())
+        .expect("could not expand path")
+        .to_string();
+    let file = Path::new(&path);
+    if !file.exists() {
+        findings.push(Finding::ok(
+            "Data File",
+            format!(
+                "{} doesn't exist yet, `tt start` creates it ({})",
+                path,
+                source.explanation()
+            ),
+        ));
+    } else {
+        match std::fs::OpenOptions::new().append(true).open(file) {
+            Ok(_) => findings.push(Finding::ok(
+                "Data File",
+                format!("{} is writable ({})", path, source.explanation()),
+            )),
+            Err(e) => findings.push(Finding::problem(
+                "Data File",
+                Status::Error,
+                format!("{} isn't writable: {}", path, e),
+                format!("give your user write access to {}", path),
+            )),
+        }
+        if let Some(problem) = doctor::permission_problem(file) {
+            findings.push(Finding::problem(
+                "Data File",
+                Status::Warning,
+                problem,
+                format!("chmod 600 {}", path),
+            ));
+        }
+    }
+
+    let storage = settings
+        .ok()
+        .and_then(|settings| settings.storage.as_deref());
+    let per_year = settings.is_ok_and(|settings| settings.per_year_files);
+    let data = match storage::backend(storage, file) {
+        Err(e) => {
+            findings.push(Finding::problem(
+                "Storage",
+                Status::Error,
+                e.to_string(),
+                "set storage to a supported format with `tt open config`".to_string(),
+            ));
+            None
+        }
+        Ok(backend) => match storage::read(&*backend, &path, per_year, storage::Selection::All) {
+            Ok(data) => {
+                findings.push(Finding::ok(
+                    "Storage",
+                    format!("{} events read", data.len()),
+                ));
+                Some(data)
+            }
+            Err(e) => {
+                findings.push(Finding::problem(
+                    "Storage",
+                    Status::Error,
+                    format!("the data could not be read: {}", e),
+                    format!(
+                        "restore {} from a backup, or `tt open data` and fix it",
+                        path
+                    ),
+                ));
+                None
+            }
+        },
+    };
+
+    #[cfg(feature = "credentials")]
+    match credentials::get("toggl") {
+        Ok(_) => findings.push(Finding::ok("Keyring", "available".to_string())),
+        Err(e) => findings.push(Finding::problem(
+            "Keyring",
+            Status::Warning,
+            format!("{:#}", e),
+            "start a secret service, or set TOGGL_API_TOKEN instead of `tt auth set`".to_string(),
+        )),
+    }
+    #[cfg(not(feature = "credentials"))]
+    findings.push(Finding::ok(
+        "Keyring",
+        "not part of this build, the credentials feature is disabled".to_string(),
+    ));
+
+    let lease_path = lease::path(&path);
+    match lease::holder(&lease_path) {
+        Some(holder) if holder.is_stale(clock::now()) => findings.push(Finding::problem(
+            "Lease",
+            Status::Warning,
+            format!(
+                "left behind by tt (pid {}), which doesn't run anymore",
+                holder.pid
+            ),
+            format!(
+                "the next change takes it over, or remove {}",
+                lease_path.display()
+            ),
+        )),
+        Some(holder) => findings.push(Finding::ok(
+            "Lease",
+            format!(
+                "held by tt (pid {}) since {}",
+                holder.pid,
+                format_date_time(holder.since)
+            ),
+        )),
+        None if lease_path.exists() => findings.push(Finding::problem(
+            "Lease",
+            Status::Warning,
+            format!("{} can't be read", lease_path.display()),
+            format!(
+                "remove {}, if no other tt process runs",
+                lease_path.display()
+            ),
+        )),
+        None => findings.push(Finding::ok("Lease", "free".to_string())),
+    }
+    match lock::read(lock::path(&path)) {
+        Ok(Some(closed)) => findings.push(Finding::ok(
+            "Closed Months",
+            format!("the entries until {} are closed", closed),
+        )),
+        Ok(None) => {}
+        Err(e) => findings.push(Finding::problem(
+            "Closed Months",
+            Status::Error,
+            e.to_string(),
+            format!(
+                "remove {} and close the months again",
+                lock::path(&path).display()
+            ),
+        )),
+    }
+
+    if let (Some(data), Ok(settings)) = (&data, settings) {
+        let duplicates = normalize::duplicates(data, duplicate_window(settings)).len();
+        if duplicates > 0 {
+            findings.push(Finding::problem(
+                "Data",
+                Status::Warning,
+                format!("{} duplicate events", duplicates),
+                "tt check --fix-duplicates".to_string(),
+            ));
+        }
+        let overlaps = overlaps::groups(data).len();
+        if overlaps > 0 {
+            findings.push(Finding::problem(
+                "Data",
+                Status::Warning,
+                format!("{} groups of overlapping intervals", overlaps),
+                "tt check --fix-overlaps union".to_string(),
+            ));
+        }
+        let future = future_events(data, Duration::minutes(i64::from(settings.max_clock_skew)));
+        if !future.is_empty() {
+            findings.push(Finding::problem(
+                "Data",
+                Status::Warning,
+                format!("{} events in the future", future.len()),
+                "check the clock of the devices, and move the events with `tt shift`".to_string(),
+            ));
+        }
+        if duplicates == 0 && overlaps == 0 && future.is_empty() {
+            findings.push(Finding::ok("Data", "consistent".to_string()));
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.status == Status::Error)
+        .count();
+    output.print_lines(
+        &doctor::lines(&findings),
+        &serde_json::json!({ "findings": findings, "errors": errors }),
+    );
+    if errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn check(
+    output: &Output,
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    fix: Option<&str>,
+    fix_duplicates: bool,
+) -> Result<bool> {
+    let mut lines = Vec::new();
+    let window = duplicate_window(settings);
+    // the duplicate starts would show up as overlaps as well
+    let duplicates = if fix_duplicates {
+        normalize::normalize(data, window)
+    } else {
+        let indexes = normalize::duplicates(data, window);
+        indexes
+            .into_iter()
+            .map(|index| data[index].clone())
+            .collect()
+    };
+    if !duplicates.is_empty() {
+        lines.push(format!(
+            "{} events ({}):",
+            iif!(fix_duplicates, "Removed duplicate", "Duplicate"),
+            duplicates.len()
+        ));
+        lines.extend(
+            get_human_readable(&duplicates)
+                .iter()
+                .map(|line| format!("  {}", line)),
+        );
+        if !fix_duplicates {
+            lines.push("Use --fix-duplicates to remove them".to_string());
+        }
+    }
+    let future = future_events(data, Duration::minutes(i64::from(settings.max_clock_skew)));
+    if !future.is_empty() {
+        lines.push(
+            "Events in the future, probably recorded on a device whose clock was ahead:"
+                .to_string(),
+        );
+        lines.extend(
+            get_human_readable(future)
+                .iter()
+                .map(|line| format!("  {}", line)),
+        );
+    }
+    let future = output::events(future);
+    let unapproved = unmarked_weeks(data, clock::today().naive_local(), |meta| {
+        meta.approval == Some(Approval::Approved)
+    });
+    if !unapproved.is_empty() {
+        let weeks = unapproved
+            .iter()
+            .map(|week| week.to_string())
+            .collect::<Vec<_>>();
+        lines.push(format!(
+            "The weeks starting on {} contain intervals which are not approved",
+            weeks.join(", ")
+        ));
+    }
+    let groups = overlaps::groups(data);
+    let mut json = Vec::new();
+    let mut fixed = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        let events = &data[group.clone()];
+        let merged = overlaps::union(events);
+        let mut group_lines = vec![format!("Overlapping intervals ({}):", index + 1)];
+        group_lines.extend(
+            get_human_readable(events)
+                .iter()
+                .map(|line| format!("  {}", line)),
+        );
+        // in interactive mode each group is shown before its question
+        if fix == Some("interactive") && !output.is_json() {
+            group_lines.iter().for_each(|line| println!("{}", line));
+        } else {
+            lines.extend(group_lines);
+        }
+        json.push(output::events(events));
+        let merge = match fix {
+            Some("union") => true,
+            Some(_) => {
+                let until = merged.get(1).map_or_else(
+                    || "now".to_string(),
+                    |stop| format_date_time(stop.time(true)),
+                );
+                let question = format!(
+                    "Merge them into one interval from {} to {}?",
+                    format_date_time(merged[0].time(true)),
+                    until
+                );
+                confirm(&question, true)
+            }
+            None => false,
+        };
+        if merge {
+            fixed.push((group.clone(), merged));
+        }
+    }
+    if groups.is_empty() {
+        lines.push("No overlapping intervals found".to_string());
+    } else if fix.is_none() {
+        lines.push(
+            "Use --fix-overlaps union or --fix-overlaps interactive to merge them".to_string(),
+        );
+    }
+    // with a fix, the json output lists the changes instead
+    if (fix.is_none() && !fix_duplicates) || !output.is_json() {
+        output.print_lines(
+            &lines,
+            &serde_json::json!({
+                "duplicates": output::events(&duplicates),
+                "overlaps": json,
+                "future": future,
+                "unapproved_weeks": unapproved,
+            }),
+        );
+    }
+    let changed = !fixed.is_empty() || (fix_duplicates && !duplicates.is_empty());
+    // replace from the back, so the ranges of the earlier groups stay valid
+    for (group, merged) in fixed.into_iter().rev() {
+        data.splice(group, merged);
+    }
+    Ok(changed)
+}
+
+fn cleanup(data: &[TrackingEvent]) -> Vec<TrackingEvent> {
+    let mut cleaned = Vec::with_capacity(data.len());
+
+    let mut conflicting = Vec::new();
+
+    let mut is_start = None;
+
+    let mut all_conflicting = Vec::new();
+
+    for e in data {
+        match is_start {
+            None => {
+                is_start = Some(e.is_start());
+                cleaned.push(e);
+            }
+            Some(true) => {
+                if e.is_start() {
+                    if conflicting.is_empty() {
+                        if let Some(last_cleaned_e) = cleaned.pop() {
+                            conflicting.push(last_cleaned_e);
+                        }
+                    }
+                    conflicting.push(e);
+                } else {
+                    if !conflicting.is_empty() {
+                        all_conflicting.push(conflicting);
+                        conflicting = Vec::new();
+                    }
+                    cleaned.push(e);
+                    is_start.replace(false);
+                }
+            }
+            Some(false) => {
+                if e.is_stop() {
+                    if conflicting.is_empty() {
+                        if let Some(last_cleaned_e) = cleaned.pop() {
+                            conflicting.push(last_cleaned_e);
+                        }
+                    }
+                    conflicting.push(e);
+                } else {
+                    if !conflicting.is_empty() {
+                        all_conflicting.push(conflicting);
+                        conflicting = Vec::new();
+                    }
+                    cleaned.push(e);
+                    is_start.replace(true);
+                }
+            }
+        }
+    }
+
+    for mut conflicting in all_conflicting {
+        let event_type = iif!(
+            conflicting
+                .first()
+                .expect("Nothing first tracking event founded")
+                .is_start(),
+            "start",
+            "stop"
+        );
+        println!("Repeated {} events found:", event_type);
+        for (i, event) in conflicting.iter().enumerate() {
+            println!(
+                "({}) {}",
+                i,
+                to_human_readable(
+                    &format!("S{}", &event_type[1..]),
+                    &event.time(true).with_timezone(&Local),
+                    event.description()
+                )
+            );
+        }
         loop {
             println!();
             println!("Please enter the number of the entry to keep (<num>|skip) [default: skip]: ");
@@ -715,131 +4328,2488 @@ fn cleanup(data: &[TrackingEvent]) -> Vec<TrackingEvent> {
                         }
                     }
                 }
-                Err(_) => println!("Could not read from stdin!"),
+                Err(_) => println!("Could not read from stdin!"),
+            }
+        }
+    }
+
+    cleaned.iter().map(Clone::clone).cloned().collect()
+}
+
+fn tz(output: &Output, command: Option<TzCommand>) -> Result<(), Error> {
+    match command {
+        Some(TzCommand::Set { zone }) => {
+            zone::validate(&zone)?;
+            zone::write_session(Some(&zone))?;
+        }
+        Some(TzCommand::Unset) => zone::write_session(None)?,
+        None => {}
+    }
+    let session = zone::read_session()?;
+    let zone = session.clone().or_else(zone::current);
+    let text = match (&session, &zone) {
+        (Some(zone), _) => format!("Time zone: {} (set with tt tz set)", zone),
+        (None, Some(zone)) => format!("Time zone: {}", zone),
+        (None, None) => format!("Time zone: UTC{}", clock::local_now().format("%:z")),
+    };
+    output.print(
+        text,
+        &serde_json::json!({ "zone": zone, "session": session.is_some() }),
+    );
+    Ok(())
+}
+
+/// the overtime which is carried into the current week, if a carry over policy is configured
+fn carried_overtime<Tz: TimeZone>(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    zone: &Tz,
+) -> Result<Option<Duration>> {
+    let policy = match &settings.overtime.carry_over {
+        Some(policy) => overtime::CarryOver::parse(policy)?,
+        None => return Ok(None),
+    };
+    let goal = &settings.time_goal.weekly;
+    let goal = Duration::hours(i64::from(goal.hours)) + Duration::minutes(i64::from(goal.minutes));
+    let (this_week, _) = named_range("this-week", clock::today().naive_local())?;
+    let mut week = match data.first() {
+        Some(first) => {
+            let date = first.time(true).with_timezone(&Local).date().naive_local();
+            date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+        }
+        None => this_week,
+    };
+    let mut differences = Vec::new();
+    while week < this_week {
+        let next_week = week + Duration::weeks(1);
+        let events = filter_events(
+            data,
+            Some((start_of_day(week), start_of_day(next_week))),
+            &None,
+        );
+        if !events.is_empty() {
+            differences.push(get_time_from_events(settings, &events, false, zone) - goal);
+        }
+        week = next_week;
+    }
+    Ok(Some(overtime::carried(&differences, policy)))
+}
+
+/// adds the carried over time to the output of a command
+fn print_carried_overtime(
+    lines: &mut Vec<String>,
+    json: &mut serde_json::Value,
+    carried: Option<Duration>,
+) {
+    if let Some(carried) = carried {
+        let (text, carried) = signed_duration(carried);
+        lines.push(format!("{}: {}", i18n::text("Carried Over"), text));
+        json["carried_over"] = carried;
+    }
+}
+
+/// the daily limits of the running interval and how much of them is used
+fn print_limits(
+    lines: &mut Vec<String>,
+    json: &mut serde_json::Value,
+    settings: &Settings,
+    data: &[TrackingEvent],
+) -> Result<()> {
+    let running = match data.last() {
+        Some(running) => running,
+        None => return Ok(()),
+    };
+    let day_start = start_of_day(clock::today().naive_local()).with_timezone(&Utc);
+    let mut today = Vec::new();
+    let mut events = data.iter();
+    while let Some(start) = events.find(|e| e.is_start()) {
+        let end = events
+            .find(|e| e.is_stop())
+            .map_or_else(clock::now, |stop| stop.time(true));
+        if end > day_start {
+            today.push((start, end - start.time(true).max(day_start)));
+        }
+    }
+    let format = "{hh}:{mm}";
+    let mut limits = Vec::new();
+    for usage in limits::usage(&settings.limits, running, &today)? {
+        let (hours, minutes, _) = split_duration(usage.tracked);
+        let tracked = duration_output(format, hours, minutes, 0);
+        let (hours, minutes, _) = split_duration(usage.limit);
+        let limit = duration_output(format, hours, minutes, 0);
+        let exceeded = usage.tracked > usage.limit;
+        lines.push(format!(
+            "Limit for \"{}\": {} of {}{}",
+            usage.name,
+            tracked.formatted,
+            limit.formatted,
+            iif!(exceeded, " (exceeded)", "")
+        ));
+        limits.push(serde_json::json!({
+            "kind": usage.kind,
+            "name": usage.name,
+            "limit": limit,
+            "tracked": tracked,
+            "exceeded": exceeded,
+        }));
+    }
+    if !limits.is_empty() {
+        json["limits"] = serde_json::json!(limits);
+    }
+    Ok(())
+}
+
+/// a difference like "+01:30" and its json
+fn signed_duration(duration: Duration) -> (String, serde_json::Value) {
+    let negative = duration < Duration::zero();
+    let (hours, minutes, _) = split_duration(iif!(negative, -duration, duration));
+    let duration = duration_output("{hh}:{mm}", hours, minutes, 0);
+    (
+        format!("{}{}", iif!(negative, "-", "+"), duration.formatted),
+        serde_json::json!({ "negative": negative, "duration": duration }),
+    )
+}
+
+fn status<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    quiet: bool,
+    zone: &Tz,
+) -> Result<()> {
+    if quiet {
+        let active = data.last().is_some_and(TrackingEvent::is_start);
+        std::process::exit(iif!(active, 0, 1));
+    }
+    if let Some(event) = data.last() {
+        let time = event.time(true).with_timezone(&Local);
+        let active = event.is_start();
+        let text = iif!(active, "Start Time", "End Time");
+        let mut lines = vec![format!("{}: {}", i18n::text("Active"), active)];
+        if let Some(description) = event.description() {
+            lines.push(format!("{}: {}", i18n::text("Description"), description));
+        }
+        lines.push(format!(
+            "{}: {:02}:{:02}:{:02}",
+            i18n::text(text),
+            time.hour(),
+            time.minute(),
+            time.second()
+        ));
+        let mut json =
+            serde_json::json!({ "active": active, "event": output::EventOutput::from(event) });
+        if let Some(policy) = &settings.breaks.policy {
+            let policy = breaks::Policy::parse(policy)?;
+            let next_break = breaks::next_break(&get_intervals(data, true), active, policy)
+                .map(|time| time.with_timezone(&Local));
+            if let Some(next_break) = next_break {
+                let time = next_break.format("%H:%M:%S");
+                let time = iif!(
+                    next_break <= clock::local_now(),
+                    i18n::format("now (due since {})", &[&time]),
+                    time.to_string()
+                );
+                lines.push(format!("{}: {}", i18n::text("Next Break"), time));
+            }
+            json["next_break"] = serde_json::json!(next_break);
+        }
+        if active {
+            print_limits(&mut lines, &mut json, settings, data)?;
+        }
+        print_carried_overtime(
+            &mut lines,
+            &mut json,
+            carried_overtime(settings, data, zone)?,
+        );
+        output.print_lines(&lines, &json);
+        std::process::exit(iif!(active, 0, -1));
+    } else {
+        output.print(
+            i18n::text("No Events found!"),
+            &serde_json::json!({ "active": false, "event": null }),
+        );
+        std::process::exit(-1);
+    }
+}
+
+fn to_human_readable<Tz: TimeZone>(
+    prefix: &str,
+    time: &DateTime<Tz>,
+    description: Option<String>,
+) -> String {
+    let description = description
+        .map(|d| format!(" \"{}\"", d))
+        .unwrap_or_default();
+    format!(
+        "{} at {:04}-{:02}-{:02} {:02}:{:02}:{:02}{}",
+        prefix,
+        time.year(),
+        time.month(),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+        description,
+    )
+}
+
+fn get_human_readable(data: &[TrackingEvent]) -> Vec<String> {
+    get_human_readable_in(data, false)
+}
+
+/// the time of the event in the zone it was recorded in, if it is known, otherwise in local time
+fn event_local_time(event: &TrackingEvent) -> DateTime<FixedOffset> {
+    let time = event.time(true);
+    match event.meta().utc_offset.and_then(FixedOffset::east_opt) {
+        Some(offset) => time.with_timezone(&offset),
+        None => {
+            let local = time.with_timezone(&Local);
+            local.with_timezone(&local.offset().fix())
+        }
+    }
+}
+
+/// the entries as text, with the times in local time or in the zones they were recorded in
+fn get_human_readable_in(data: &[TrackingEvent], event_local: bool) -> Vec<String> {
+    data.iter()
+        .map(|event| {
+            let time = iif!(
+                event_local,
+                event_local_time(event),
+                event.time(true).with_timezone(&Local).into()
+            );
+            let prefix = iif!(event.is_start(), "Start", "Stop ");
+            let line = to_human_readable(prefix, &time, event.description());
+            let line = match (event_local, &event.meta().zone) {
+                (true, Some(zone)) => format!("{} ({})", line, zone),
+                _ => line,
+            };
+            let line = match event.project() {
+                Some(project) => format!("{} @{}", line, project),
+                None => line,
+            };
+            let line = match event.context() {
+                Some(context) => format!("{} [{}]", line, context),
+                None => line,
+            };
+            let line = match &event.meta().task {
+                Some(task) => format!("{} (task {})", line, task),
+                None => line,
+            };
+            let line = match event.meta().allocation.as_slice() {
+                [] => line,
+                shares => format!(
+                    "{} ({})",
+                    line,
+                    shares
+                        .iter()
+                        .map(|share| format!("{} {}%", share.project, share.percent))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            let line = match event.meta().estimate {
+                Some(estimate) => {
+                    let (hours, minutes, _) = split_duration(Duration::seconds(estimate));
+                    let estimate = duration_output("{hh}:{mm}", hours, minutes, 0);
+                    format!("{} (estimate {})", line, estimate.formatted)
+                }
+                None => line,
+            };
+            event
+                .meta()
+                .tags
+                .iter()
+                .fold(line, |line, tag| format!("{} #{}", line, tag))
+        })
+        .collect::<Vec<_>>()
+}
+
+fn export_human_readable(path: &str, data: &[TrackingEvent]) {
+    let lines = get_human_readable(data);
+    std::fs::write(path, lines.join("\n")).expect("could not export file");
+}
+
+/// whether each interval is on a day off or outside of the working hours of the config
+fn interval_flags(settings: &Settings, data: &[TrackingEvent]) -> Result<Vec<after_hours::Flags>> {
+    let offset = |time: &settings::Time| {
+        Duration::hours(i64::from(time.hours)) + Duration::minutes(i64::from(time.minutes))
+    };
+    let working_hours = after_hours::WorkingHours {
+        days: work_days(settings)?,
+        start: offset(&settings.working_hours.start),
+        end: offset(&settings.working_hours.end),
+    };
+    Ok(get_intervals(data, true)
+        .into_iter()
+        .map(|(start, end)| {
+            working_hours.flags(start.with_timezone(&Local), end.with_timezone(&Local))
+        })
+        .collect())
+}
+
+fn lunch_rule(settings: &Settings) -> Result<Option<breaks::LunchRule>, Error> {
+    settings
+        .breaks
+        .lunch_deduction
+        .as_deref()
+        .map(breaks::LunchRule::parse)
+        .transpose()
+}
+
+/// the deducted lunch break of each interval, which is on the last interval of the day
+fn lunch_deductions<Tz: TimeZone>(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    zone: &Tz,
+) -> Result<Vec<Option<Duration>>> {
+    let rule = match lunch_rule(settings)? {
+        Some(rule) => rule,
+        None => return Ok(Vec::new()),
+    };
+    let mut last_of_day = HashMap::new();
+    for day in get_data_as_days(data, zone) {
+        let intervals = get_intervals(&day, true);
+        if let (Some(deduction), Some((start, _))) = (rule.deduction(&intervals), intervals.last())
+        {
+            last_of_day.insert(*start, deduction);
+        }
+    }
+    Ok(get_intervals(data, true)
+        .iter()
+        .map(|(start, _)| last_of_day.get(start).copied())
+        .collect())
+}
+
+/// writes the intervals to the file or to stdout, if the path is "-"
+fn export_json_lines<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    path: &Path,
+    data: &[TrackingEvent],
+    zone: &Tz,
+) -> Result<()> {
+    let billed = billing::billed(&settings.projects, data, clock::now())?;
+    let flags = interval_flags(settings, data)?;
+    let deductions = lunch_deductions(settings, data, zone)?;
+    if path == Path::new("-") {
+        jsonl::write(
+            io::BufWriter::new(io::stdout().lock()),
+            data,
+            &billed,
+            &flags,
+            &deductions,
+        )?;
+        return Ok(());
+    }
+    let expanded_path = shellexpand::full(&path.to_string_lossy())
+        .expect("could not expand path")
+        .to_string();
+    let file = std::fs::File::create(&expanded_path)
+        .with_context(|| format!("could not create {}", expanded_path))?;
+    let count = jsonl::write(io::BufWriter::new(file), data, &billed, &flags, &deductions)?;
+    output.print_lines(
+        &[],
+        &serde_json::json!({ "exported": expanded_path, "intervals": count }),
+    );
+    Ok(())
+}
+
+/// writes the events of the time spans with a hash chain, and shows the last hash, which
+/// identifies the whole export
+fn export_audit(
+    output: &Output,
+    path: &Path,
+    data: &[TrackingEvent],
+    range: Vec<String>,
+) -> Result<()> {
+    let filter = FilterData {
+        filter: iif!(range.is_empty(), Some("all".to_string()), None),
+        range,
+        ..FilterData::default()
+    };
+    let data = apply_filter(data, &filter)?;
+    let records = hash_chain::chain(
+        data.iter()
+            .map(|event| serde_json::json!(output::EventOutput::from(event)))
+            .collect(),
+    );
+    let last = records
+        .last()
+        .map_or(hash_chain::GENESIS.to_string(), |record| {
+            record["hash"].as_str().unwrap_or_default().to_string()
+        });
+    let content = records
+        .iter()
+        .map(|record| record.to_string() + "\n")
+        .collect::<String>();
+    if path == Path::new("-") {
+        print!("{}", content);
+        return Ok(());
+    }
+    let expanded_path = shellexpand::full(&path.to_string_lossy())
+        .expect("could not expand path")
+        .to_string();
+    std::fs::write(&expanded_path, content)
+        .with_context(|| format!("could not write {}", expanded_path))?;
+    output.print(
+        format!(
+            "Exported {} events, the last hash is {}",
+            records.len(),
+            last
+        ),
+        &serde_json::json!({ "exported": expanded_path, "events": records.len(), "hash": last }),
+    );
+    Ok(())
+}
+
+/// checks that no line of an audit export was changed, removed or added in between
+fn check_audit(output: &Output, path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let (events, last) = hash_chain::verify(&content)?;
+    output.print(
+        format!(
+            "The hash chain of the {} events is intact, the last hash is {}",
+            events, last
+        ),
+        &serde_json::json!({ "events": events, "hash": last }),
+    );
+    Ok(())
+}
+
+/// writes a diagram of the time per project in the time spans, which the report would show
+fn export_diagram<Tz: TimeZone>(
+    output: &Output,
+    settings: &Settings,
+    path: &Path,
+    data: &[TrackingEvent],
+    diagram: &str,
+    range: Vec<String>,
+    zone: &Tz,
+) -> Result<()> {
+    let filter = FilterData {
+        filter: iif!(range.is_empty(), Some("all".to_string()), None),
+        range,
+        ..FilterData::default()
+    };
+    let data = apply_filter(data, &filter)?;
+    let title = match get_filter_range(&filter)? {
+        Some((from, to)) => format!(
+            "Time per project from {} to {}",
+            from.date().naive_local(),
+            to.date().naive_local()
+        ),
+        None => "Time per project".to_string(),
+    };
+    let groups = group_work_times(settings, &data, &Grouping::Project(None), false, zone);
+    let text = match diagram {
+        "mermaid-gantt" => {
+            let sections = groups
+                .iter()
+                .map(|(project, events, _)| {
+                    let tasks = get_intervals(events, false)
+                        .into_iter()
+                        .map(|(start, stop)| {
+                            let name = events
+                                .iter()
+                                .find(|e| e.is_start() && e.time(false) == start)
+                                .and_then(TrackingEvent::description)
+                                .unwrap_or_else(|| "no description".to_string());
+                            (
+                                start.with_timezone(&Local),
+                                stop.with_timezone(&Local),
+                                name,
+                            )
+                        })
+                        .collect();
+                    let label = project.clone().unwrap_or_else(|| "no project".to_string());
+                    (label, tasks)
+                })
+                .collect::<Vec<_>>();
+            diagram::gantt(&title, &sections)
+        }
+        _ => {
+            let (projects, without): (Vec<_>, Vec<_>) = groups
+                .into_iter()
+                .map(|(project, _, work_time)| (project, work_time))
+                .partition(|(project, _)| project.is_some());
+            let mut totals = projects
+                .into_iter()
+                .map(|(project, work_time)| (project.unwrap_or_default(), work_time))
+                .collect::<Vec<_>>();
+            if diagram == "dot" {
+                diagram::dot(&totals)
+            } else {
+                totals.extend(
+                    without
+                        .into_iter()
+                        .map(|(_, time)| ("no project".to_string(), time)),
+                );
+                diagram::pie(&title, &totals)
+            }
+        }
+    };
+    if path == Path::new("-") {
+        print!("{}", text);
+        return Ok(());
+    }
+    let expanded_path = shellexpand::full(&path.to_string_lossy())
+        .expect("could not expand path")
+        .to_string();
+    std::fs::write(&expanded_path, text)
+        .with_context(|| format!("could not write {}", expanded_path))?;
+    output.print_lines(&[], &serde_json::json!({ "exported": expanded_path }));
+    Ok(())
+}
+
+/// runs the editor of the user on the file and waits until it is closed
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| iif!(cfg!(windows), "notepad", "vi").to_string());
+    // editors are often configured with arguments, like "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("the editor is empty")?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("could not run the editor \"{}\"", editor))?;
+    if !status.success() {
+        anyhow::bail!("the editor \"{}\" failed with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// edits a temporary copy of the content until it is valid or the user gives up. Returns the
+/// edited content, if it is valid and was changed.
+fn edit_copy(
+    content: &str,
+    extension: &str,
+    validate: impl Fn(&Path) -> Result<()>,
+) -> Result<Option<String>> {
+    let copy = std::env::temp_dir().join(format!("tt-{}.{}", std::process::id(), extension));
+    std::fs::write(&copy, content)
+        .with_context(|| format!("could not write {}", copy.display()))?;
+    let result = loop {
+        if let Err(e) = run_editor(&copy) {
+            break Err(e);
+        }
+        match validate(&copy) {
+            Ok(()) => {
+                break std::fs::read_to_string(&copy)
+                    .map(|edited| (edited != content).then_some(edited))
+                    .map_err(Into::into)
+            }
+            Err(e) => {
+                eprintln!("{:#}", e);
+                if !confirm("Edit again? Otherwise the changes are discarded", false) {
+                    break Ok(None);
+                }
+            }
+        }
+    };
+    let _ = std::fs::remove_file(&copy);
+    result
+}
+
+/// whether the events are sorted by time and alternate between start and stop, like the events
+/// which are recorded by the commands
+fn validate_events(data: &[TrackingEvent]) -> Result<(), Error> {
+    let mut previous: Option<&TrackingEvent> = None;
+    for event in data {
+        let time = format_date_time(event.time(true));
+        if previous.is_some_and(|previous| previous.time(true) > event.time(true)) {
+            return Err(Error::InvalidState(format!(
+                "The entry at {} is earlier than the one before it!",
+                time
+            )));
+        }
+        if previous.is_none_or(TrackingEvent::is_stop) != event.is_start() {
+            return Err(Error::InvalidState(format!(
+                "The entry at {} has to be a {}!",
+                time,
+                iif!(event.is_start(), "stop", "start")
+            )));
+        }
+        previous = Some(event);
+    }
+    Ok(())
+}
+
+fn open_config(output: &Output) -> Result<()> {
+    let path = paths::global_config_file().context(
+        "Could not find the home directory for the config. Set XDG_CONFIG_HOME or HOME.",
+    )?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            include_str!("../default_config.toml").to_string()
+        }
+        Err(e) => return Err(e).with_context(|| format!("could not read {}", path.display())),
+    };
+    let edited = edit_copy(&content, "toml", |copy| Ok(Settings::check_file(copy)?))?;
+    match edited {
+        Some(edited) => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, edited)
+                .with_context(|| format!("could not write {}", path.display()))?;
+            output.print(
+                format!("Saved the config to {}", path.display()),
+                &serde_json::json!({ "saved": path }),
+            );
+        }
+        None => eprintln!("The config is unchanged!"),
+    }
+    Ok(())
+}
+
+/// asks until the answer is valid. Without an answer, or without stdin, the default is used
+fn ask<T>(question: &str, default: &str, parse: impl Fn(&str) -> Result<T, Error>) -> T {
+    loop {
+        eprint!("{} [{}]: ", question, default);
+        let mut input = String::new();
+        let input = match io::stdin().read_line(&mut input) {
+            Ok(read) if read > 0 && !input.trim().is_empty() => input.trim().to_string(),
+            _ => default.to_string(),
+        };
+        match parse(&input) {
+            Ok(answer) => return answer,
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn init(output: &Output, force: bool) -> Result<()> {
+    let path = paths::global_config_file().context(
+        "Could not find the home directory for the config. Set XDG_CONFIG_HOME or HOME.",
+    )?;
+    if path.exists() && !force {
+        anyhow::bail!(
+            "There is a config at {} already! Use --force to replace it, or `tt open config` to \
+             edit it.",
+            path.display()
+        );
+    }
+    let (default_data_file, _) = paths::resolve_data_file(None, None)?;
+    let default_data_file = default_data_file.display().to_string();
+    let data_file = ask(
+        "Where should the data be saved?",
+        &default_data_file,
+        |path| Ok((path != default_data_file).then(|| path.to_string())),
+    );
+    let default_storage = match data_file.as_deref().map(Path::new) {
+        Some(path) if path.extension().is_some_and(|e| e == "json") => "json",
+        Some(path) if path.extension().is_some_and(|e| e == "jsonl") => "json-lines",
+        _ => iif!(cfg!(feature = "binary"), "binary", "json"),
+    };
+    let storage = ask(
+        "Which format should the data be saved in? (binary, json or json-lines)",
+        default_storage,
+        |format| {
+            storage::backend(Some(format), Path::new(""))?;
+            Ok((format != default_storage).then(|| format.to_string()))
+        },
+    );
+    let daily_goal = ask("How long do you work per day?", "8h", |goal| {
+        let goal = duration_parse::parse(goal)?;
+        if goal < Duration::zero() || goal > Duration::hours(24) {
+            return Err(Error::Parse("The daily goal has to be 0-24h!".to_string()));
+        }
+        // the goal is configured in minutes
+        Ok(Duration::minutes(goal.num_minutes()))
+    });
+    let work_days = ask("Which days do you work on?", "mon-fri", parse_weekdays);
+    let rounding = ask(
+        "Which project is billed in increments?",
+        "none",
+        |project| Ok((project != "none").then(|| project.to_string())),
+    )
+    .map(|project| {
+        let rounding = ask(
+            "In which increments? (like \"30m per day\" or \"6m per interval\")",
+            "30m per day",
+            |rounding| billing::Rounding::parse(rounding).map(|_| rounding.to_string()),
+        );
+        (project, rounding)
+    });
+    let break_policy = ask(
+        "Remind you to take breaks in `tt status`? (minutes of work/break like \"50/10\")",
+        "none",
+        |policy| {
+            if policy == "none" {
+                return Ok(None);
+            }
+            breaks::Policy::parse(policy)?;
+            Ok(Some(policy.to_string()))
+        },
+    );
+    let capture_window_title = cfg!(feature = "window-title")
+        && confirm(
+            "Save the title of the active window with new intervals?",
+            false,
+        );
+    let config = setup::Setup {
+        data_file,
+        storage,
+        daily_goal,
+        work_days,
+        rounding,
+        break_policy,
+        capture_window_title,
+    }
+    .config();
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // an existing config is only replaced by a valid one
+    let new = path.with_extension("toml.new");
+    std::fs::write(&new, config).with_context(|| format!("could not write {}", new.display()))?;
+    if let Err(e) = Settings::check_file(&new) {
+        let _ = std::fs::remove_file(&new);
+        return Err(e.into());
+    }
+    std::fs::rename(&new, &path).with_context(|| format!("could not write {}", path.display()))?;
+    output.print(
+        format!("Created the config at {}", path.display()),
+        &serde_json::json!({ "created": path }),
+    );
+    Ok(())
+}
+
+fn open_data(data: &mut Vec<TrackingEvent>) -> Result<bool> {
+    let content = serde_json::to_string_pretty(data).expect("could not serialize data");
+    let edited = edit_copy(&content, "json", |copy| {
+        Ok(validate_events(&read_json_data(copy)?)?)
+    })?;
+    match edited {
+        Some(edited) => {
+            *data = serde_json::from_str(&edited)?;
+            Ok(true)
+        }
+        None => {
+            eprintln!("The data is unchanged!");
+            Ok(false)
+        }
+    }
+}
+
+fn confirm(question: &str, default: bool) -> bool {
+    eprintln!("{} {}: ", question, iif!(default, "[Y/n]", "[y/N]"));
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    match input.trim().to_lowercase().as_str() {
+        "" => default,
+        answer => answer == "y" || answer == "yes",
+    }
+}
+
+/// asks if the description which is usually used around this time should be used
+fn suggest_description(data: &[TrackingEvent], at: DateTime<Local>) -> Option<String> {
+    let history = data
+        .iter()
+        .filter(|event| event.is_start())
+        .filter_map(|event| Some((event.time(true).with_timezone(&Local), event.description()?)))
+        .collect::<Vec<_>>();
+    let suggestion = suggest::suggest(&history, at)?;
+    let question = format!(
+        "Usually \"{}\" at {} - use it?",
+        suggestion.description,
+        suggestion.time.format("%H:%M")
+    );
+    iif!(confirm(&question, true), Some(suggestion.description), None)
+}
+
+/// imports the events of a calendar as intervals. Events which overlap with already tracked time
+/// or haven't ended yet are skipped. Returns true, if something was imported.
+#[allow(clippy::too_many_arguments)]
+fn import_ics(
+    output: &Output,
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    path: &Path,
+    filter: &FilterData,
+    as_intervals: bool,
+    tags: Vec<String>,
+    yes: bool,
+) -> Result<bool> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let range = get_filter_range(filter)?;
+    let now = clock::local_now();
+    let until = range.map_or(now, |(_, to)| to.min(now));
+    let mut import = Import::new(data, duplicate_window(settings));
+    for event in ics::parse(&content, until)? {
+        let in_range = range.is_none_or(|(from, _)| event.start >= from) && event.end <= now;
+        if !in_range || !matches_description(&filter.filter, event.summary.as_deref()) {
+            continue;
+        }
+        import.add(
+            event.start.with_timezone(&Utc),
+            event.end.with_timezone(&Utc),
+            event.summary,
+            Metadata {
+                tags: tags.clone(),
+                ..Metadata::default()
+            },
+        );
+    }
+
+    let hint = (!as_intervals).then_some("Use --as-intervals to import these events");
+    import.finish(output, data, "calendar events", hint, yes)
+}
+
+/// intervals to import, which don't overlap with each other or the already tracked time
+struct Import {
+    tracked: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    events: Vec<TrackingEvent>,
+    skipped: usize,
+    /// the window of the duplicate events, which are collapsed after the import
+    duplicate_window: Duration,
+}
+
+impl Import {
+    fn new(data: &[TrackingEvent], duplicate_window: Duration) -> Self {
+        Self {
+            tracked: get_intervals(data, true),
+            events: Vec::new(),
+            skipped: 0,
+            duplicate_window,
+        }
+    }
+
+    fn add(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        description: Option<String>,
+        meta: Metadata,
+    ) {
+        if self
+            .tracked
+            .iter()
+            .any(|(tracked_start, tracked_end)| start < *tracked_end && end > *tracked_start)
+        {
+            self.skipped += 1;
+            return;
+        }
+        self.tracked.push((start, end));
+        self.events.push(TrackingEvent::Start(TrackingData {
+            description,
+            time: start,
+            meta,
+        }));
+        self.events.push(TrackingEvent::Stop(TrackingData {
+            description: None,
+            time: end,
+            meta: Metadata::default(),
+        }));
+    }
+
+    /// shows the intervals and adds them to the data after confirmation. With a `preview_hint`
+    /// the intervals are only shown, followed by the hint.
+    fn finish(
+        mut self,
+        output: &Output,
+        data: &mut Vec<TrackingEvent>,
+        what: &str,
+        preview_hint: Option<&str>,
+        yes: bool,
+    ) -> Result<bool> {
+        if !output.is_json() || preview_hint.is_some() {
+            let color = output.use_color();
+            let mut lines = diff::render(&[], &self.events, color);
+            if self.skipped > 0 {
+                lines.push(format!(
+                    "Skipped {} {}, which overlap with already tracked time",
+                    self.skipped, what
+                ));
+            }
+            if let Some(hint) = preview_hint.filter(|_| !self.events.is_empty()) {
+                lines.push(hint.to_string());
+            }
+            output.print_lines(&lines, &output::events(&self.events));
+        }
+        if preview_hint.is_some() || self.events.is_empty() {
+            return Ok(false);
+        }
+        if !yes
+            && !confirm(
+                &format!("Import {} {}?", self.events.len() / 2, what),
+                false,
+            )
+        {
+            return Ok(false);
+        }
+        data.append(&mut self.events);
+        let duplicates = normalize::normalize(data, self.duplicate_window);
+        if !duplicates.is_empty() && !output.is_json() {
+            println!("Collapsed {} duplicate events", duplicates.len());
+        }
+        Ok(true)
+    }
+}
+
+/// the api token of `tt auth set toggl` or, without one, of $TOGGL_API_TOKEN
+fn toggl_client() -> Result<toggl::Client> {
+    #[cfg(feature = "credentials")]
+    let stored = credentials::get("toggl")?;
+    #[cfg(not(feature = "credentials"))]
+    let stored = None;
+    let token = stored
+        .or_else(|| std::env::var("TOGGL_API_TOKEN").ok())
+        .context("There is no api token for toggl, please store it with `tt auth set toggl`!")?;
+    Ok(toggl::Client::new(token))
+}
+
+/// the range of a sync, which toggl needs
+fn toggl_range(filter: &FilterData) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let (from, to) = get_filter_range(filter)?
+        .context("Please specify the time span to sync, e.g. --range last-week")?;
+    Ok((from.with_timezone(&Utc), to.with_timezone(&Utc)))
+}
+
+fn format_conflict(start: DateTime<Utc>, stop: DateTime<Utc>, description: Option<&str>) -> String {
+    let line = format!("{} - {}", format_date_time(start), format_date_time(stop));
+    match description {
+        Some(description) => format!("{} \"{}\"", line, description),
+        None => line,
+    }
+}
+
+/// pulls the finished time entries of toggl which aren't tracked yet
+fn import_toggl(
+    output: &Output,
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    filter: &FilterData,
+    dry_run: bool,
+    yes: bool,
+) -> Result<bool> {
+    let (from, to) = toggl_range(filter)?;
+    let client = toggl_client()?;
+    let entries = client.entries(from, to)?;
+    let mut workspaces = entries.iter().map(|e| e.workspace_id).collect::<Vec<_>>();
+    workspaces.sort_unstable();
+    workspaces.dedup();
+    let mut projects = Vec::new();
+    for workspace in workspaces {
+        projects.extend(client.projects(workspace)?);
+    }
+
+    let tracked = get_intervals(data, true);
+    let mut import = Import::new(data, duplicate_window(settings));
+    let mut conflicts = Vec::new();
+    for entry in entries {
+        let stop = match entry.stop {
+            Some(stop) => stop,
+            None => continue,
+        };
+        match toggl::sync((entry.start, stop), &tracked) {
+            toggl::Sync::Synced => continue,
+            toggl::Sync::Conflict => {
+                conflicts.push(format_conflict(
+                    entry.start,
+                    stop,
+                    entry.description.as_deref(),
+                ));
+                continue;
+            }
+            toggl::Sync::New => {}
+        }
+        let project = entry
+            .project_id
+            .and_then(|id| projects.iter().find(|project| project.id == id))
+            .map(|project| toggl::local(&settings.toggl.projects, &project.name).to_string());
+        let mut tags = entry
+            .tags
+            .unwrap_or_default()
+            .iter()
+            .map(|tag| toggl::local(&settings.toggl.tags, tag).to_string())
+            .collect::<Vec<_>>();
+        if let Some(project) = &project {
+            for tag in project::inherited_tags(&settings.projects, project) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
             }
         }
+        import.add(
+            entry.start,
+            stop,
+            entry
+                .description
+                .filter(|description| !description.is_empty()),
+            Metadata {
+                project,
+                tags,
+                ..Metadata::default()
+            },
+        );
+    }
+    if !conflicts.is_empty() && !output.is_json() {
+        eprintln!(
+            "Skipped {} toggl entries, which overlap with other local intervals:",
+            conflicts.len()
+        );
+        for conflict in &conflicts {
+            eprintln!("  {}", conflict);
+        }
     }
+    let hint = dry_run.then_some("Run without --dry-run to import these intervals");
+    import.finish(output, data, "intervals", hint, yes)
+}
 
-    cleaned.iter().map(Clone::clone).cloned().collect()
+/// pushes the finished intervals which aren't in toggl yet
+fn export_toggl(
+    output: &Output,
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let workspace = settings
+        .toggl
+        .workspace_id
+        .context("Please set the workspace_id in the toggl table of the config!")?;
+    let (from, to) = toggl_range(filter)?;
+    let data = apply_filter(data, filter)?;
+    let client = toggl_client()?;
+    let remote = client
+        .entries(from, to)?
+        .iter()
+        .map(|entry| (entry.start, entry.stop.unwrap_or_else(clock::now)))
+        .collect::<Vec<_>>();
+    let projects = client.projects(workspace)?;
+
+    let mut entries = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut synced = 0;
+    for (start, stop) in get_intervals(&data, true) {
+        let event = match data.iter().find(|e| e.is_start() && e.time(true) == start) {
+            // the running interval is pushed once it is stopped
+            Some(event) if data.last() != Some(event) => event,
+            _ => continue,
+        };
+        match toggl::sync((start, stop), &remote) {
+            toggl::Sync::Synced => synced += 1,
+            toggl::Sync::Conflict => {
+                conflicts.push(format_conflict(start, stop, event.description().as_deref()))
+            }
+            toggl::Sync::New => {
+                let project_id = match event.project() {
+                    Some(project) => {
+                        let name = toggl::remote(&settings.toggl.projects, &project);
+                        let project = projects.iter().find(|project| project.name == name);
+                        Some(project.map(|project| project.id).with_context(|| {
+                            format!("The toggl workspace has no project \"{}\"!", name)
+                        })?)
+                    }
+                    None => None,
+                };
+                let tags = event.meta().tags.iter();
+                entries.push(toggl::NewEntry {
+                    description: event.description(),
+                    start,
+                    stop,
+                    duration: (stop - start).num_seconds(),
+                    workspace_id: workspace,
+                    project_id,
+                    tags: tags
+                        .map(|tag| toggl::remote(&settings.toggl.tags, tag).to_string())
+                        .collect(),
+                    created_with: "timetracking",
+                });
+            }
+        }
+    }
+
+    let mut lines = entries
+        .iter()
+        .map(|entry| {
+            let line = format_conflict(entry.start, entry.stop, entry.description.as_deref());
+            format!("Push {}", line)
+        })
+        .collect::<Vec<_>>();
+    lines.extend(
+        conflicts
+            .iter()
+            .map(|conflict| format!("Conflict {}", conflict)),
+    );
+    if synced > 0 {
+        lines.push(format!("{} intervals are in toggl already", synced));
+    }
+    let json = serde_json::json!({
+        "push": entries,
+        "conflicts": conflicts,
+        "synced": synced,
+    });
+    if dry_run || entries.is_empty() {
+        if dry_run && !entries.is_empty() {
+            lines.push("Run without --dry-run to push these intervals".to_string());
+        }
+        output.print_lines(&lines, &json);
+        return Ok(());
+    }
+    if !output.is_json() {
+        output.print_lines(&lines, &json);
+    }
+    if !yes
+        && !confirm(
+            &format!("Push {} intervals to toggl?", entries.len()),
+            false,
+        )
+    {
+        return Ok(());
+    }
+    for entry in &entries {
+        client.create(entry)?;
+    }
+    output.print(
+        format!("Pushed {} intervals to toggl", entries.len()),
+        &serde_json::json!({ "pushed": entries.len(), "conflicts": conflicts }),
+    );
+    Ok(())
 }
 
-fn status(data: &[TrackingEvent]) {
-    if let Some(event) = data.last() {
-        let time = event.time(true).with_timezone(&Local);
-        let active = event.is_start();
-        let text = iif!(active, "Start", "End");
-        if let Some(description) = event.description() {
-            println!("Active: {}", active);
-            println!("Description: {}", description,);
+/// replaces the data with the entries of the file, after showing the differences. Duplicate
+/// events of the file, e.g. of merged data, are collapsed.
+/// how the import changes the intervals in the offline periods, which are usually filled in
+/// with the data of another machine
+fn offline_changes(
+    periods: &[offline::Period],
+    data: &[TrackingEvent],
+    imported: &[TrackingEvent],
+) -> Vec<String> {
+    let now = clock::now();
+    let in_period = |period: &offline::Period, data| {
+        get_intervals(data, false)
+            .into_iter()
+            .filter(|(start, stop)| period.overlap(*start, *stop, now).is_some())
+            .collect::<Vec<_>>()
+    };
+    let mut lines = Vec::new();
+    for period in periods {
+        let (before, after) = (in_period(period, data), in_period(period, imported));
+        if before == after {
+            continue;
+        }
+        lines.push(iif!(
+            after.is_empty(),
+            format!(
+                "Leaves the offline period {} empty",
+                format_offline_period(period)
+            ),
+            format!(
+                "Fills the offline period {} with {} {}",
+                format_offline_period(period),
+                after.len(),
+                iif!(after.len() == 1, "interval", "intervals")
+            )
+        ));
+    }
+    lines
+}
+
+fn import_json(
+    output: &Output,
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    path: &Path,
+    offline: &[offline::Period],
+    yes: bool,
+) -> Result<bool> {
+    let mut imported = read_json_data(path)?;
+    let duplicates = normalize::normalize(&mut imported, duplicate_window(settings));
+    let changes = diff::render(data, &imported, output.use_color());
+    if changes.is_empty() {
+        eprintln!("The file contains no changes!");
+        return Ok(false);
+    }
+    if !output.is_json() {
+        println!("{}", changes.join("\n"));
+        for line in offline_changes(offline, data, &imported) {
+            println!("{}", line);
+        }
+        if !duplicates.is_empty() {
             println!(
-                "{} Time: {:02}:{:02}:{:02}",
-                text,
-                time.hour(),
-                time.minute(),
-                time.second()
+                "Collapsed {} duplicate events of the file",
+                duplicates.len()
             );
-        } else {
-            println!("Active: {}", active);
-            println!(
-                "{} Time: {:02}:{:02}:{:02}",
+        }
+    }
+    if !yes && !confirm("Replace all entries with the imported ones?", false) {
+        return Ok(false);
+    }
+    *data = imported;
+    Ok(true)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn import_csv(
+    output: &Output,
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    path: &Path,
+    map: &str,
+    date_format: Option<&str>,
+    delimiter: char,
+    dry_run: bool,
+    tags: Vec<String>,
+    yes: bool,
+) -> Result<bool> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let mut rows = csv::parse(&content, delimiter)?.into_iter();
+    let header = rows.next().unwrap_or_default();
+    let mut columns = HashMap::new();
+    for mapping in map.split(',') {
+        let (field, column) = mapping
+            .split_once('=')
+            .with_context(|| format!("invalid mapping \"{}\", expected field=column", mapping))?;
+        let field = field.trim();
+        if !["start", "stop", "desc", "context", "project"].contains(&field) {
+            anyhow::bail!("unknown field \"{}\" in the mapping", field);
+        }
+        let index = header
+            .iter()
+            .position(|name| name.trim() == column.trim())
+            .with_context(|| format!("the csv file has no column \"{}\"", column.trim()))?;
+        columns.insert(field, index);
+    }
+    if !columns.contains_key("start") || !columns.contains_key("stop") {
+        anyhow::bail!("the mapping needs the start and the stop column");
+    }
+
+    let parse_time = |value: &str| -> Result<DateTime<Utc>> {
+        match date_format {
+            Some(format) => NaiveDateTime::parse_from_str(value, format)
+                .ok()
+                .and_then(|time| Local.from_local_datetime(&time).single())
+                .map(|time| time.with_timezone(&Utc))
+                .with_context(|| format!("\"{}\" doesn't match the format \"{}\"", value, format)),
+            None => Ok(parse_date_time(value)?),
+        }
+    };
+    let mut import = Import::new(data, duplicate_window(settings));
+    for (line, row) in rows.enumerate() {
+        let field = |name| {
+            columns
+                .get(name)
+                .and_then(|index| row.get(*index))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let time = |name| {
+            field(name)
+                .with_context(|| format!("the {} time is missing", name))
+                .and_then(parse_time)
+                .with_context(|| format!("invalid row {} of the csv file", line + 2))
+        };
+        let (start, stop) = (time("start")?, time("stop")?);
+        if stop < start {
+            anyhow::bail!("row {} of the csv file stops before it starts", line + 2);
+        }
+        import.add(
+            start,
+            stop,
+            field("desc").map(str::to_string),
+            Metadata {
+                context: field("context").map(str::to_string),
+                tags: field("project").map_or_else(
+                    || tags.clone(),
+                    |project| {
+                        let mut tags = tags.clone();
+                        for tag in project::inherited_tags(&settings.projects, project) {
+                            if !tags.contains(&tag) {
+                                tags.push(tag);
+                            }
+                        }
+                        tags
+                    },
+                ),
+                project: field("project").map(str::to_string),
+                ..Metadata::default()
+            },
+        );
+    }
+
+    let hint = dry_run.then_some("Run without --dry-run to import these intervals");
+    import.finish(output, data, "intervals", hint, yes)
+}
+
+#[cfg(feature = "credentials")]
+fn auth(output: &Output, command: AuthCommand) -> Result<()> {
+    match command {
+        AuthCommand::Set { service } => {
+            eprintln!("Please enter the credential for \"{}\": ", service);
+            let secret = credentials::read_secret()?;
+            anyhow::ensure!(!secret.is_empty(), "The credential must not be empty!");
+            credentials::set(&service, &secret)?;
+            output.print(
+                format!("Stored credential for \"{}\"", service),
+                &serde_json::json!({ "service": service, "stored": true }),
+            );
+        }
+        AuthCommand::Remove { service } => {
+            credentials::remove(&service)?;
+            output.print(
+                format!("Removed credential for \"{}\"", service),
+                &serde_json::json!({ "service": service, "stored": false }),
+            );
+        }
+        AuthCommand::Check { service } => {
+            let stored = credentials::get(&service)?.is_some();
+            let text = if stored {
+                format!("A credential is stored for \"{}\"", service)
+            } else {
+                format!("No credential is stored for \"{}\"", service)
+            };
+            output.print(
                 text,
-                time.hour(),
-                time.minute(),
-                time.second()
+                &serde_json::json!({ "service": service, "stored": stored }),
             );
         }
-        std::process::exit(iif!(active, 0, -1));
-    } else {
-        println!("No Events found!");
-        std::process::exit(-1);
     }
+    Ok(())
 }
 
-fn to_human_readable<Tz: TimeZone>(
-    prefix: &str,
-    time: &DateTime<Tz>,
-    description: Option<String>,
-) -> String {
-    let description = description
-        .map(|d| format!(" \"{}\"", d))
-        .unwrap_or_default();
-    format!(
-        "{} at {:04}-{:02}-{:02} {:02}:{:02}:{:02}{}",
-        prefix,
-        time.year(),
-        time.month(),
-        time.day(),
-        time.hour(),
-        time.minute(),
-        time.second(),
-        description,
-    )
+/// the arguments tt was called with, to describe the command in the journal and the trash
+fn command_line() -> String {
+    std::env::args().skip(1).collect::<Vec<_>>().join(" ")
 }
 
-fn get_human_readable(data: &[TrackingEvent]) -> Vec<String> {
-    data.iter()
-        .map(|event| match event {
-            TrackingEvent::Start(TrackingData { time, description }) => {
-                to_human_readable("Start", &time.with_timezone(&Local), description.clone())
+fn classify(
+    output: &Output,
+    settings: &Settings,
+    data: &mut [TrackingEvent],
+    filter: &FilterData,
+    apply: bool,
+) -> Result<bool> {
+    let rules = rules::parse(&settings.rules)?;
+    let selected = apply_filter(data, filter)?;
+    let mut changes = Vec::new();
+    for event in data.iter_mut().filter(|event| selected.contains(event)) {
+        if let TrackingEvent::Start(TrackingData {
+            description: Some(description),
+            meta,
+            ..
+        }) = event
+        {
+            let before = meta.clone();
+            rules::classify(settings, &rules, description, meta)?;
+            if *meta != before {
+                changes.push(event.clone());
+                if !apply {
+                    if let TrackingEvent::Start(data) = event {
+                        data.meta = before;
+                    }
+                }
+            }
+        }
+    }
+    let mut lines = get_human_readable(&changes);
+    if !apply && !changes.is_empty() {
+        lines.push("Use --apply to change these entries".to_string());
+    }
+    if !apply || !output.is_json() {
+        output.print_lines(&lines, &output::events(&changes));
+    }
+    Ok(apply && !changes.is_empty())
+}
+
+fn dedupe_descriptions(
+    output: &Output,
+    data: &mut [TrackingEvent],
+    filter: &FilterData,
+    max_distance: usize,
+    yes: bool,
+) -> Result<bool> {
+    let selected = apply_filter(data, filter)?;
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for description in selected
+        .iter()
+        .filter(|e| e.is_start())
+        .filter_map(|e| e.description())
+    {
+        match counts.iter_mut().find(|(known, _)| *known == description) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((description, 1)),
+        }
+    }
+    let clusters = similarity::clusters(&counts, max_distance);
+    if clusters.is_empty() {
+        eprintln!("There are no similar descriptions!");
+        return Ok(false);
+    }
+
+    let mut renames = Vec::new();
+    for cluster in &clusters {
+        let (canonical, _) = &cluster[0];
+        let usages = cluster
+            .iter()
+            .map(|(description, count)| format!("\"{}\" ({})", description, count))
+            .collect::<Vec<_>>();
+        if !output.is_json() {
+            println!("{}", usages.join(", "));
+        }
+        if yes || confirm(&format!("Rename them to \"{}\"?", canonical), true) {
+            renames.extend(
+                cluster[1..]
+                    .iter()
+                    .map(|(description, _)| (description.clone(), canonical.clone())),
+            );
+        }
+    }
+    let mut changed = false;
+    for event in data.iter_mut().filter(|event| selected.contains(event)) {
+        if let TrackingEvent::Start(TrackingData {
+            description: Some(description),
+            ..
+        }) = event
+        {
+            if let Some((_, canonical)) = renames.iter().find(|(from, _)| from == description) {
+                *description = canonical.clone();
+                changed = true;
             }
-            TrackingEvent::Stop(TrackingData { time, description }) => {
-                to_human_readable("Stop ", &time.with_timezone(&Local), description.clone())
+        }
+    }
+    Ok(changed)
+}
+
+fn close_month(output: &Output, lock_path: &Path, month: Option<String>) -> Result<()> {
+    let mut closed_until = lock::read(lock_path)?;
+    if let Some(month) = month {
+        let until = lock::end_of_month(&month)?;
+        if closed_until.is_some_and(|closed_until| closed_until >= until) {
+            eprintln!("The month {} is already closed!", month);
+        } else {
+            lock::write(lock_path, until)?;
+            closed_until = Some(until);
+        }
+    }
+    let text = match closed_until {
+        Some(until) => format!("Entries before {} are closed", until),
+        None => "No entries are closed".to_string(),
+    };
+    output.print(text, &serde_json::json!({ "closed_until": closed_until }));
+    Ok(())
+}
+
+fn format_offline_period(period: &offline::Period) -> String {
+    match period.end {
+        Some(end) => format!(
+            "{} - {}",
+            format_date_time(period.start),
+            format_date_time(end)
+        ),
+        None => format!("since {}", format_date_time(period.start)),
+    }
+}
+
+fn offline_command(output: &Output, path: &Path, command: OfflineCommand) -> Result<()> {
+    let mut periods = offline::read(path)?;
+    match command {
+        OfflineCommand::Start { at } => {
+            offline::start(&mut periods, event_time(at)?)?;
+            offline::write(path, &periods)?;
+        }
+        OfflineCommand::End { at } => {
+            offline::end(&mut periods, event_time(at)?)?;
+            offline::write(path, &periods)?;
+        }
+        OfflineCommand::List => {
+            let lines = periods
+                .iter()
+                .map(format_offline_period)
+                .collect::<Vec<_>>();
+            output.print_lines(&lines, &periods);
+            return Ok(());
+        }
+    }
+    let period = periods.last().expect("no offline period");
+    output.print(format!("Offline {}", format_offline_period(period)), period);
+    Ok(())
+}
+
+/// marks the selected intervals as submitted
+fn mark_submitted(data: &mut [TrackingEvent], filter: &FilterData) -> Result<bool> {
+    let selected = apply_filter(data, filter)?;
+    let mut changed = false;
+    for event in data.iter_mut().filter(|event| selected.contains(event)) {
+        if let TrackingEvent::Start(TrackingData { meta, .. }) = event {
+            changed |= !meta.submitted;
+            meta.submitted = true;
+        }
+    }
+    if !changed {
+        eprintln!("There are no unsubmitted intervals!");
+    }
+    Ok(changed)
+}
+
+fn approve(data: &mut [TrackingEvent], filter: &FilterData, reject: bool) -> Result<bool> {
+    let selected = apply_filter(data, filter)?;
+    let approval = Some(iif!(reject, Approval::Rejected, Approval::Approved));
+    let mut changed = false;
+    for event in data.iter_mut().filter(|event| selected.contains(event)) {
+        if let TrackingEvent::Start(TrackingData { meta, .. }) = event {
+            changed |= meta.approval != approval;
+            meta.approval = approval;
+        }
+    }
+    if !changed {
+        eprintln!(
+            "There are no intervals which aren't {} yet!",
+            iif!(reject, "rejected", "approved")
+        );
+    }
+    Ok(changed)
+}
+
+/// the mondays of the weeks before the current one with intervals which are not marked, e.g. as
+/// submitted. Only the weeks after the first marked interval count, so nobody is reminded who
+/// doesn't submit their time.
+fn unmarked_weeks(
+    data: &[TrackingEvent],
+    today: NaiveDate,
+    is_marked: impl Fn(&Metadata) -> bool,
+) -> Vec<NaiveDate> {
+    let monday =
+        |date: NaiveDate| date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+    let this_week = monday(today);
+    let mut weeks = Vec::new();
+    for start in data
+        .iter()
+        .filter(|event| event.is_start())
+        .skip_while(|event| !is_marked(event.meta()))
+        .filter(|event| !is_marked(event.meta()))
+    {
+        let week = monday(start.time(true).with_timezone(&Local).date().naive_local());
+        if week < this_week && !weeks.contains(&week) {
+            weeks.push(week);
+        }
+    }
+    weeks
+}
+
+fn warn_unsubmitted(output: &Output, data: &[TrackingEvent]) {
+    let weeks = unmarked_weeks(data, clock::today().naive_local(), |meta| meta.submitted);
+    if !weeks.is_empty() && !output.is_json() {
+        let weeks = weeks
+            .iter()
+            .map(|week| week.to_string())
+            .collect::<Vec<_>>();
+        eprintln!(
+            "The weeks starting on {} contain unsubmitted intervals. Use tt mark-submitted once \
+             they are submitted!",
+            weeks.join(", ")
+        );
+    }
+}
+
+fn delete(
+    output: &Output,
+    data: &mut Vec<TrackingEvent>,
+    filter: &FilterData,
+    yes: bool,
+) -> Result<bool> {
+    let selected = select_intervals(data, filter)?;
+    let deleted = data
+        .iter()
+        .zip(&selected)
+        .filter(|(_, selected)| **selected)
+        .map(|(event, _)| event.clone())
+        .collect::<Vec<_>>();
+    if deleted.is_empty() {
+        eprintln!("There are no entries to delete!");
+        return Ok(false);
+    }
+    if !output.is_json() {
+        for line in get_human_readable(&deleted) {
+            println!("{}", line);
+        }
+    }
+    if !yes
+        && !confirm(
+            &format!("Move {} entries to the trash?", deleted.len()),
+            false,
+        )
+    {
+        return Ok(false);
+    }
+    let mut selected = selected.into_iter();
+    data.retain(|_| !selected.next().unwrap_or(false));
+    Ok(true)
+}
+
+/// a duration with an optional sign, like "-1h" or "+30m"
+fn parse_offset(offset: &str) -> Result<Duration, Error> {
+    let (negative, duration) = match offset.strip_prefix('-') {
+        Some(duration) => (true, duration),
+        None => (false, offset.strip_prefix('+').unwrap_or(offset)),
+    };
+    let duration = duration_parse::parse(duration)?;
+    Ok(iif!(negative, -duration, duration))
+}
+
+/// sets the shares of the interval which starts at the time
+fn allocate(
+    output: &Output,
+    data: &mut [TrackingEvent],
+    start: &str,
+    shares: &[String],
+) -> Result<bool> {
+    let shares = allocation::parse(shares)?;
+    let index = match start {
+        "last" => data.iter().rposition(TrackingEvent::is_start),
+        start => {
+            let time = parse_date_time(start)?;
+            data.iter().position(|event| {
+                event.is_start() && event.time(false) == time.with_second(0).unwrap()
+            })
+        }
+    }
+    .with_context(|| format!("There is no interval which starts at {}!", start))?;
+    let stop = data[index + 1..]
+        .iter()
+        .find(|event| event.is_stop())
+        .map_or_else(clock::now, |stop| stop.time(false));
+    let event = &mut data[index];
+    let time = stop - event.time(false);
+    let parts = allocation::split(time, &shares)
+        .into_iter()
+        .map(|(project, time)| {
+            let (hours, minutes, _) = split_duration(time);
+            (
+                project.to_string(),
+                duration_output("{hh}:{mm}", hours, minutes, 0),
+            )
+        })
+        .collect::<Vec<_>>();
+    let text = match parts.as_slice() {
+        [] => format!(
+            "The interval at {} isn't split anymore",
+            format_date_time(event.time(false))
+        ),
+        parts => format!(
+            "Split the interval at {}: {}",
+            format_date_time(event.time(false)),
+            parts
+                .iter()
+                .map(|(project, time)| format!("{} {}", project, time.formatted))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let changed = event.meta().allocation != shares;
+    if let TrackingEvent::Start(TrackingData { meta, .. }) = event {
+        meta.allocation = shares;
+    }
+    if !output.is_json() {
+        println!("{}", text);
+    }
+    Ok(changed)
+}
+
+/// moves the selected entries and their interruptions by the offset, after showing the changes
+fn shift(
+    output: &Output,
+    data: &mut Vec<TrackingEvent>,
+    filter: &FilterData,
+    by: &str,
+    dry_run: bool,
+    yes: bool,
+) -> Result<bool> {
+    let offset = parse_offset(by)?;
+    if offset.is_zero() {
+        anyhow::bail!("The entries can't be shifted by zero!");
+    }
+    let selected = select_intervals(data, filter)?;
+    let count = selected.iter().filter(|selected| **selected).count();
+    if count == 0 {
+        eprintln!("There are no entries to shift!");
+        return Ok(false);
+    }
+    let mut shifted = data.clone();
+    for (event, _) in shifted
+        .iter_mut()
+        .zip(&selected)
+        .filter(|(_, selected)| **selected)
+    {
+        match event {
+            TrackingEvent::Start(TrackingData { time, meta, .. })
+            | TrackingEvent::Stop(TrackingData { time, meta, .. }) => {
+                *time = *time + offset;
+                for interruption in &mut meta.interruptions {
+                    interruption.time = interruption.time + offset;
+                }
             }
+        }
+    }
+    shifted.sort_by_key(|event| event.time(true));
+    if overlaps::groups(&shifted).len() > overlaps::groups(data).len() {
+        return Err(Error::Conflict(
+            "The shifted entries would overlap with other entries!".to_string(),
+        )
+        .into());
+    }
+    if !output.is_json() {
+        let color = output.use_color();
+        for line in diff::render(data, &shifted, color) {
+            println!("{}", line);
+        }
+    }
+    if dry_run {
+        return Ok(false);
+    }
+    if !yes && !confirm(&format!("Shift {} entries by {}?", count, by), false) {
+        return Ok(false);
+    }
+    *data = shifted;
+    Ok(true)
+}
+
+fn show_paths(
+    output: &Output,
+    settings: &Settings,
+    data_file: &str,
+    source: paths::Source,
+) -> Result<()> {
+    let global_config = paths::global_config_file();
+    let is_legacy = global_config != paths::config_file();
+    let xdg_config = iif!(
+        std::env::var_os("XDG_CONFIG_HOME").is_some(),
+        "in $XDG_CONFIG_HOME",
+        "in the home directory, as XDG_CONFIG_HOME is not set"
+    );
+    let project_config = iif!(
+        settings.enable_project_settings,
+        settings::project_config_file(),
+        None
+    );
+    let mut files = vec![("Data File", Some(data_file.into()), source.explanation())];
+    for (_, path) in iif!(
+        settings.per_year_files,
+        storage::shard_paths(data_file)?,
+        Vec::new()
+    ) {
+        files.push(("Per Year File", Some(path), "next to the data file"));
+    }
+    files.extend(vec![
+        ("Journal", Some(Journal::path(data_file)), "undo and redo"),
+        ("Trash", Some(Trash::path(data_file)), "removed entries"),
+        (
+            "Offline Periods",
+            Some(offline::path(data_file)),
+            "for `tt offline`",
+        ),
+        (
+            "Planned Intervals",
+            Some(planned::path(data_file)),
+            "for `tt record --planned`",
+        ),
+        ("History", history::path(), "for `tt last`"),
+        (
+            "Lease",
+            Some(lease::path(data_file)),
+            "while a command changes the data",
+        ),
+        (
+            "Day Index",
+            Some(DayIndex::path(data_file)),
+            "tracked time per day",
+        ),
+        (
+            "Change Feed",
+            Some(changes::path(data_file)),
+            "for `tt changes`",
+        ),
+        (
+            "Closed Months",
+            Some(lock::path(data_file)),
+            "for `tt close-month`",
+        ),
+        (
+            "Audit Log",
+            Some(lock::audit_path(data_file)),
+            "changes of closed months",
+        ),
+        (
+            "Legacy Data File",
+            paths::legacy_data_file().filter(|legacy| legacy.is_file()),
+            "kept as a backup after the migration",
+        ),
+        (
+            "Global Config",
+            global_config,
+            iif!(is_legacy, "in the legacy location", xdg_config),
+        ),
+        ("Project Config", project_config, "in the closest directory"),
+        (
+            "Local Config",
+            settings::local_config_file(),
+            "in the current directory",
+        ),
+        (
+            "Scripts",
+            paths::config_dir().map(|dir| dir.join("scripts")),
+            "for `tt report --script`",
+        ),
+    ]);
+    let files = files
+        .into_iter()
+        .filter_map(|(name, path, explanation)| Some((name, path?, explanation)))
+        .collect::<Vec<_>>();
+    let width = files
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut lines = Vec::new();
+    let mut json = Vec::new();
+    for (name, path, explanation) in &files {
+        let exists = path.exists();
+        lines.push(format!(
+            "{:width$} {}  ({}{})",
+            format!("{}:", name),
+            path.display(),
+            explanation,
+            iif!(exists, "", ", doesn't exist"),
+            width = width
+        ));
+        json.push(serde_json::json!({
+            "name": name.to_lowercase().replace(' ', "_"),
+            "path": path,
+            "exists": exists,
+        }));
+    }
+    let mut variables = std::env::vars()
+        .map(|(name, _)| name)
+        .filter(|name| {
+            name.starts_with("TT_") || name == "XDG_CONFIG_HOME" || name == "XDG_DATA_HOME"
         })
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+    variables.sort();
+    if !variables.is_empty() {
+        lines.push(format!("Environment: {}", variables.join(", ")));
+    }
+    output.print_lines(
+        &lines,
+        &serde_json::json!({
+            "files": json,
+            "data_file_source": source,
+            "environment": variables,
+        }),
+    );
+    Ok(())
 }
 
-fn export_human_readable(path: String, data: &[TrackingEvent]) {
-    let lines = get_human_readable(data);
-    std::fs::write(path, lines.join("\n")).expect("could not export file");
+fn list_changes(output: &Output, path: &Path, since: u64) -> Result<()> {
+    let changes = changes::since(path, since)?;
+    let mut lines = Vec::new();
+    for change in &changes {
+        let operation = &change.operation;
+        lines.push(format!(
+            "({}) {} by \"{}\"",
+            change.sequence,
+            operation
+                .time
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            operation.command
+        ));
+        for (sign, events) in [("+", &operation.added), ("-", &operation.removed)] {
+            lines.extend(
+                get_human_readable(events)
+                    .into_iter()
+                    .map(|line| format!("    {} {}", sign, line)),
+            );
+        }
+    }
+    let cursor = changes.last().map_or(since, |change| change.sequence);
+    output.print_lines(
+        &lines,
+        &serde_json::json!({ "changes": changes, "cursor": cursor }),
+    );
+    Ok(())
 }
 
-fn main() -> Result<()> {
-    let Options { command, data_file } = Options::from_args();
+/// lists the commands of the history, the latest first, or runs the latest start or stop again
+fn last(output: &Output, repeat: bool) -> Result<()> {
+    let commands = match history::path() {
+        Some(path) => history::read(&path)?,
+        None => Vec::new(),
+    };
+    if !repeat {
+        let lines = commands
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, args)| format!("({}) tt {}", index, history::display(args)))
+            .collect::<Vec<_>>();
+        let commands = commands.iter().rev().collect::<Vec<_>>();
+        output.print_lines(&lines, &serde_json::json!({ "commands": commands }));
+        return Ok(());
+    }
+    let args = commands
+        .iter()
+        .rev()
+        .find(|args| {
+            matches!(
+                Options::from_iter_safe(std::iter::once(&"tt".to_string()).chain(args.iter())),
+                Ok(Options {
+                    command: Some(Command::Start { .. } | Command::Stop { .. }),
+                    ..
+                })
+            )
+        })
+        .ok_or_else(|| {
+            Error::InvalidState("There is no start or stop in the history to repeat!".to_string())
+        })?;
+    eprintln!("Repeating: tt {}", history::display(args));
+    let status = std::process::Command::new(std::env::current_exe()?)
+        .args(args)
+        .status()?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+fn trash_command(
+    output: &Output,
+    data: &mut Vec<TrackingEvent>,
+    command: TrashCommand,
+    trash_path: &Path,
+    remaining_trash: &mut Option<Trash>,
+) -> Result<bool> {
+    let mut trash = Trash::read(trash_path)?;
+    match command {
+        TrashCommand::List => {
+            let mut lines = Vec::new();
+            for (index, entry) in trash.entries.iter().enumerate() {
+                lines.push(format!(
+                    "({}) deleted at {} by \"{}\"",
+                    index,
+                    entry
+                        .deleted_at
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    entry.command
+                ));
+                lines.extend(
+                    get_human_readable(&entry.events)
+                        .into_iter()
+                        .map(|line| format!("    {}", line)),
+                );
+            }
+            let entries = trash
+                .entries
+                .iter()
+                .map(|entry| {
+                    serde_json::json!({
+                        "deleted_at": entry.deleted_at.with_timezone(&Local),
+                        "command": entry.command,
+                        "events": output::events(&entry.events),
+                    })
+                })
+                .collect::<Vec<_>>();
+            output.print_lines(&lines, &entries);
+            Ok(false)
+        }
+        TrashCommand::Restore { index } => {
+            let entry = trash.restore(index)?;
+            data.extend(entry.events);
+            *remaining_trash = Some(trash);
+            Ok(true)
+        }
+        TrashCommand::Empty { yes } => {
+            let count = trash
+                .entries
+                .iter()
+                .map(|entry| entry.events.len())
+                .sum::<usize>();
+            if count == 0 {
+                eprintln!("The trash is already empty!");
+                return Ok(false);
+            }
+            if !yes && !confirm(&format!("Remove {} entries permanently?", count), false) {
+                return Ok(false);
+            }
+            Trash::default().write(trash_path)?;
+            output.print_lines(&[], &serde_json::json!({ "removed": count }));
+            Ok(false)
+        }
+    }
+}
+
+/// prints which commands were undone or redone and returns them as outcome
+fn report_replayed(output: &Output, action: &str, operations: &[Operation]) -> serde_json::Value {
+    if operations.is_empty() {
+        eprintln!("There is nothing to {}!", action);
+    } else if !output.is_json() {
+        let verb = iif!(action == "undo", "Undid", "Redid");
+        for operation in operations {
+            println!("{} \"{}\"", verb, operation.command);
+        }
+    }
+    let commands = operations.iter().map(|o| &o.command).collect::<Vec<_>>();
+    serde_json::json!({ action: commands })
+}
 
-    let settings = Settings::new()?;
+fn main() -> Result<()> {
+    // the aliases are needed to parse the arguments, errors in the config are reported after
+    let settings = Settings::new();
+    let args = std::env::args_os()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect();
+    let args = match settings.as_ref().map(|settings| &settings.aliases) {
+        Ok(aliases) if !aliases.is_empty() => alias::expand(args, aliases),
+        _ => args,
+    };
+    let args = time_range::expand(args);
+    let options = Options::from_iter_safe(&args);
+    // tt last isn't recorded, so tt last --repeat finds the command before it
+    if !matches!(
+        options,
+        Ok(Options {
+            command: Some(Command::Last { .. }),
+            ..
+        })
+    ) {
+        if let Some(path) = history::path() {
+            // a missing history isn't worth failing the command for
+            let _ = history::record(&path, &args[1..]);
+        }
+    }
+    let options = options.unwrap_or_else(|e| e.exit());
+    let Options {
+        command,
+        data_file,
+        output,
+        now,
+        read_only,
+        unlock,
+        no_pager,
+    } = options;
+    // tt tz shows the zone of the system next to the zone of the session
+    if !matches!(command, Some(Command::Tz { .. })) {
+        if let Some(zone) = zone::read_session()? {
+            zone::apply(&zone);
+        }
+    }
+    if let Some(now) = now {
+        clock::freeze(Some(parse_date_time(&now)?));
+    }
+    let output = Output::new(output);
+    let pages = matches!(
+        command,
+        Some(Command::List { follow: false, .. } | Command::Report { .. })
+    );
+    let output = iif!(pages && !no_pager, output.paged(), output);
 
-    let path = match data_file {
-        Some(path) => path,
-        None => shellexpand::full(&settings.data_file)?.parse()?,
+    // a broken config can be fixed or replaced, so it isn't read before
+    match &command {
+        Some(Command::Open { target }) if target == "config" => return open_config(&output),
+        Some(Command::Init { force }) => return init(&output, *force),
+        Some(Command::Last { repeat }) => return last(&output, *repeat),
+        Some(Command::Doctor) => return doctor(&output, settings.as_ref(), data_file),
+        _ => {}
+    }
+    let settings = settings?;
+    i18n::set(i18n::Language::parse(&settings.language).ok_or_else(|| {
+        Error::Parse(format!(
+            "unknown language \"{}\", expected \"en\", \"de\" or \"auto\"",
+            settings.language
+        ))
+    })?);
+    let read_only = read_only || settings.read_only;
+    let command = match command.unwrap_or_default() {
+        Command::Quick => quick_command()?,
+        command => command,
     };
+    if read_only && changes_data(&command) {
+        return Err(Error::InvalidState(
+            "This command would change the data, which is not allowed in read-only mode!"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let (path, source) = paths::resolve_data_file(data_file, settings.data_file.as_deref())?;
+    let is_default = matches!(source, paths::Source::XdgDataHome | paths::Source::Home);
+    if is_default && !read_only {
+        if let Some(legacy) = paths::migrate_legacy_data_file(&path)? {
+            eprintln!(
+                "Migrated data file from {} to {}",
+                legacy.display(),
+                path.display()
+            );
+        }
+    }
     let expanded_path = shellexpand::full(&path.to_string_lossy())
         .expect("could not expand path")
         .to_string();
-    let mut data = read_data(&expanded_path).unwrap_or_default();
+    // held until the changes are written, so other tt processes don't change the data or the
+    // files next to it meanwhile. It is taken before any of them is read.
+    let _lease = changes_data(&command)
+        .then(|| lease::acquire(&lease::path(&expanded_path)))
+        .transpose()?;
+    let backend = storage::backend(settings.storage.as_deref(), Path::new(&expanded_path))?;
+    let index_path = DayIndex::path(&expanded_path);
+    let day_index = DayIndex::read(
+        &index_path,
+        &data_files(&expanded_path, settings.per_year_files)?,
+    );
+    let indexed_days = match &day_index {
+        Some(index) => indexed_days(&command, index)?,
+        None => None,
+    };
+    let mut data = storage::read(
+        &*backend,
+        &expanded_path,
+        settings.per_year_files,
+        data_selection(&command, indexed_days.is_some())?,
+    )?;
+    let before = data.clone();
+    let mut command_outcome = None;
+    // printed after the changes are written
+    let mut receipt = Vec::new();
+    let journal_path = Journal::path(&expanded_path);
+    let trash_path = Trash::path(&expanded_path);
+    let planned_path = planned::path(&expanded_path);
+    // the plan without the started placeholder, written once the data is saved
+    let mut remaining_plan = None;
+    // whether the removed events are moved to the trash once the data is saved
+    let mut removed_to_trash = false;
+    // the trash without the restored entry, written once the data is saved
+    let mut remaining_trash = None;
+    // set by undo and redo, which change the journal instead of adding to it
+    let mut replayed_journal = None;
+    // the zone the days are split in, the same for every command
+    let zone = Local;
 
-    let data_changed = match command.unwrap_or_default() {
-        Command::Start { description, at } => {
-            start_tracking(&settings, &mut data, description, at)?;
+    let data_changed = match command {
+        Command::Start {
+            description,
+            context,
+            project,
+            suggest,
+            no_suggest,
+            template,
+            from_plan,
+            task,
+            estimate,
+            at,
+        } => {
+            let at = resolve_at(&output, &settings, &data, at, false)?;
+            let estimate = estimate.as_deref().map(duration_parse::parse).transpose()?;
+            let mut placeholders = None;
+            let template = match template {
+                Some(name) => settings.templates.get(&name).cloned().ok_or_else(|| {
+                    Error::Parse(format!("There is no template \"{}\" in the config", name))
+                })?,
+                None if from_plan => {
+                    let mut planned = planned::read(&planned_path)?;
+                    let next = planned::take_next(&mut planned, clock::now()).ok_or_else(|| {
+                        Error::InvalidState("There is no planned interval to start!".to_string())
+                    })?;
+                    placeholders = Some(planned);
+                    settings::Template {
+                        description: next.description,
+                        project: next.project,
+                        context: next.context,
+                        tags: next.tags,
+                    }
+                }
+                None => Default::default(),
+            };
+            let description = description.or(template.description);
+            let context = context.or(template.context);
+            let project = project.or(template.project);
+            let is_running = data.last().is_some_and(TrackingEvent::is_start);
+            let should_suggest = !no_suggest
+                && !is_running
+                && (suggest || settings.suggest_description && io::stdin().is_terminal());
+            let description = match description {
+                None if should_suggest => {
+                    let time = at.as_deref().map_or_else(
+                        || Ok(clock::local_now()),
+                        |at| parse_date_time(at).map(|time| time.with_timezone(&Local)),
+                    )?;
+                    suggest_description(&data, time)
+                }
+                description => description,
+            };
+            if let Some(warning) = check_description(&settings, description.as_deref())? {
+                if !output.is_json() {
+                    eprintln!("{}", warning);
+                }
+            }
+            if let Some(warning) = check_task(&settings, task.as_deref()) {
+                if !output.is_json() {
+                    eprintln!("{}", warning);
+                }
+            }
+            let outcome = start_tracking(
+                &settings,
+                &mut data,
+                description,
+                context,
+                project,
+                template.tags,
+                task,
+                estimate,
+                at,
+            )?;
+            // the placeholder is only removed once the interval is started
+            if outcome.warning().is_none() {
+                remaining_plan = placeholders;
+            }
+            if let Some(warning) = outcome.warning().filter(|_| !output.is_json()) {
+                eprintln!("{}", warning);
+            }
+            command_outcome = serde_json::to_value(outcome).ok();
+            true
+        }
+        Command::Record {
+            range,
+            description,
+            context,
+            project,
+            tags,
+            planned: true,
+        } => {
+            let (start, stop) = record_range(&range)?;
+            if stop <= clock::now() {
+                return Err(Error::Conflict(format!(
+                    "Can't plan an interval which ended already at {}!",
+                    format_date_time(stop)
+                ))
+                .into());
+            }
+            let placeholder = planned::Placeholder {
+                start,
+                stop,
+                description,
+                project,
+                context,
+                tags,
+            };
+            let mut placeholders = planned::read(&planned_path)?;
+            planned::add(&mut placeholders, placeholder.clone())?;
+            planned::write(&planned_path, &placeholders)?;
+            output.print(
+                format!(
+                    "Planned {} - {}",
+                    format_date_time(start),
+                    format_date_time(stop)
+                ),
+                &placeholder,
+            );
+            false
+        }
+        Command::Record {
+            range,
+            description,
+            context,
+            project,
+            tags,
+            planned: false,
+        } => {
+            let (start, stop) = record_interval(
+                &settings,
+                &mut data,
+                &range,
+                description,
+                context,
+                project,
+                tags,
+            )?;
+            command_outcome = Some(serde_json::json!({
+                "outcome": "recorded",
+                "start": start,
+                "stop": stop,
+            }));
+            true
+        }
+        Command::Stop {
+            description,
+            at,
+            quiet,
+        } => {
+            let at = resolve_at(&output, &settings, &data, at, true)?;
+            let outcome = stop_tracking(&mut data, description, at)?;
+            if let Some(warning) = outcome.warning().filter(|_| !output.is_json()) {
+                eprintln!("{}", warning);
+            }
+            let summary = match outcome {
+                StopOutcome::Stopped { index, .. } => stop_summary(&settings, &data, index, &zone)?,
+                StopOutcome::NotRunning { .. } => None,
+            };
+            command_outcome = serde_json::to_value(outcome).ok();
+            if let (Some(outcome), Some((lines, json))) = (&mut command_outcome, summary) {
+                outcome["summary"] = json;
+                receipt = iif!(quiet, Vec::new(), lines);
+            }
+            true
+        }
+        Command::Undo { steps } => {
+            let mut journal = Journal::read(&journal_path)?;
+            let operations = journal.undo(&mut data, steps)?;
+            command_outcome = Some(report_replayed(&output, "undo", &operations));
+            replayed_journal = Some(journal);
+            true
+        }
+        Command::Redo { steps } => {
+            let mut journal = Journal::read(&journal_path)?;
+            let operations = journal.redo(&mut data, steps)?;
+            command_outcome = Some(report_replayed(&output, "redo", &operations));
+            replayed_journal = Some(journal);
             true
         }
-        Command::Stop { description, at } => {
-            stop_tracking(&mut data, description, at)?;
+        Command::Interrupt { note, at } => {
+            let at = resolve_at(&output, &settings, &data, at, true)?;
+            interrupt(&mut data, note, at)?;
             true
         }
-        Command::Continue => {
-            continue_tracking(&mut data);
+        Command::Continue {
+            description,
+            project,
+            context,
+            tags,
+        } => {
+            let overrides = ContinueOverrides {
+                description,
+                project,
+                context,
+                tags,
+            };
+            let outcome = continue_tracking(&settings, &mut data, overrides);
+            if let Some(warning) = outcome.warning().filter(|_| !output.is_json()) {
+                eprintln!("{}", warning);
+            }
+            command_outcome = serde_json::to_value(outcome).ok();
             true
         }
-        Command::List { filter } => {
-            let data = filter_events(&data, &filter.from, &filter.to, &filter.filter)?;
-            for s in get_human_readable(&data) {
-                println!("{}", s);
+        Command::List {
+            filter,
+            event_local,
+            cumulative,
+            follow,
+        } => {
+            let shown = apply_filter(&data, &filter)?;
+            let (lines, events) = list_lines(&shown, event_local, cumulative);
+            output.print_lines(&lines, &events);
+            if follow {
+                let files = || data_files(&expanded_path, settings.per_year_files);
+                let selection =
+                    get_filter_range(&filter)?.map_or(storage::Selection::All, |(from, to)| {
+                        storage::Selection::Range(from.with_timezone(&Utc), to.with_timezone(&Utc))
+                    });
+                let mut states = file_states(&files()?);
+                let mut shown = shown;
+                loop {
+                    std::thread::sleep(std::time::Duration::from_secs(1));
+                    let current = file_states(&files()?);
+                    if current == states {
+                        continue;
+                    }
+                    // the file may be read while it is written, then it is read again the next time
+                    let data = match storage::read(
+                        &*backend,
+                        &expanded_path,
+                        settings.per_year_files,
+                        selection,
+                    ) {
+                        Ok(data) => data,
+                        Err(_) => continue,
+                    };
+                    states = current;
+                    let filtered = apply_filter(&data, &filter)?;
+                    let unchanged = shown
+                        .iter()
+                        .zip(&filtered)
+                        .take_while(|(shown, event)| shown == event)
+                        .count();
+                    let (lines, events) = list_lines(&filtered, event_local, cumulative);
+                    for (line, event) in lines.iter().zip(&events).skip(unchanged) {
+                        output.print(line, event);
+                    }
+                    shown = filtered;
+                }
             }
             false
         }
+        Command::Tz { command } => {
+            tz(&output, command)?;
+            false
+        }
         Command::Path => {
-            println!("{}", expanded_path);
+            let legacy = paths::legacy_data_file()
+                .filter(|legacy| legacy.is_file() && legacy != Path::new(&expanded_path));
+            let shards = iif!(
+                settings.per_year_files,
+                storage::shard_paths(&expanded_path)?,
+                Vec::new()
+            );
+            output.print(
+                &expanded_path,
+                &serde_json::json!({
+                    "data_file": expanded_path,
+                    "source": source,
+                    "legacy_data_file": legacy,
+                    "per_year_files": shards.into_iter().map(|(_, path)| path).collect::<Vec<_>>(),
+                }),
+            );
+            if !output.is_json() {
+                eprintln!("The data file is {}.", source.explanation());
+            }
+            if let Some(legacy) = legacy {
+                eprintln!(
+                    "The legacy data file still exists at {}. It can be removed once the migrated \
+                     data has been checked.",
+                    legacy.display()
+                );
+            }
+            false
+        }
+        Command::Paths => {
+            show_paths(&output, &settings, &expanded_path, source)?;
             false
         }
+        // the config was opened before reading the settings
+        Command::Open { .. } => {
+            let changed = open_data(&mut data)?;
+            removed_to_trash = changed;
+            changed
+        }
         Command::Show {
             format,
             filter,
             include_seconds,
             plain,
             remaining,
+            by_context,
+            by_project,
+            depth,
+            timeline,
+            except,
         } => {
+            let filter = FilterData { except, ..filter };
+            let grouping = match (by_context, by_project) {
+                (true, _) => Some(Grouping::Context),
+                (_, true) => Some(Grouping::Project(depth)),
+                _ => None,
+            };
             show(
+                &output,
                 &settings,
                 &data,
                 &filter,
@@ -847,46 +6817,320 @@ fn main() -> Result<()> {
                 include_seconds,
                 plain,
                 remaining,
+                grouping,
+                timeline,
+                indexed_days,
+                &zone,
             )?;
             false
         }
-        Command::Status => {
-            status(&data);
+        Command::Status { quiet } => {
+            if !quiet {
+                warn_unsubmitted(&output, &data);
+            }
+            status(&output, &settings, &data, quiet, &zone)?;
+            false
+        }
+        Command::Report {
+            filter,
+            gaps,
+            on_call,
+            gantt,
+            interruptions,
+            cumulative,
+            diff_against,
+            commits,
+            script,
+            plan,
+            estimates,
+            plan_view,
+            except,
+        } => {
+            let filter = FilterData { except, ..filter };
+            warn_unsubmitted(&output, &data);
+            if plan_view {
+                let placeholders = planned::read(&planned_path)?;
+                report_plan_view(&output, &data, &filter, &placeholders)?;
+            } else if estimates {
+                report_estimates(&output, &data, &filter)?;
+            } else if let Some(plan) = plan {
+                report_plan(&output, &settings, &data, &filter, &plan, &zone)?;
+            } else if let Some(script) = script {
+                report_script(&settings, &data, &filter, &script, &zone)?;
+            } else if let Some(exported) = diff_against {
+                report_diff(&output, &data, &filter, &exported)?;
+            } else if !commits.is_empty() {
+                report_commits(&output, &data, &filter, &commits)?;
+            } else if gaps {
+                report_gaps(&output, &settings, &data, &filter)?;
+            } else if interruptions {
+                report_interruptions(&output, &data, &filter, &zone)?;
+            } else if gantt {
+                report_gantt(&output, &data, &filter)?;
+            } else if on_call {
+                report_on_call(&output, &settings, &data, &filter)?;
+            } else {
+                let offline = offline::read(offline::path(&expanded_path))?;
+                report(
+                    &output, &settings, &data, &filter, cumulative, &offline, &zone,
+                )?;
+            }
+            false
+        }
+        Command::Predict => {
+            predict(&output, &settings, &data, &zone)?;
+            false
+        }
+        Command::Stats { streaks, switches } => {
+            stats(&output, &settings, &data, streaks, switches, &zone)?;
             false
         }
         Command::Cleanup => {
             data = cleanup(&data);
+            removed_to_trash = true;
             true
         }
+        Command::Check {
+            audit: Some(audit), ..
+        } => {
+            check_audit(&output, &audit)?;
+            false
+        }
+        Command::Check {
+            fix_overlaps,
+            fix_duplicates,
+            ..
+        } => {
+            let changed = check(
+                &output,
+                &settings,
+                &mut data,
+                fix_overlaps.as_deref(),
+                fix_duplicates,
+            )?;
+            removed_to_trash = changed;
+            changed
+        }
+        Command::Calc { expression, format } => {
+            let result = calc::evaluate(&expression)?;
+            let negative = result < Duration::zero();
+            let (hours, minutes, seconds) = split_duration(iif!(negative, -result, result));
+            let format = format
+                .unwrap_or_else(|| iif!(seconds == 0, "{hh}:{mm}", "{hh}:{mm}:{ss}").to_string());
+            let duration = duration_output(&format, hours, minutes, seconds);
+            output.print(
+                format!("{}{}", iif!(negative, "-", ""), duration.formatted),
+                &serde_json::json!({ "negative": negative, "duration": duration }),
+            );
+            false
+        }
+        Command::Classify { filter, apply } => {
+            classify(&output, &settings, &mut data, &filter, apply)?
+        }
+        Command::DedupeDescriptions {
+            filter,
+            max_distance,
+            yes,
+        } => dedupe_descriptions(&output, &mut data, &filter, max_distance, yes)?,
+        Command::CloseMonth { month } => {
+            close_month(&output, &lock::path(&expanded_path), month)?;
+            false
+        }
+        Command::MarkSubmitted { filter } => mark_submitted(&mut data, &filter)?,
+        Command::Approve { filter, reject } => approve(&mut data, &filter, reject)?,
+        Command::Delete { filter, yes } => {
+            removed_to_trash = delete(&output, &mut data, &filter, yes)?;
+            removed_to_trash
+        }
+        Command::Shift {
+            filter,
+            by,
+            dry_run,
+            yes,
+        } => shift(&output, &mut data, &filter, &by, dry_run, yes)?,
+        Command::Allocate { start, shares } => allocate(&output, &mut data, &start, &shares)?,
+        Command::Trash { command } => trash_command(
+            &output,
+            &mut data,
+            command,
+            &trash_path,
+            &mut remaining_trash,
+        )?,
+        Command::EmailReport { week, to, dry_run } => {
+            email_report(&output, &settings, &data, &week, to, dry_run, &zone)?;
+            false
+        }
+        Command::Offline { command } => {
+            offline_command(&output, &offline::path(&expanded_path), command)?;
+            false
+        }
+        Command::Changes { since } => {
+            list_changes(&output, &changes::path(&expanded_path), since)?;
+            false
+        }
+        Command::Export {
+            target:
+                Some(ExportTarget::TogglApi {
+                    filter,
+                    dry_run,
+                    yes,
+                }),
+            ..
+        } => {
+            export_toggl(&output, &settings, &data, &filter, dry_run, yes)?;
+            false
+        }
+        Command::Export { path: None, .. } => {
+            anyhow::bail!(i18n::text("Please specify the file to export to!"));
+        }
+        Command::Export {
+            diagram: None,
+            audit: false,
+            range,
+            ..
+        } if !range.is_empty() => {
+            anyhow::bail!("--range only works with --diagram or --audit");
+        }
+        Command::Export {
+            path: Some(path),
+            audit: true,
+            range,
+            ..
+        } => {
+            export_audit(&output, &path, &data, range)?;
+            false
+        }
+        Command::Export {
+            path: Some(path),
+            diagram: Some(diagram),
+            range,
+            anonymize,
+            ..
+        } => {
+            let data = iif!(anonymize, anonymize::anonymize(&data), data.clone());
+            export_diagram(&output, &settings, &path, &data, &diagram, range, &zone)?;
+            false
+        }
+        Command::Export {
+            path: Some(path),
+            json_lines: true,
+            anonymize,
+            ..
+        } => {
+            let data = iif!(anonymize, anonymize::anonymize(&data), data.clone());
+            export_json_lines(&output, &settings, &path, &data, &zone)?;
+            false
+        }
         #[cfg(not(feature = "binary"))]
-        Command::Export { path } => {
+        Command::Export {
+            path: Some(path),
+            anonymize,
+            ..
+        } => {
+            let data = iif!(anonymize, anonymize::anonymize(&data), data.clone());
             let expanded_path = shellexpand::full(&path.to_string_lossy())
                 .expect("could not expand path")
                 .to_string();
-            export_human_readable(expanded_path, &data);
+            export_human_readable(&expanded_path, &data);
+            output.print_lines(&[], &serde_json::json!({ "exported": expanded_path }));
             false
         }
 
         #[cfg(feature = "binary")]
         Command::Export {
-            path,
+            path: Some(path),
             readable,
             pretty,
+            anonymize,
+            ..
         } => {
+            let data = iif!(anonymize, anonymize::anonymize(&data), data.clone());
             let expanded_path = shellexpand::full(&path.to_string_lossy())
                 .expect("could not expand path")
                 .to_string();
             if readable {
-                export_human_readable(expanded_path, &data);
+                export_human_readable(&expanded_path, &data);
             } else {
-                write_json_data(expanded_path, &data, pretty);
+                write_json_data(&expanded_path, &data, pretty)?;
             }
+            output.print_lines(&[], &serde_json::json!({ "exported": expanded_path }));
             false
         }
-        #[cfg(feature = "binary")]
-        Command::Import { path } => {
-            data = read_json_data(path)?;
-            true
+        Command::Import {
+            source:
+                Some(ImportSource::Ics {
+                    path,
+                    from,
+                    to,
+                    filter,
+                    as_intervals,
+                    tags,
+                    yes,
+                }),
+            ..
+        } => import_ics(
+            &output,
+            &settings,
+            &mut data,
+            &path,
+            &FilterData {
+                from,
+                to,
+                filter,
+                ..FilterData::default()
+            },
+            as_intervals,
+            tags,
+            yes,
+        )?,
+        Command::Import {
+            source:
+                Some(ImportSource::Csv {
+                    path,
+                    map,
+                    date_format,
+                    delimiter,
+                    dry_run,
+                    tags,
+                    yes,
+                }),
+            ..
+        } => import_csv(
+            &output,
+            &settings,
+            &mut data,
+            &path,
+            &map,
+            date_format.as_deref(),
+            delimiter,
+            dry_run,
+            tags,
+            yes,
+        )?,
+        Command::Import {
+            source:
+                Some(ImportSource::TogglApi {
+                    filter,
+                    dry_run,
+                    yes,
+                }),
+            ..
+        } => import_toggl(&output, &settings, &mut data, &filter, dry_run, yes)?,
+        Command::Import {
+            path: Some(path),
+            yes,
+            ..
+        } => {
+            let offline = offline::read(offline::path(&expanded_path))?;
+            import_json(&output, &settings, &mut data, &path, &offline, yes)?
+        }
+        Command::Import { .. } => {
+            anyhow::bail!(i18n::text("Please specify the file to import!"));
+        }
+        #[cfg(feature = "credentials")]
+        Command::Auth { command } => {
+            auth(&output, command)?;
+            false
         }
         #[allow(unreachable_patterns)]
         _ => unimplemented!(),
@@ -895,35 +7139,95 @@ fn main() -> Result<()> {
     if data_changed {
         data.sort_by_key(|e| e.time(true));
         data.dedup();
-        write_data(expanded_path, &data);
+        if let Some(parent) = Path::new(&expanded_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let (added, removed) = diff::changes(&before, &data);
+        let operation = Operation {
+            command: command_line(),
+            time: clock::now(),
+            added,
+            removed,
+        };
+        if let Some(closed_until) = lock::read(lock::path(&expanded_path))? {
+            let cutoff = start_of_day(closed_until);
+            let changes_closed = operation
+                .added
+                .iter()
+                .chain(&operation.removed)
+                .any(|event| event.time(true) < cutoff);
+            if changes_closed && !unlock {
+                return Err(Error::Conflict(i18n::format(
+                    "The entries before {} are closed. Use --unlock to change them!",
+                    &[&closed_until],
+                ))
+                .into());
+            }
+            if changes_closed {
+                lock::audit(lock::audit_path(&expanded_path), &operation)?;
+            }
+        }
+        storage::write(&*backend, &expanded_path, &data, settings.per_year_files)?;
+        if let Some(placeholders) = remaining_plan {
+            planned::write(&planned_path, &placeholders)?;
+        }
+        if removed_to_trash {
+            let mut trash = Trash::read(&trash_path)?;
+            trash.add(command_line(), operation.removed.clone());
+            trash.write(&trash_path)?;
+        }
+        if let Some(trash) = remaining_trash {
+            trash.write(&trash_path)?;
+        }
+        let changed_days = operation
+            .added
+            .iter()
+            .chain(&operation.removed)
+            .map(day_index::date);
+        let mut day_index = match day_index {
+            Some(mut index) => {
+                index.update(&data, changed_days);
+                index
+            }
+            None => DayIndex::build(&data),
+        };
+        day_index.write(
+            &index_path,
+            &data_files(&expanded_path, settings.per_year_files)?,
+        )?;
+        let changes_output =
+            ChangesOutput::new(&operation.added, &operation.removed).with_outcome(command_outcome);
+        changes::append(&changes::path(&expanded_path), operation.clone())?;
+        let journal = match replayed_journal {
+            Some(journal) => journal,
+            None => {
+                let mut journal = Journal::read(&journal_path)?;
+                journal.record(operation);
+                journal
+            }
+        };
+        journal.write(&journal_path)?;
+        output.print_lines(&[], &changes_output);
+        if !output.is_json() && !output.is_plain() {
+            for line in receipt {
+                println!("{}", line);
+            }
+        }
     }
 
     Ok(())
 }
 
-fn parse_date_time(s: &str) -> Result<DateTime<Utc>> {
-    let from_time = |s: &str| NaiveTime::parse_from_str(s, "%H:%M:%S");
-    let from_date_time = |s: &str| Local.datetime_from_str(s, "%Y-%m-%d %H:%M:%S");
-
-    from_time(s)
-        .or_else(|_| from_time(&format!("{}:0", s)))
-        .or_else(|_| from_time(&format!("{}:0:0", s)))
-        .map_err(Into::into)
-        .and_then(|time| Local::today().and_time(time).context("invalid time"))
-        .or_else(|_| {
-            from_date_time(s)
-                .or_else(|_| from_date_time(&format!("{}:0", s)))
-                .or_else(|_| from_date_time(&format!("{}:0:0", s)))
-        })
-        .map(|date_time| date_time.with_timezone(&Utc))
-        .map_err(Into::into)
+fn parse_date_time(s: &str) -> Result<DateTime<Utc>, Error> {
+    engine::parse_date_time(s, clock::today().naive_local(), &Local)
+        .ok_or_else(|| Error::Parse(format!("Could not parse \"{}\" as a date or time", s)))
 }
 
-fn parse_date_or_date_time(s: &str) -> Result<DateOrDateTime> {
-    if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+fn parse_date_or_date_time(s: &str) -> Result<DateOrDateTime, Error> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
         return Ok(date.into());
     }
-    if let Ok(date_time) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S") {
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
         return Ok(date_time.into());
     }
 
@@ -933,19 +7237,29 @@ fn parse_date_or_date_time(s: &str) -> Result<DateOrDateTime> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_data::{at, interval, local, on, with_meta};
 
     #[test]
     fn test_parse_date_time() {
         assert_eq!(
-            Local::now().date().and_hms(0, 0, 15).with_timezone(&Utc),
+            clock::local_now()
+                .date()
+                .and_hms(0, 0, 15)
+                .with_timezone(&Utc),
             parse_date_time("00:00:15").unwrap()
         );
         assert_eq!(
-            Local::now().date().and_hms(0, 15, 0).with_timezone(&Utc),
+            clock::local_now()
+                .date()
+                .and_hms(0, 15, 0)
+                .with_timezone(&Utc),
             parse_date_time("00:15").unwrap()
         );
         assert_eq!(
-            Local::now().date().and_hms(15, 0, 0).with_timezone(&Utc),
+            clock::local_now()
+                .date()
+                .and_hms(15, 0, 0)
+                .with_timezone(&Utc),
             parse_date_time("15").unwrap()
         );
 
@@ -961,6 +7275,464 @@ mod tests {
             Local.ymd(2021, 4, 1).and_hms(15, 0, 0).with_timezone(&Utc),
             parse_date_time("2021-04-01 15").unwrap()
         );
+        assert_eq!(
+            Local.ymd(2021, 4, 1).and_hms(14, 30, 0).with_timezone(&Utc),
+            parse_date_time("2021-04-01 2:30 PM").unwrap()
+        );
+        assert!(parse_date_time("2021-04-01").is_err());
+        assert!(parse_date_time("25:00").is_err());
+    }
+
+    fn event(start: bool, hour: u32, context: Option<&str>) -> TrackingEvent {
+        let meta = Metadata {
+            context: context.map(str::to_string),
+            ..Metadata::default()
+        };
+        with_meta(test_data::event(start, at(hour, 0)), meta)
+    }
+
+    #[test]
+    fn test_filter_by_context() {
+        let data = vec![
+            event(true, 8, Some("office")),
+            event(false, 10, None),
+            event(true, 11, None),
+            event(false, 12, None),
+            event(true, 13, Some("office")),
+            event(false, 15, None),
+        ];
+        assert_eq!(
+            vec![
+                data[0].clone(),
+                data[1].clone(),
+                data[4].clone(),
+                data[5].clone()
+            ],
+            filter_by_context(&data, Some("office"))
+        );
+        assert_eq!(
+            vec![data[2].clone(), data[3].clone()],
+            filter_by_context(&data, None)
+        );
+    }
+
+    #[test]
+    fn test_stop_tracking() {
+        let mut data = vec![];
+        assert_eq!(
+            StopOutcome::NotRunning { since: None },
+            stop_tracking(&mut data, None, None).unwrap()
+        );
+
+        let mut data = vec![event(true, 8, None)];
+        assert!(matches!(
+            stop_tracking(&mut data, None, Some("2021-03-31 00:00".to_string())),
+            Err(Error::Conflict(_))
+        ));
+        assert_eq!(1, data.len());
+
+        let outcome = stop_tracking(&mut data, None, None).unwrap();
+        assert!(matches!(outcome, StopOutcome::Stopped { .. }));
+        assert_eq!(
+            StopOutcome::NotRunning {
+                since: Some(data[1].time(true))
+            },
+            stop_tracking(&mut data, None, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_continue_tracking() {
+        let settings = Settings::default();
+        let mut start = event(true, 8, Some("office"));
+        if let TrackingEvent::Start(data) = &mut start {
+            data.description = Some("review".to_string());
+            data.meta.project = Some("client-a".to_string());
+            data.meta.tags = vec!["billable".to_string()];
+            data.meta.submitted = true;
+        }
+        let mut data = vec![start, event(false, 10, None)];
+        continue_tracking(&settings, &mut data, ContinueOverrides::default());
+        let continued = data[2].meta();
+        assert_eq!(Some("review".to_string()), data[2].description());
+        assert_eq!(Some("client-a".to_string()), continued.project);
+        assert_eq!(vec!["billable".to_string()], continued.tags);
+        assert!(!continued.submitted);
+
+        data.push(event(false, 12, None));
+        let overrides = ContinueOverrides {
+            project: Some("internal".to_string()),
+            tags: vec!["meeting".to_string()],
+            ..ContinueOverrides::default()
+        };
+        continue_tracking(&settings, &mut data, overrides);
+        assert_eq!(Some("review".to_string()), data[4].description());
+        assert_eq!(Some("internal".to_string()), data[4].meta().project);
+        assert_eq!(vec!["meeting".to_string()], data[4].meta().tags);
+    }
+
+    #[test]
+    fn test_stop_summary() {
+        let settings = Settings::default();
+        let mut data = vec![];
+        let at = |time: &str| Some(format!("2021-04-01 {}", time));
+        start_tracking(
+            &settings,
+            &mut data,
+            Some("review".to_string()),
+            None,
+            None,
+            Vec::new(),
+            None,
+            None,
+            at("08:00"),
+        )
+        .unwrap();
+        let stop = |data: &mut Vec<TrackingEvent>, time| match stop_tracking(data, None, at(time))
+            .unwrap()
+        {
+            StopOutcome::Stopped { index, .. } => index,
+            outcome => panic!("{:?}", outcome),
+        };
+        let index = stop(&mut data, "09:30");
+        let (lines, json) = stop_summary(&settings, &data, index, &Local)
+            .unwrap()
+            .unwrap();
+        assert_eq!("Stopped \"review\": 08:00 - 09:30 (01:30)", lines[0]);
+        assert_eq!("01:30", json["work_time"]["formatted"]);
+
+        // the stop which was just inserted, not an earlier one at the same time
+        start_tracking(
+            &settings,
+            &mut data,
+            Some("call".to_string()),
+            None,
+            None,
+            Vec::new(),
+            None,
+            None,
+            at("09:30"),
+        )
+        .unwrap();
+        let index = stop(&mut data, "09:30");
+        let (lines, _) = stop_summary(&settings, &data, index, &Local)
+            .unwrap()
+            .unwrap();
+        assert_eq!("Stopped \"call\": 09:30 - 09:30 (00:00)", lines[0]);
+    }
+
+    #[test]
+    fn test_backdated_events_are_sorted() {
+        let settings = Settings::default();
+        let mut data = vec![];
+        let at = |time: &str| Some(format!("2021-04-01 {}", time));
+        let start = |data: &mut Vec<TrackingEvent>, time| {
+            start_tracking(
+                &settings,
+                data,
+                None,
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                at(time),
+            )
+        };
+        start(&mut data, "10:00").unwrap();
+        stop_tracking(&mut data, None, at("12:00")).unwrap();
+        // a start before the next start would be an interval without a stop
+        assert!(matches!(start(&mut data, "08:00"), Err(Error::Conflict(_))));
+        clock::freeze(Some(local(1, 12, 30)));
+        record_interval(&settings, &mut data, "8-9", None, None, None, Vec::new()).unwrap();
+        clock::freeze(None);
+        start(&mut data, "13:00").unwrap();
+
+        let times = data
+            .iter()
+            .map(|event| {
+                (
+                    event.is_start(),
+                    event.time(true).with_timezone(&Local).hour(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(true, 8), (false, 9), (true, 10), (false, 12), (true, 13)],
+            times
+        );
+
+        // inside of an interval, there is nothing to start or stop
+        assert!(matches!(start(&mut data, "11:00"), Err(Error::Conflict(_))));
+        assert!(matches!(
+            stop_tracking(&mut data, None, at("11:00")),
+            Err(Error::Conflict(_))
+        ));
+        assert_eq!(
+            StopOutcome::NotRunning {
+                since: Some(data[1].time(true))
+            },
+            stop_tracking(&mut data, None, at("09:30")).unwrap()
+        );
+        assert_eq!(5, data.len());
+    }
+
+    #[test]
+    fn test_resolve_at() {
+        let local = |hour, minute| local(1, hour, minute);
+        clock::freeze(Some(local(17, 5)));
+        let settings = Settings {
+            ambiguous_time: "snap".to_string(),
+            ..Settings::default()
+        };
+        let output = Output::new(OutputFormat::Json);
+        let data = vec![test_data::event(true, local(9, 0))];
+        let at = |time: &str| Some(time.to_string());
+        let resolve = |time, running| resolve_at(&output, &settings, &data, at(time), running);
+        assert_eq!(
+            Some(format_date_time(local(17, 0))),
+            resolve("5:00", true).unwrap()
+        );
+        // a start before the running interval fits as typed
+        assert_eq!(at("5:00"), resolve("5:00", false).unwrap());
+        assert_eq!(at("05:00"), resolve("05:00", true).unwrap());
+        clock::freeze(None);
+    }
+
+    #[test]
+    fn test_running_interval_ends_now() {
+        clock::freeze(Some(Utc.ymd(2021, 4, 1).and_hms(12, 30, 45)));
+        let data = vec![
+            event(true, 8, None),
+            event(false, 10, None),
+            event(true, 11, None),
+        ];
+        assert_eq!(
+            vec![
+                (data[0].time(true), data[1].time(true)),
+                (data[2].time(true), Utc.ymd(2021, 4, 1).and_hms(12, 30, 0)),
+            ],
+            get_intervals(&data, false)
+        );
+        clock::freeze(None);
+    }
+
+    #[test]
+    fn test_interrupt() {
+        let mut data = vec![event(true, 8, None), event(false, 10, None)];
+        let time = |hour| at(hour, 30);
+        let at = |hour| Some(format_date_time(time(hour)));
+        assert!(interrupt(&mut data, Some("phone call".to_string()), at(8)).is_ok());
+        assert!(matches!(
+            interrupt(&mut data, None, at(10)),
+            Err(Error::Conflict(_))
+        ));
+        assert_eq!(
+            vec![Interruption {
+                time: time(8),
+                note: Some("phone call".to_string()),
+            }],
+            data[0].meta().interruptions
+        );
+    }
+
+    #[test]
+    fn test_cumulative_times() {
+        // the totals start at zero each local day
+        let local = |start, hour| test_data::event(start, local(1, hour, 0));
+        let data = [
+            local(true, 8),
+            local(false, 10),
+            local(true, 11),
+            local(false, 12),
+        ];
+        assert_eq!(
+            vec![
+                None,
+                Some(Duration::hours(2)),
+                None,
+                Some(Duration::hours(3))
+            ],
+            cumulative_times(&data)
+        );
+    }
+
+    #[test]
+    fn test_days_and_work_time_in_the_same_zone() {
+        // both intervals are on 2021-04-02 two hours east, but on two days in utc
+        let east = FixedOffset::east(2 * 3600);
+        let data = [
+            interval(on(1, 22, 0), on(1, 23, 50), "coding"),
+            interval(on(2, 0, 0), on(2, 2, 0), "coding"),
+        ]
+        .concat();
+        assert_eq!(1, get_data_as_days(&data, &east).len());
+        let settings = Settings {
+            min_daily_break: 30,
+            ..Settings::default()
+        };
+        // the break of 10 minutes is 20 minutes too short
+        assert_eq!(
+            Duration::minutes(3 * 60 + 30),
+            get_time_from_events(&settings, &data, false, &east)
+        );
+        assert_eq!(
+            Duration::minutes(3 * 60 + 50),
+            get_time_from_events(&settings, &data, false, &Utc)
+        );
+    }
+
+    #[test]
+    fn test_unmarked_weeks() {
+        let start = |day, submitted| {
+            let meta = Metadata {
+                submitted,
+                ..Metadata::default()
+            };
+            with_meta(test_data::event(true, local(day, 8, 0)), meta)
+        };
+        let today = NaiveDate::from_ymd(2021, 4, 21);
+        let is_submitted = |meta: &Metadata| meta.submitted;
+        // nothing was ever submitted
+        assert!(unmarked_weeks(&[start(1, false)], today, is_submitted).is_empty());
+        let data = [
+            start(1, false),
+            start(6, true),
+            start(8, false),
+            start(13, false),
+            start(14, true),
+            start(20, false),
+        ];
+        assert_eq!(
+            vec![
+                NaiveDate::from_ymd(2021, 4, 5),
+                NaiveDate::from_ymd(2021, 4, 12)
+            ],
+            unmarked_weeks(&data, today, is_submitted)
+        );
+    }
+
+    #[test]
+    fn test_future_events() {
+        let event = |minute| test_data::event(true, at(12, minute));
+        let data = [event(0), event(34), event(40)];
+        clock::freeze(Some(at(12, 30)));
+        assert_eq!(&data[2..], future_events(&data, Duration::minutes(5)));
+        assert!(future_events(&data, Duration::minutes(10)).is_empty());
+        clock::freeze(None);
+    }
+
+    #[test]
+    fn test_validate_events() {
+        let event = |start, hour| test_data::event(start, at(hour, 0));
+        assert!(validate_events(&[]).is_ok());
+        assert!(validate_events(&[event(true, 8), event(false, 9), event(true, 10)]).is_ok());
+        assert!(validate_events(&[event(false, 8)]).is_err());
+        assert!(validate_events(&[event(true, 8), event(true, 9)]).is_err());
+        assert!(validate_events(&[event(true, 9), event(false, 8)]).is_err());
+    }
+
+    #[test]
+    fn test_check_description() {
+        let mut settings = Settings::default();
+        assert!(matches!(check_description(&settings, None), Ok(None)));
+
+        settings.description.template = Some(r"^[A-Z]+-\d+: .+".to_string());
+        assert!(matches!(
+            check_description(&settings, Some("PROJ-12: login")),
+            Ok(None)
+        ));
+        assert!(matches!(
+            check_description(&settings, Some("login")),
+            Ok(Some(_))
+        ));
+        settings.description.strict = true;
+        assert!(matches!(
+            check_description(&settings, None),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    /// "coding" from 8 to 9 and from 12 on, "meeting" from 10 to 11 on the first day, in the
+    /// local zone like the filters
+    fn coding_and_meeting() -> Vec<TrackingEvent> {
+        [
+            interval(local(1, 8, 0), local(1, 9, 0), "coding"),
+            interval(local(1, 10, 0), local(1, 11, 0), "meeting"),
+            vec![test_data::start(local(1, 12, 0), "coding")],
+        ]
+        .concat()
+    }
+
+    fn coding_filter() -> FilterData {
+        FilterData {
+            from: Some("2021-04-01".to_string()),
+            filter: Some("coding".to_string()),
+            ..FilterData::default()
+        }
+    }
+
+    #[test]
+    fn test_delete_whole_intervals() {
+        let output = Output::new(OutputFormat::Json);
+        let mut data = coding_and_meeting();
+        // the stops have no description, but belong to the selected starts
+        assert!(delete(&output, &mut data, &coding_filter(), true).unwrap());
+        assert_eq!(coding_and_meeting()[2..4], data[..]);
+        assert!(!delete(&output, &mut data, &coding_filter(), true).unwrap());
+    }
+
+    #[test]
+    fn test_shift_whole_intervals() {
+        let output = Output::new(OutputFormat::Json);
+        let mut data = coding_and_meeting();
+        assert!(shift(&output, &mut data, &coding_filter(), "30m", false, true).unwrap());
+        let mut expected = coding_and_meeting();
+        expected[0] = test_data::start(local(1, 8, 30), "coding");
+        expected[1] = test_data::event(false, local(1, 9, 30));
+        expected[4] = test_data::start(local(1, 12, 30), "coding");
+        assert_eq!(expected, data);
+    }
+
+    #[test]
+    fn test_parse_weekdays() {
+        use Weekday::*;
+        assert_eq!(
+            vec![Mon, Tue, Wed, Thu, Fri],
+            parse_weekdays("mon-fri").unwrap()
+        );
+        assert_eq!(
+            vec![Sat, Sun, Mon, Wed],
+            parse_weekdays("sat-mon,wed").unwrap()
+        );
+        assert!(parse_weekdays("mon-someday").is_err());
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_read_legacy_binary_data() {
+        #[derive(Serialize)]
+        struct OldTrackingData {
+            description: Option<String>,
+            #[serde(with = "ts_seconds")]
+            time: DateTime<Utc>,
+        }
+        #[derive(Serialize)]
+        enum OldTrackingEvent {
+            Start(OldTrackingData),
+        }
+
+        let path = std::env::temp_dir().join("timetracking_test_legacy.bin");
+        let old = vec![OldTrackingEvent::Start(OldTrackingData {
+            description: Some("test".to_string()),
+            time: Utc.ymd(2021, 4, 1).and_hms(8, 0, 0),
+        })];
+        std::fs::write(&path, bincode::serialize(&old).unwrap()).unwrap();
+        let data =
+            storage::Storage::read(&storage::Binary, &path, &storage::Selection::All).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(vec![test_data::start(at(8, 0), "test")], data);
     }
 
     #[test]
@@ -978,4 +7750,12 @@ mod tests {
             parse_date_or_date_time("2020-04-01 12").unwrap()
         );
     }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(Duration::hours(-1), parse_offset("-1h").unwrap());
+        assert_eq!(Duration::minutes(30), parse_offset("+30m").unwrap());
+        assert_eq!(Duration::minutes(90), parse_offset("1h30m").unwrap());
+        assert!(parse_offset("--1h").is_err());
+    }
 }
@@ -0,0 +1,127 @@
+//! collapses duplicate events, e.g. after importing or merging the data of two machines which
+//! both recorded the same start. A duplicate is an event of the same type with the same
+//! description as the previous kept event, at most the window after it. The first one is kept
+//! with its metadata, as it was recorded first.
+
+use crate::TrackingEvent;
+use chrono::Duration;
+
+/// the indexes of the duplicates in the sorted data. Each event is compared with the last kept
+/// one, so a chain of events a few seconds apart doesn't collapse into the first beyond the
+/// window.
+pub fn duplicates(data: &[TrackingEvent], window: Duration) -> Vec<usize> {
+    let mut duplicates = Vec::new();
+    let mut kept: Option<&TrackingEvent> = None;
+    for (index, event) in data.iter().enumerate() {
+        let duplicate = kept.is_some_and(|kept| {
+            kept.is_start() == event.is_start()
+                && kept.description() == event.description()
+                && event.time(true) - kept.time(true) <= window
+        });
+        if duplicate {
+            duplicates.push(index);
+        } else {
+            kept = Some(event);
+        }
+    }
+    duplicates
+}
+
+/// sorts the data and removes the duplicates, which are returned
+pub fn normalize(data: &mut Vec<TrackingEvent>, window: Duration) -> Vec<TrackingEvent> {
+    data.sort_by_key(|event| event.time(true));
+    let mut removed = Vec::new();
+    for index in duplicates(data, window).into_iter().rev() {
+        removed.push(data.remove(index));
+    }
+    removed.reverse();
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, start};
+
+    /// a start or stop at the second after 8:00
+    fn event(is_start: bool, second: i64, description: Option<&str>) -> TrackingEvent {
+        let time = at(8, 0) + Duration::seconds(second);
+        match description {
+            Some(description) => start(time, description),
+            None => crate::test_data::event(is_start, time),
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let window = Duration::seconds(5);
+        let mut data = vec![
+            event(true, 3, Some("work")),
+            event(true, 0, Some("work")),
+            // the same start from the other machine
+            event(true, 0, Some("work")),
+            event(false, 20, None),
+            event(false, 25, None),
+        ];
+        let removed = normalize(&mut data, window);
+        assert_eq!(
+            vec![
+                event(true, 0, Some("work")),
+                event(true, 3, Some("work")),
+                event(false, 25, None),
+            ],
+            removed
+        );
+        assert_eq!(
+            vec![event(true, 0, Some("work")), event(false, 20, None)],
+            data
+        );
+    }
+
+    #[test]
+    fn test_normalize_chain() {
+        // 36 is within the window of 32, but not of 30
+        let mut data = vec![
+            event(true, 30, Some("email")),
+            event(true, 32, Some("email")),
+            event(true, 36, Some("email")),
+        ];
+        let removed = normalize(&mut data, Duration::seconds(5));
+        assert_eq!(vec![event(true, 32, Some("email"))], removed);
+        assert_eq!(
+            vec![
+                event(true, 30, Some("email")),
+                event(true, 36, Some("email")),
+            ],
+            data
+        );
+    }
+
+    #[test]
+    fn test_normalize_different_events() {
+        // another description or type isn't a duplicate
+        let mut data = vec![
+            event(true, 40, Some("review")),
+            event(false, 41, None),
+            event(true, 42, Some("review")),
+            event(true, 43, Some("email")),
+        ];
+        let expected = data.clone();
+        assert!(normalize(&mut data, Duration::seconds(5)).is_empty());
+        assert_eq!(expected, data);
+    }
+
+    #[test]
+    fn test_duplicates_without_window() {
+        let data = [
+            event(true, 0, Some("work")),
+            event(true, 0, Some("work")),
+            event(true, 1, Some("work")),
+            event(true, 2, None),
+            event(true, 2, Some("work")),
+        ];
+        // only events at the same second collapse, and no description differs from one
+        assert_eq!(vec![1], duplicates(&data, Duration::zero()));
+        assert!(duplicates(&[], Duration::zero()).is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+//! the periods in which tt was offline on this machine, marked with `tt offline start` and
+//! `tt offline end`. The time in them is tracked on another machine and filled in later, so a
+//! gap in them is intentional instead of missing data. They are stored in a file next to the
+//! data file.
+
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Period {
+    pub start: DateTime<Utc>,
+    /// none while offline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Period {
+    /// the part of the period between from and to, a running period ends at now
+    pub fn overlap(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let (start, end) = (self.start.max(from), self.end.unwrap_or(now).min(to));
+        (start < end).then_some((start, end))
+    }
+}
+
+pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    let mut path = data_file.as_ref().as_os_str().to_owned();
+    path.push(".offline");
+    path.into()
+}
+
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Period>, Error> {
+    match std::fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+        Ok(content) => serde_json::from_str(&content).map_err(|_| {
+            Error::InvalidState(format!("could not read {}", path.as_ref().display()))
+        }),
+    }
+}
+
+pub fn write<P: AsRef<Path>>(path: P, periods: &[Period]) -> Result<(), Error> {
+    let content = serde_json::to_string_pretty(periods).expect("could not serialize periods");
+    Ok(std::fs::write(path, content)?)
+}
+
+/// starts a period, after the end of the last one
+pub fn start(periods: &mut Vec<Period>, time: DateTime<Utc>) -> Result<(), Error> {
+    match periods.last() {
+        Some(Period { end: None, .. }) => {
+            return Err(Error::InvalidState("Already offline!".to_string()))
+        }
+        Some(Period { end: Some(end), .. }) if *end > time => {
+            return Err(Error::Conflict(
+                "The offline period would start before the end of the last one!".to_string(),
+            ))
+        }
+        _ => {}
+    }
+    periods.push(Period {
+        start: time,
+        end: None,
+    });
+    Ok(())
+}
+
+/// ends the running period
+pub fn end(periods: &mut [Period], time: DateTime<Utc>) -> Result<(), Error> {
+    match periods.last_mut() {
+        Some(period) if period.end.is_none() => {
+            if period.start >= time {
+                return Err(Error::Conflict(
+                    "The offline period would end before it started!".to_string(),
+                ));
+            }
+            period.end = Some(time);
+            Ok(())
+        }
+        _ => Err(Error::InvalidState("Not offline!".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_periods() {
+        let at = |hour| Utc.ymd(2021, 4, 1).and_hms(hour, 0, 0);
+        let mut periods = Vec::new();
+        assert!(end(&mut periods, at(9)).is_err());
+        start(&mut periods, at(9)).unwrap();
+        assert!(start(&mut periods, at(10)).is_err());
+        assert!(end(&mut periods, at(8)).is_err());
+        end(&mut periods, at(12)).unwrap();
+        assert!(start(&mut periods, at(11)).is_err());
+        start(&mut periods, at(14)).unwrap();
+        assert_eq!(
+            Some((at(10), at(12))),
+            periods[0].overlap(at(10), at(13), at(20))
+        );
+        assert_eq!(None, periods[0].overlap(at(12), at(13), at(20)));
+        // the running period ends now
+        assert_eq!(
+            Some((at(14), at(16))),
+            periods[1].overlap(at(0), at(23), at(16))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum StartOutcome {
+    Started {
+        at: DateTime<Utc>,
+    },
+    /// the running interval was stopped and a new one started
+    Restarted {
+        at: DateTime<Utc>,
+    },
+    AlreadyRunning {
+        since: DateTime<Utc>,
+        description: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum StopOutcome {
+    Stopped {
+        at: DateTime<Utc>,
+        /// the index of the inserted stop in the data
+        #[serde(skip)]
+        index: usize,
+    },
+    /// there is nothing to stop. `since` is the time of the last stop, if there is one
+    NotRunning { since: Option<DateTime<Utc>> },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum ContinueOutcome {
+    Continued {
+        at: DateTime<Utc>,
+        description: Option<String>,
+    },
+    AlreadyRunning {
+        since: DateTime<Utc>,
+    },
+    /// there is no earlier interval to continue
+    NothingToContinue,
+}
+
+impl StartOutcome {
+    /// text for outcomes which didn't change anything
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Self::AlreadyRunning {
+                description: Some(description),
+                ..
+            } => Some(format!(
+                "Timetracking with the description \"{}\" is already running!",
+                description
+            )),
+            Self::AlreadyRunning { .. } => Some("Time tracking is already running!".to_string()),
+            Self::Started { .. } | Self::Restarted { .. } => None,
+        }
+    }
+}
+
+impl StopOutcome {
+    /// text for outcomes which didn't change anything
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Self::NotRunning { .. } => Some("Time tracking is already stopped!".to_string()),
+            Self::Stopped { .. } => None,
+        }
+    }
+}
+
+impl ContinueOutcome {
+    /// text for outcomes which didn't change anything
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Self::AlreadyRunning { .. } => Some("Time tracking is already running!".to_string()),
+            Self::NothingToContinue => Some(
+                "Time tracking couldn't be continued, because there are no entries. Use the start command instead!"
+                    .to_string(),
+            ),
+            Self::Continued { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::TrackingEvent;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// one fact of the json output per line, like "total: 08:00", without colors or charts
+    Plain,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "plain" => Ok(Self::Plain),
+            _ => Err(format!("unknown output format \"{}\"", s)),
+        }
+    }
+}
+
+/// json representation of an event, with the time in local time instead of a timestamp
+#[derive(Debug, Serialize)]
+pub struct EventOutput {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub time: DateTime<Local>,
+    pub description: Option<String>,
+    pub context: Option<String>,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allocation: Vec<crate::allocation::Share>,
+}
+
+impl From<&TrackingEvent> for EventOutput {
+    fn from(event: &TrackingEvent) -> Self {
+        Self {
+            kind: if event.is_start() { "start" } else { "stop" },
+            time: event.time(true).with_timezone(&Local),
+            description: event.description(),
+            context: event.context(),
+            project: event.project(),
+            tags: event.meta().tags.clone(),
+            task: event.meta().task.clone(),
+            estimate: event.meta().estimate,
+            allocation: event.meta().allocation.clone(),
+        }
+    }
+}
+
+pub fn events(data: &[TrackingEvent]) -> Vec<EventOutput> {
+    data.iter().map(Into::into).collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct DurationOutput {
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+    pub formatted: String,
+}
+
+/// result of commands which modify the data file
+#[derive(Debug, Serialize)]
+pub struct ChangesOutput {
+    pub changed: bool,
+    pub added: Vec<EventOutput>,
+    pub removed: Vec<EventOutput>,
+    /// what the command did, for commands reporting a typed outcome
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<serde_json::Value>,
+}
+
+impl ChangesOutput {
+    pub fn new(added: &[TrackingEvent], removed: &[TrackingEvent]) -> Self {
+        let added = events(added);
+        let removed = events(removed);
+        Self {
+            changed: !added.is_empty() || !removed.is_empty(),
+            added,
+            removed,
+            outcome: None,
+        }
+    }
+
+    pub fn with_outcome(self, outcome: Option<serde_json::Value>) -> Self {
+        Self { outcome, ..self }
+    }
+}
+
+/// every command reports its result through this, so it can be rendered as text or json
+pub struct Output {
+    format: OutputFormat,
+    /// the text which is shown in the pager at the end, see `paged`
+    paged: Option<RefCell<Vec<String>>>,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            paged: None,
+        }
+    }
+
+    /// collects the text output, and shows it through the pager if it doesn't fit on the screen
+    pub fn paged(mut self) -> Self {
+        if self.format == OutputFormat::Text && crate::pager::available() {
+            self.paged = Some(RefCell::new(Vec::new()));
+        }
+        self
+    }
+
+    fn println(&self, line: String) {
+        match &self.paged {
+            Some(lines) => lines.borrow_mut().push(line),
+            None => println!("{}", line),
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.format == OutputFormat::Plain
+    }
+
+    /// whether the text may be colored, which is never the case for json and plain output
+    pub fn use_color(&self) -> bool {
+        self.format == OutputFormat::Text && crate::diff::use_color()
+    }
+
+    pub fn print<T: Serialize + ?Sized>(&self, text: impl Display, value: &T) {
+        match self.format {
+            OutputFormat::Text => self.println(text.to_string()),
+            OutputFormat::Json => self.print_json(value),
+            OutputFormat::Plain => self.print_plain(value),
+        }
+    }
+
+    /// like print, but prints nothing at all in text mode, if there are no lines
+    pub fn print_lines<T: Serialize + ?Sized>(&self, lines: &[String], value: &T) {
+        match self.format {
+            OutputFormat::Text => {
+                for line in lines {
+                    self.println(line.clone());
+                }
+            }
+            OutputFormat::Json => self.print_json(value),
+            OutputFormat::Plain => self.print_plain(value),
+        }
+    }
+
+    fn print_plain<T: Serialize + ?Sized>(&self, value: &T) {
+        let value = serde_json::to_value(value).expect("could not serialize output");
+        for line in plain_lines(&value) {
+            println!("{}", line);
+        }
+    }
+
+    fn print_json<T: Serialize + ?Sized>(&self, value: &T) {
+        println!(
+            "{}",
+            serde_json::to_string(value).expect("could not serialize output")
+        );
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        if let Some(lines) = self.paged.take() {
+            crate::pager::show(&lines.into_inner());
+        }
+    }
+}
+
+/// a line per value of the json output, labeled with the keys leading to it and the positions
+/// in lists, like "days 1 work time: 08:00". Durations are shown formatted, lists of plain
+/// values on one line and empty values not at all.
+pub fn plain_lines(value: &serde_json::Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    collect_plain(String::new(), value, &mut lines);
+    lines
+}
+
+fn collect_plain(label: String, value: &serde_json::Value, lines: &mut Vec<String>) {
+    use serde_json::Value;
+
+    let child = |key: &str| {
+        let key = key.replace('_', " ");
+        iif::iif!(label.is_empty(), key.clone(), format!("{} {}", label, key))
+    };
+    let line = |text: String| {
+        iif::iif!(
+            label.is_empty(),
+            text.clone(),
+            format!("{}: {}", label, text)
+        )
+    };
+    match value {
+        Value::Null => {}
+        Value::Object(map) => match map.get("formatted") {
+            Some(Value::String(formatted)) => lines.push(line(formatted.clone())),
+            _ => {
+                for (key, value) in map {
+                    collect_plain(child(key), value, lines);
+                }
+            }
+        },
+        Value::Array(values)
+            if values
+                .iter()
+                .all(|value| !value.is_object() && !value.is_array()) =>
+        {
+            let values = values
+                .iter()
+                .filter(|value| !value.is_null())
+                .map(|value| match value {
+                    Value::String(text) => text.clone(),
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                lines.push(line(values.join(", ")));
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_plain(child(&(index + 1).to_string()), value, lines);
+            }
+        }
+        Value::String(text) => lines.push(line(text.clone())),
+        value => lines.push(line(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_lines() {
+        let value = serde_json::json!({
+            "days": [
+                {"date": "2021-04-01", "work_time": {"hours": 8, "minutes": 0, "formatted": "08:00"}},
+            ],
+            "on_call": null,
+            "tags": ["a", "b"],
+            "running": false,
+        });
+        assert_eq!(
+            vec![
+                "days 1 date: 2021-04-01",
+                "days 1 work time: 08:00",
+                "running: false",
+                "tags: a, b",
+            ],
+            plain_lines(&value)
+        );
+        assert_eq!(
+            vec!["1: start"],
+            plain_lines(&serde_json::json!([["start"]]))
+        );
+        assert_eq!(vec!["done"], plain_lines(&serde_json::json!("done")));
+    }
+}
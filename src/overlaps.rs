@@ -0,0 +1,88 @@
+//! finds intervals which overlap, e.g. after merging the data of two machines. As the events are
+//! sorted by time, overlapping intervals show up as several start events before their stops.
+
+use crate::TrackingEvent;
+
+use std::ops::Range;
+
+/// the ranges of events which belong to overlapping intervals
+pub fn groups(data: &[TrackingEvent]) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut depth = 0;
+    let mut max_depth = 0;
+    let mut first = 0;
+    for (index, event) in data.iter().enumerate() {
+        if event.is_start() {
+            if depth == 0 {
+                first = index;
+                max_depth = 0;
+            }
+            depth += 1;
+            max_depth = max_depth.max(depth);
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 && max_depth > 1 {
+                groups.push(first..index + 1);
+            }
+        }
+    }
+    if depth > 0 && max_depth > 1 {
+        groups.push(first..data.len());
+    }
+    groups
+}
+
+/// one interval covering the whole group, with the description and metadata of the first start
+/// event. A group which is still running stays running.
+pub fn union(group: &[TrackingEvent]) -> Vec<TrackingEvent> {
+    let mut merged = group[..1].to_vec();
+    let starts = group.iter().filter(|event| event.is_start()).count();
+    let stops = group.iter().filter(|event| event.is_stop()).count();
+    if starts == stops {
+        merged.extend(group.last().cloned());
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, event};
+
+    /// 8-9, then 10-12 on one machine and 11-13 on the other, then two starts running
+    fn data() -> Vec<TrackingEvent> {
+        vec![
+            event(true, at(8, 0)),
+            event(false, at(9, 0)),
+            event(true, at(10, 0)),
+            event(true, at(11, 0)),
+            event(false, at(12, 0)),
+            event(false, at(13, 0)),
+            event(true, at(14, 0)),
+            event(true, at(15, 0)),
+        ]
+    }
+
+    #[test]
+    fn test_groups() {
+        let data = data();
+        assert_eq!(vec![2..6, 6..8], groups(&data));
+        assert!(groups(&data[..2]).is_empty());
+        assert!(groups(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_union() {
+        let data = data();
+        assert_eq!(
+            vec![event(true, at(10, 0)), event(false, at(13, 0))],
+            union(&data[2..6])
+        );
+    }
+
+    #[test]
+    fn test_union_running() {
+        let data = data();
+        assert_eq!(vec![event(true, at(14, 0))], union(&data[6..8]));
+    }
+}
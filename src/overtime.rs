@@ -0,0 +1,76 @@
+//! how the difference between the tracked time and the weekly goal carries over to the next week
+
+use crate::duration_parse;
+use crate::error::Error;
+use chrono::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CarryOver {
+    /// every week starts at zero
+    Reset,
+    Full,
+    /// at most this much overtime or missing time is carried over
+    Cap(Duration),
+}
+
+impl CarryOver {
+    pub fn parse(policy: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::Parse(format!(
+                "invalid carry over policy \"{}\", expected \"reset\", \"full\" or a cap like \
+                 \"cap 10h\"",
+                policy
+            ))
+        };
+        match policy.trim() {
+            "reset" => Ok(Self::Reset),
+            "full" => Ok(Self::Full),
+            policy => {
+                let cap = policy.strip_prefix("cap ").ok_or_else(invalid)?;
+                Ok(Self::Cap(
+                    duration_parse::parse(cap.trim()).map_err(|_| invalid())?,
+                ))
+            }
+        }
+    }
+
+    fn apply(self, balance: Duration) -> Duration {
+        match self {
+            Self::Reset => Duration::zero(),
+            Self::Full => balance,
+            Self::Cap(cap) => balance.max(-cap).min(cap),
+        }
+    }
+}
+
+/// the balance which is carried into the current week, from the differences between the tracked
+/// time and the goal of the previous weeks, oldest first
+pub fn carried(differences: &[Duration], policy: CarryOver) -> Duration {
+    differences
+        .iter()
+        .fold(Duration::zero(), |balance, difference| {
+            policy.apply(balance + *difference)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_carried() {
+        let hours = Duration::hours;
+        assert_eq!(
+            CarryOver::Cap(hours(10)),
+            CarryOver::parse("cap 10h").unwrap()
+        );
+        assert!(CarryOver::parse("10h").is_err());
+
+        let differences = [hours(8), hours(6), hours(-3)];
+        assert_eq!(Duration::zero(), carried(&differences, CarryOver::Reset));
+        assert_eq!(hours(11), carried(&differences, CarryOver::Full));
+        // the 14 hours of the first two weeks are capped at 10
+        assert_eq!(hours(7), carried(&differences, CarryOver::Cap(hours(10))));
+        assert_eq!(Duration::zero(), carried(&[], CarryOver::Full));
+    }
+}
@@ -0,0 +1,94 @@
+//! shows long text output of `tt list` and `tt report` in the pager of the user, like git does.
+//! The pager is $PAGER, or less if it is not set. An empty $PAGER turns paging off.
+
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// the height of the screen if it can't be found out
+const DEFAULT_HEIGHT: usize = 24;
+
+/// output is only paged on a terminal
+pub fn available() -> bool {
+    io::stdout().is_terminal() && command().is_some()
+}
+
+fn command() -> Option<Vec<String>> {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let words = pager
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    (!words.is_empty()).then_some(words)
+}
+
+/// the lines of the terminal from $LINES or stty
+fn height() -> usize {
+    let lines = std::env::var("LINES").ok().or_else(|| {
+        let tty = File::open("/dev/tty").ok()?;
+        let size = Command::new("stty")
+            .arg("size")
+            .stdin(tty)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let size = String::from_utf8_lossy(&size.stdout).to_string();
+        size.split_whitespace().next().map(str::to_string)
+    });
+    lines
+        .and_then(|lines| lines.trim().parse().ok())
+        .unwrap_or(DEFAULT_HEIGHT)
+}
+
+/// whether the lines of text don't fit on the screen
+pub fn too_long(lines: &[String], height: usize) -> bool {
+    // the prompt takes a line after the output
+    lines
+        .iter()
+        .map(|line| line.lines().count().max(1))
+        .sum::<usize>()
+        >= height
+}
+
+/// prints the lines, through the pager if they don't fit on the screen
+pub fn show(lines: &[String]) {
+    if too_long(lines, height()) && page(lines).is_ok() {
+        return;
+    }
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+fn page(lines: &[String]) -> io::Result<()> {
+    let command = command().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+    let mut pager = Command::new(&command[0]);
+    pager.args(&command[1..]).stdin(Stdio::piped());
+    // colors are passed through, like git does
+    if std::env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+    let mut child = pager.spawn()?;
+    let mut stdin = child.stdin.take().expect("could not open stdin");
+    for line in lines {
+        // quitting the pager early closes its stdin
+        if writeln!(stdin, "{}", line).is_err() {
+            break;
+        }
+    }
+    drop(stdin);
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_too_long() {
+        let lines = vec!["a".to_string(), "b\nc".to_string()];
+        assert!(!too_long(&lines, 4));
+        assert!(too_long(&lines, 3));
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "binary")]
+const DATA_FILE_NAME: &str = "timetracking.bin";
+
+#[cfg(not(feature = "binary"))]
+const DATA_FILE_NAME: &str = "timetracking.json";
+
+/// where the path of the data file came from, in the order in which the sources are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// `--data-file`
+    Flag,
+    /// `TT_DATA_FILE`
+    Environment,
+    /// `data_file` in the config
+    Config,
+    /// `$XDG_DATA_HOME/timetracking`
+    XdgDataHome,
+    /// `~/.local/share/timetracking`
+    Home,
+}
+
+impl Source {
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Self::Flag => "given with --data-file",
+            Self::Environment => "set with TT_DATA_FILE",
+            Self::Config => "set with data_file in the config",
+            Self::XdgDataHome => "in the default location in $XDG_DATA_HOME",
+            Self::Home => {
+                "in the default location in the home directory, as XDG_DATA_HOME is not set"
+            }
+        }
+    }
+}
+
+/// the home directory. There is no fallback to the working directory, as that would scatter the
+/// data over whichever directories tt is called from.
+fn home_dir() -> Option<PathBuf> {
+    dirs::home_dir().filter(|home| !home.as_os_str().is_empty())
+}
+
+/// resolves a xdg base directory. Relative paths are invalid according to the spec and ignored.
+fn xdg_dir(variable: &str, fallback: &str) -> Option<(PathBuf, bool)> {
+    match std::env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+    {
+        Some(path) => Some((path, true)),
+        None => Some((home_dir()?.join(fallback), false)),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/timetracking`
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|(dir, _)| dir.join("timetracking"))
+}
+
+fn data_dir_with_source() -> Result<(PathBuf, Source)> {
+    let (dir, from_xdg) = xdg_dir("XDG_DATA_HOME", ".local/share").context(
+        "Could not find the home directory for the data file. Set XDG_DATA_HOME or HOME, set \
+         data_file in the config or TT_DATA_FILE, or use --data-file.",
+    )?;
+    let source = iif::iif!(from_xdg, Source::XdgDataHome, Source::Home);
+    Ok((dir.join("timetracking"), source))
+}
+
+/// `$XDG_DATA_HOME/timetracking`
+pub fn data_dir() -> Result<PathBuf> {
+    data_dir_with_source().map(|(dir, _)| dir)
+}
+
+/// where the data file is stored, from the first of these which is set: the `--data-file` flag,
+/// `TT_DATA_FILE`, `data_file` in the config, `$XDG_DATA_HOME` and the home directory
+pub fn resolve_data_file(
+    flag: Option<PathBuf>,
+    configured: Option<&str>,
+) -> Result<(PathBuf, Source)> {
+    if let Some(path) = flag {
+        return Ok((path, Source::Flag));
+    }
+    if let Some(configured) = configured {
+        // the settings read TT_DATA_FILE into data_file as well
+        let source = iif::iif!(
+            std::env::var_os("TT_DATA_FILE").is_some(),
+            Source::Environment,
+            Source::Config
+        );
+        return Ok((shellexpand::full(configured)?.parse()?, source));
+    }
+    let (dir, source) = data_dir_with_source()?;
+    Ok((dir.join(DATA_FILE_NAME), source))
+}
+
+/// where the data file was stored before xdg base directories were used
+pub fn legacy_data_file() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(DATA_FILE_NAME))
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// where the global config was read from before xdg base directories were used
+pub fn legacy_config_file() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".config/timetracking/config.toml"))
+}
+
+/// the global config which is read, which is the legacy one only as long as there is no config at
+/// the new location
+pub fn global_config_file() -> Option<PathBuf> {
+    match (config_file(), legacy_config_file()) {
+        (Some(config), Some(legacy)) if !config.exists() && legacy.exists() => Some(legacy),
+        (config, legacy) => config.or(legacy),
+    }
+}
+
+/// copies the legacy data file to the given location, if there is a legacy data file and nothing
+/// exists at the new location yet. The legacy file is kept as a backup. Returns the legacy file,
+/// if it was migrated.
+pub fn migrate_legacy_data_file(path: &Path) -> Result<Option<PathBuf>> {
+    let legacy = match legacy_data_file() {
+        Some(legacy) if !path.exists() && legacy.is_file() => legacy,
+        _ => return Ok(None),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("could not create {}", parent.display()))?;
+    }
+    std::fs::copy(&legacy, path).with_context(|| {
+        format!(
+            "could not migrate {} to {}",
+            legacy.display(),
+            path.display()
+        )
+    })?;
+    Ok(Some(legacy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_data_file() {
+        let flag = PathBuf::from("flag.json");
+        assert_eq!(
+            (flag.clone(), Source::Flag),
+            resolve_data_file(Some(flag), Some("config.json")).unwrap()
+        );
+        if std::env::var_os("TT_DATA_FILE").is_none() {
+            assert_eq!(
+                (PathBuf::from("config.json"), Source::Config),
+                resolve_data_file(None, Some("config.json")).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! a small backtracking regex engine for the classification rules. It supports alternation,
+//! groups, the quantifiers `*`, `+`, `?` and `{n,m}`, the anchors `^` and `$`, `.`, character
+//! classes and the escapes `\d`, `\w` and `\s`. `(?i)` at the start of the pattern makes it case
+//! insensitive. Patterns like `(a*)*b` backtrack exponentially, so matching gives up after a
+//! number of steps.
+
+use crate::error::Error;
+
+use std::cell::Cell;
+
+/// how many nodes are matched at most, which takes well below a second
+const MAX_STEPS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Vec<(char, char)>, bool),
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pattern: String,
+    node: Node,
+    case_insensitive: bool,
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+    case_insensitive: bool,
+}
+
+fn escape_class(c: char) -> Option<(Vec<(char, char)>, bool)> {
+    let (ranges, negated) = match c.to_ascii_lowercase() {
+        'd' => (vec![('0', '9')], c == 'D'),
+        'w' => (
+            vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+            c == 'W',
+        ),
+        's' => (vec![(' ', ' '), ('\t', '\r')], c == 'S'),
+        _ => return None,
+    };
+    Some((ranges, negated))
+}
+
+fn escaped_char(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> Error {
+        Error::Parse(format!("invalid regex \"{}\": {}", self.pattern, message))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let matches = s
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c));
+        if matches {
+            self.pos += s.chars().count();
+        }
+        matches
+    }
+
+    fn literal(&self, c: char) -> char {
+        iif::iif!(self.case_insensitive, c.to_ascii_lowercase(), c)
+    }
+
+    fn alternation(&mut self) -> Result<Node, Error> {
+        let mut alternatives = vec![self.concat()?];
+        while self.eat("|") {
+            alternatives.push(self.concat()?);
+        }
+        Ok(iif::iif!(
+            alternatives.len() == 1,
+            alternatives.remove(0),
+            Node::Alternation(alternatives)
+        ))
+    }
+
+    fn concat(&mut self) -> Result<Node, Error> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantifier(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn atom(&mut self) -> Result<Node, Error> {
+        let node = match self.next() {
+            Some('(') => {
+                self.eat("?:");
+                let node = self.alternation()?;
+                if !self.eat(")") {
+                    return Err(self.error("missing )"));
+                }
+                node
+            }
+            Some('[') => self.class()?,
+            Some('.') => Node::Any,
+            Some('^') => Node::Start,
+            Some('$') => Node::End,
+            Some('\\') => match self.next() {
+                None => return Err(self.error("trailing \\")),
+                Some(c) => match escape_class(c) {
+                    Some((ranges, negated)) => Node::Class(ranges, negated),
+                    None => Node::Char(self.literal(escaped_char(c))),
+                },
+            },
+            Some(c) if "*+?{".contains(c) => {
+                return Err(self.error(&format!("nothing to repeat before {}", c)))
+            }
+            Some(c) => Node::Char(self.literal(c)),
+            None => return Err(self.error("unexpected end")),
+        };
+        Ok(node)
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn quantifier(&mut self, atom: Node) -> Result<Node, Error> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let min = self.number().ok_or_else(|| self.error("invalid {n,m}"))?;
+                let max = match self.eat(",") {
+                    true if self.peek() == Some('}') => None,
+                    true => Some(self.number().ok_or_else(|| self.error("invalid {n,m}"))?),
+                    false => Some(min),
+                };
+                if self.peek() != Some('}') || max.is_some_and(|max| max < min) {
+                    return Err(self.error("invalid {n,m}"));
+                }
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        // lazy quantifiers match the same texts
+        self.eat("?");
+        Ok(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn class(&mut self) -> Result<Node, Error> {
+        let negated = self.eat("^");
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = match self.next() {
+                None => return Err(self.error("missing ]")),
+                Some(']') if !first => break,
+                Some('\\') => {
+                    let c = self.next().ok_or_else(|| self.error("trailing \\"))?;
+                    match escape_class(c) {
+                        Some((class, false)) => {
+                            ranges.extend(class);
+                            first = false;
+                            continue;
+                        }
+                        Some((_, true)) => {
+                            return Err(self.error("negated classes can't be used in [...]"))
+                        }
+                        None => escaped_char(c),
+                    }
+                }
+                Some(c) => c,
+            };
+            first = false;
+            let c = self.literal(c);
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                let end = match self.next() {
+                    Some('\\') => self.next().map(escaped_char),
+                    c => c,
+                }
+                .ok_or_else(|| self.error("missing ]"))?;
+                ranges.push((c, self.literal(end)));
+            } else {
+                ranges.push((c, c));
+            }
+        }
+        Ok(Node::Class(ranges, negated))
+    }
+}
+
+type Continuation<'a> = &'a mut dyn FnMut(usize) -> bool;
+
+/// the steps which are left, shared by all the backtracking
+type Steps = Cell<usize>;
+
+fn match_node(node: &Node, text: &[char], pos: usize, steps: &Steps, k: Continuation) -> bool {
+    if steps.get() == 0 {
+        return false;
+    }
+    steps.set(steps.get() - 1);
+    let next_char = text.get(pos);
+    match node {
+        Node::Char(c) => next_char == Some(c) && k(pos + 1),
+        Node::Any => next_char.is_some() && k(pos + 1),
+        Node::Class(ranges, negated) => {
+            next_char.is_some_and(|c| {
+                ranges.iter().any(|(start, end)| (start..=end).contains(&c)) != *negated
+            }) && k(pos + 1)
+        }
+        Node::Start => pos == 0 && k(pos),
+        Node::End => pos == text.len() && k(pos),
+        Node::Concat(nodes) => match_sequence(nodes, text, pos, steps, k),
+        Node::Alternation(alternatives) => alternatives
+            .iter()
+            .any(|alternative| match_node(alternative, text, pos, steps, k)),
+        Node::Repeat(node, min, max) => match_repeat(node, (*min, *max), 0, text, pos, steps, k),
+    }
+}
+
+fn match_sequence(
+    nodes: &[Node],
+    text: &[char],
+    pos: usize,
+    steps: &Steps,
+    k: Continuation,
+) -> bool {
+    match nodes.split_first() {
+        None => k(pos),
+        Some((first, rest)) => match_node(first, text, pos, steps, &mut |pos| {
+            match_sequence(rest, text, pos, steps, k)
+        }),
+    }
+}
+
+fn match_repeat(
+    node: &Node,
+    (min, max): (usize, Option<usize>),
+    count: usize,
+    text: &[char],
+    pos: usize,
+    steps: &Steps,
+    k: Continuation,
+) -> bool {
+    // greedy: try to match once more first. Empty matches are only repeated until min is reached,
+    // otherwise they would repeat forever.
+    let more = max.is_none_or(|max| count < max)
+        && match_node(node, text, pos, steps, &mut |next| {
+            (next != pos || count < min)
+                && match_repeat(node, (min, max), count + 1, text, next, steps, k)
+        });
+    more || count >= min && k(pos)
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let case_insensitive = pattern.starts_with("(?i)");
+        let mut parser = Parser {
+            pattern,
+            chars: pattern.chars().collect(),
+            pos: iif::iif!(case_insensitive, 4, 0),
+            case_insensitive,
+        };
+        let node = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unmatched )"));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            node,
+            case_insensitive,
+        })
+    }
+
+    /// whether the pattern matches anywhere in the text, or an error if that takes too long
+    pub fn is_match(&self, text: &str) -> Result<bool, Error> {
+        let chars = text
+            .chars()
+            .map(|c| iif::iif!(self.case_insensitive, c.to_ascii_lowercase(), c))
+            .collect::<Vec<_>>();
+        let steps = Cell::new(MAX_STEPS);
+        let matches = (0..=chars.len())
+            .any(|start| match_node(&self.node, &chars, start, &steps, &mut |_| true));
+        if !matches && steps.get() == 0 {
+            return Err(Error::Parse(format!(
+                "the regex \"{}\" takes too long to match \"{}\", it should repeat less",
+                self.pattern, text
+            )));
+        }
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_match() {
+        let matches =
+            |pattern: &str, text: &str| Pattern::new(pattern).unwrap().is_match(text).unwrap();
+        assert!(matches("^meeting|standup", "meeting with bob"));
+        assert!(matches("^meeting|standup", "daily standup"));
+        assert!(!matches("^meeting|standup", "prepare meeting"));
+        assert!(matches(r"PROJ-\d+", "fix PROJ-123 login"));
+        assert!(!matches(r"PROJ-\d+", "fix PROJ- login"));
+        assert!(matches(r"^(ab){2,3}$", "ababab"));
+        assert!(!matches(r"^(ab){2,3}$", "abababab"));
+        assert!(matches(r"^[a-c-]+x?$", "ab-c"));
+        assert!(!matches(r"^[^a-c]", "b"));
+        assert!(matches(r"(?i)^Review", "review docs"));
+        assert!(!matches(r"^Review", "review docs"));
+        assert!(matches(r"^(a*)*b$", "aaab"));
+        assert!(matches(r"\w+\s\.", "word ."));
+
+        assert!(Pattern::new("(ab").is_err());
+        assert!(Pattern::new("ab)").is_err());
+        assert!(Pattern::new("*a").is_err());
+        assert!(Pattern::new("[ab").is_err());
+        assert!(Pattern::new("a{3,1}").is_err());
+
+        let exponential = Pattern::new("(a*)*b").unwrap();
+        assert!(exponential.is_match(&"a".repeat(40)).is_err());
+    }
+}
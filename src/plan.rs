@@ -0,0 +1,126 @@
+//! the budgeted time from a plan file, which `tt report --plan` compares with the tracked time.
+//! The `[days]` table has the time per day of the week like "mon" or per date, the `[projects]`
+//! table the time per project for the whole report. Times are durations like "7h30m" or hours.
+
+use crate::duration_parse;
+use crate::error::Error;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use config::{Config, File, FileFormat};
+use serde::Deserialize;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Default, Deserialize)]
+struct PlanFile {
+    #[serde(default)]
+    days: HashMap<String, String>,
+    #[serde(default)]
+    projects: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Plan {
+    weekdays: HashMap<Weekday, Duration>,
+    dates: HashMap<NaiveDate, Duration>,
+    /// sorted by the lowercase project names, like the keys of the config
+    pub projects: Vec<(String, Duration)>,
+}
+
+/// a duration like "7h30m", or a number of hours
+fn parse_duration(text: &str) -> Result<Duration, Error> {
+    match text.parse::<f64>() {
+        Ok(hours) => Ok(Duration::seconds((hours * 3600.0).round() as i64)),
+        Err(_) => duration_parse::parse(text),
+    }
+}
+
+impl Plan {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let invalid = |e: config::ConfigError| {
+            Error::Parse(format!("could not read the plan {}: {}", path.display(), e))
+        };
+        let mut config = Config::new();
+        config
+            .merge(File::new(&path.to_string_lossy(), FileFormat::Toml))
+            .map_err(invalid)?;
+        Self::parse(config.try_into().map_err(invalid)?)
+    }
+
+    fn parse(file: PlanFile) -> Result<Self, Error> {
+        let mut plan = Self::default();
+        for (day, time) in file.days {
+            let time = parse_duration(&time)?;
+            if let Ok(weekday) = day.parse::<Weekday>() {
+                plan.weekdays.insert(weekday, time);
+            } else {
+                let date = NaiveDate::parse_from_str(&day, "%Y-%m-%d").map_err(|_| {
+                    Error::Parse(format!(
+                        "invalid day \"{}\" in the plan, expected a weekday like \"mon\" or a \
+                         date like \"2021-04-01\"",
+                        day
+                    ))
+                })?;
+                plan.dates.insert(date, time);
+            }
+        }
+        for (project, time) in file.projects {
+            plan.projects
+                .push((project.to_lowercase(), parse_duration(&time)?));
+        }
+        plan.projects.sort();
+        Ok(plan)
+    }
+
+    /// the planned time of the date, a date overrides its day of the week
+    pub fn day(&self, date: NaiveDate) -> Option<Duration> {
+        self.dates
+            .get(&date)
+            .or_else(|| self.weekdays.get(&date.weekday()))
+            .copied()
+    }
+
+    /// the dates which are planned explicitly
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.dates.keys().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let strings = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        let plan = Plan::parse(PlanFile {
+            days: strings(&[("mon", "8h"), ("fri", "6"), ("2021-04-02", "4h30m")]),
+            projects: strings(&[("Client-A", "20h"), ("client-a/website", "7.5")]),
+        })
+        .unwrap();
+        // 2021-04-02 is a friday
+        let date = |day| NaiveDate::from_ymd(2021, 4, day);
+        assert_eq!(Some(Duration::minutes(270)), plan.day(date(2)));
+        assert_eq!(Some(Duration::hours(6)), plan.day(date(9)));
+        assert_eq!(Some(Duration::hours(8)), plan.day(date(5)));
+        assert_eq!(None, plan.day(date(6)));
+        assert_eq!(
+            vec![
+                ("client-a".to_string(), Duration::hours(20)),
+                ("client-a/website".to_string(), Duration::minutes(450)),
+            ],
+            plan.projects
+        );
+
+        let invalid = PlanFile {
+            days: strings(&[("monday-ish", "8h")]),
+            ..PlanFile::default()
+        };
+        assert!(Plan::parse(invalid).is_err());
+    }
+}
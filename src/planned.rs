@@ -0,0 +1,102 @@
+//! the placeholder intervals of `tt record --planned`, e.g. to plan the next week. They are
+//! stored in a file next to the data file, so they never count as tracked time. `tt report
+//! --plan-view` shows them next to the tracked time and `tt start --from-plan` starts the next
+//! one.
+
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placeholder {
+    pub start: DateTime<Utc>,
+    pub stop: DateTime<Utc>,
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+    let mut path = data_file.as_ref().as_os_str().to_owned();
+    path.push(".planned");
+    path.into()
+}
+
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Placeholder>, Error> {
+    match std::fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+        Ok(content) => serde_json::from_str(&content).map_err(|_| {
+            Error::InvalidState(format!("could not read {}", path.as_ref().display()))
+        }),
+    }
+}
+
+pub fn write<P: AsRef<Path>>(path: P, placeholders: &[Placeholder]) -> Result<(), Error> {
+    let content =
+        serde_json::to_string_pretty(placeholders).expect("could not serialize placeholders");
+    Ok(std::fs::write(path, content)?)
+}
+
+/// adds the placeholder in the order of the start, unless it overlaps with another one
+pub fn add(placeholders: &mut Vec<Placeholder>, placeholder: Placeholder) -> Result<(), Error> {
+    if let Some(other) = placeholders
+        .iter()
+        .find(|other| placeholder.start < other.stop && placeholder.stop > other.start)
+    {
+        return Err(Error::Conflict(format!(
+            "The placeholder overlaps with the one from {} to {}!",
+            crate::format_date_time(other.start),
+            crate::format_date_time(other.stop)
+        )));
+    }
+    let index = placeholders.partition_point(|other| other.start <= placeholder.start);
+    placeholders.insert(index, placeholder);
+    Ok(())
+}
+
+/// removes the next placeholder, which is the first one that isn't over yet
+pub fn take_next(placeholders: &mut Vec<Placeholder>, now: DateTime<Utc>) -> Option<Placeholder> {
+    let index = placeholders
+        .iter()
+        .position(|placeholder| placeholder.stop > now)?;
+    Some(placeholders.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn placeholder(start: u32, stop: u32) -> Placeholder {
+        Placeholder {
+            start: Utc.ymd(2021, 4, 12).and_hms(start, 0, 0),
+            stop: Utc.ymd(2021, 4, 12).and_hms(stop, 0, 0),
+            description: None,
+            project: None,
+            context: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let mut placeholders = Vec::new();
+        add(&mut placeholders, placeholder(13, 17)).unwrap();
+        add(&mut placeholders, placeholder(9, 12)).unwrap();
+        assert!(add(&mut placeholders, placeholder(11, 14)).is_err());
+        assert_eq!(vec![placeholder(9, 12), placeholder(13, 17)], placeholders);
+
+        let now = Utc.ymd(2021, 4, 12).and_hms(12, 30, 0);
+        assert_eq!(Some(placeholder(13, 17)), take_next(&mut placeholders, now));
+        assert_eq!(None, take_next(&mut placeholders, now));
+        assert_eq!(1, placeholders.len());
+    }
+}
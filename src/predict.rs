@@ -0,0 +1,44 @@
+use chrono::{Duration, NaiveTime};
+
+/// the arrival time and the length of the breaks of a past work day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Day {
+    pub arrival: NaiveTime,
+    pub pause: Duration,
+}
+
+fn median<T: Ord + Copy>(mut values: Vec<T>) -> Option<T> {
+    values.sort();
+    values.get(values.len() / 2).copied()
+}
+
+/// the median arrival time and break length, so that a few unusual days don't count much
+pub fn typical_day(days: &[Day]) -> Option<Day> {
+    Some(Day {
+        arrival: median(days.iter().map(|day| day.arrival).collect())?,
+        pause: median(days.iter().map(|day| day.pause).collect())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typical_day() {
+        let day = |hour, minute, pause| Day {
+            arrival: NaiveTime::from_hms(hour, minute, 0),
+            pause: Duration::minutes(pause),
+        };
+        assert_eq!(
+            Some(day(8, 20, 45)),
+            typical_day(&[
+                day(8, 0, 45),
+                day(11, 30, 0),
+                day(8, 15, 60),
+                day(8, 20, 30)
+            ])
+        );
+        assert_eq!(None, typical_day(&[]));
+    }
+}
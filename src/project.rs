@@ -0,0 +1,83 @@
+use crate::settings::ProjectSettings;
+
+use std::collections::HashMap;
+
+/// projects are nested by separating the levels with `/`, e.g. `client-a/website/frontend`
+const SEPARATOR: char = '/';
+
+/// the project and all of its parents, starting with the top level
+pub fn ancestors(project: &str) -> impl Iterator<Item = &str> {
+    project
+        .match_indices(SEPARATOR)
+        .map(move |(index, _)| &project[..index])
+        .chain(std::iter::once(project))
+}
+
+/// whether the project is `parent` or one of its sub projects
+pub fn is_within(project: &str, parent: &str) -> bool {
+    let parent = parent.trim_end_matches(SEPARATOR);
+    project == parent
+        || project
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with(SEPARATOR))
+}
+
+/// the project cut off after the given amount of levels
+pub fn at_depth(project: &str, depth: usize) -> &str {
+    ancestors(project)
+        .nth(depth.saturating_sub(1))
+        .unwrap_or(project)
+}
+
+/// the tags configured for the project and all of its parents
+pub fn inherited_tags(projects: &HashMap<String, ProjectSettings>, project: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for ancestor in ancestors(project) {
+        // the config keys are lowercase
+        if let Some(settings) = projects.get(&ancestor.to_lowercase()) {
+            for tag in &settings.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hierarchy() {
+        assert_eq!(
+            vec!["a", "a/b", "a/b/c"],
+            ancestors("a/b/c").collect::<Vec<_>>()
+        );
+        assert!(is_within("a/b/c", "a/b"));
+        assert!(is_within("a/b", "a/b/"));
+        assert!(!is_within("a/bc", "a/b"));
+        assert_eq!("a/b", at_depth("a/b/c", 2));
+        assert_eq!("a/b/c", at_depth("a/b/c", 5));
+
+        let projects = vec![
+            ("a".to_string(), vec!["billable"]),
+            ("a/b".to_string(), vec!["web", "billable"]),
+        ]
+        .into_iter()
+        .map(|(project, tags)| {
+            let tags = tags.into_iter().map(str::to_string).collect();
+            let settings = ProjectSettings {
+                tags,
+                ..ProjectSettings::default()
+            };
+            (project, settings)
+        })
+        .collect();
+        assert_eq!(
+            vec!["billable".to_string(), "web".to_string()],
+            inherited_tags(&projects, "A/b/c")
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! the shorthand of `tt quick`, which reads a single line like "s coding", "x" or "c" from stdin,
+//! e.g. from a terminal popup bound to a global hotkey. The line becomes the arguments of start,
+//! stop or continue.
+
+use crate::error::Error;
+
+/// the arguments of the command the line stands for
+pub fn args(line: &str) -> Result<Vec<String>, Error> {
+    let line = line.trim();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let command = match word.to_lowercase().as_str() {
+        "s" | "start" => "start",
+        "x" | "stop" => "stop",
+        "c" | "continue" => "continue",
+        _ => {
+            return Err(Error::Parse(format!(
+                "unknown shorthand \"{}\", expected \"s <description>\", \"x\" or \"c\"",
+                line
+            )))
+        }
+    };
+    let mut args = vec![command.to_string()];
+    // a description like "-1 bug" isn't a flag
+    match (command, rest.trim()) {
+        (_, "") => {}
+        ("continue", rest) => args.push(format!("--description={}", rest)),
+        (_, rest) => args.extend(vec!["--".to_string(), rest.to_string()]),
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        assert_eq!(
+            vec!["start", "--", "fix the login"],
+            args("s fix the login\n").unwrap()
+        );
+        assert_eq!(vec!["start"], args("S").unwrap());
+        assert_eq!(vec!["stop"], args(" x ").unwrap());
+        assert_eq!(
+            vec!["continue", "--description=review"],
+            args("c review").unwrap()
+        );
+        assert!(args("").is_err());
+        assert!(args("coding").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Duration, Local};
+
+/// returns the parts of the window which are not covered by any of the intervals
+pub fn gaps(
+    (window_start, window_end): (DateTime<Local>, DateTime<Local>),
+    intervals: &[(DateTime<Local>, DateTime<Local>)],
+) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let mut intervals = intervals
+        .iter()
+        .filter(|(start, end)| *start < window_end && *end > window_start)
+        .collect::<Vec<_>>();
+    intervals.sort_by_key(|(start, _)| *start);
+
+    let mut gaps = Vec::new();
+    let mut current = window_start;
+    for (start, end) in intervals {
+        if *start > current {
+            gaps.push((current, *start));
+        }
+        current = current.max(*end);
+    }
+    if current < window_end {
+        gaps.push((current, window_end));
+    }
+    gaps
+}
+
+/// returns how much of the interval lies within the windows
+pub fn overlap(
+    interval: (DateTime<Local>, DateTime<Local>),
+    windows: &[(DateTime<Local>, DateTime<Local>)],
+) -> Duration {
+    gaps(interval, windows)
+        .into_iter()
+        .fold(interval.1 - interval.0, |overlap, (start, end)| {
+            overlap - (end - start)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_gaps() {
+        let at = |hour, minute| Local.ymd(2021, 4, 1).and_hms(hour, minute, 0);
+        assert_eq!(
+            vec![(at(9, 0), at(9, 30)), (at(12, 0), at(13, 0))],
+            gaps(
+                (at(9, 0), at(17, 0)),
+                &[
+                    (at(13, 0), at(18, 0)),
+                    (at(9, 30), at(11, 0)),
+                    (at(10, 30), at(12, 0)),
+                ]
+            )
+        );
+        assert_eq!(
+            vec![(at(9, 0), at(17, 0))],
+            gaps((at(9, 0), at(17, 0)), &[(at(7, 0), at(8, 0))])
+        );
+        assert!(gaps((at(9, 0), at(17, 0)), &[(at(8, 0), at(18, 0))]).is_empty());
+    }
+
+    #[test]
+    fn test_overlap() {
+        let at = |hour, minute| Local.ymd(2021, 4, 1).and_hms(hour, minute, 0);
+        assert_eq!(
+            Duration::minutes(90),
+            overlap(
+                (at(9, 0), at(12, 0)),
+                &[(at(8, 0), at(10, 0)), (at(11, 30), at(14, 0))]
+            )
+        );
+        assert_eq!(
+            Duration::zero(),
+            overlap((at(9, 0), at(12, 0)), &[(at(13, 0), at(14, 0))])
+        );
+    }
+}
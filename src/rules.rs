@@ -0,0 +1,110 @@
+use crate::error::Error;
+use crate::pattern::Pattern;
+use crate::project;
+use crate::settings::Settings;
+use crate::Metadata;
+
+/// adds tags or a project to intervals whose description matches the pattern, e.g.
+/// `^meeting|standup -> tag:meeting` or `PROJ-\d+ -> project:client-a, tag:billable`
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pattern: Pattern,
+    tags: Vec<String>,
+    project: Option<String>,
+}
+
+impl Rule {
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        let invalid =
+            |message: &str| Error::Parse(format!("invalid rule \"{}\": {}", rule, message));
+        let (pattern, actions) = rule
+            .rsplit_once("->")
+            .ok_or_else(|| invalid("expected <pattern> -> <action>"))?;
+        let mut tags = Vec::new();
+        let mut project = None;
+        for action in actions.split(',') {
+            match action.trim().split_once(':') {
+                Some(("tag", tag)) if !tag.trim().is_empty() => tags.push(tag.trim().to_string()),
+                Some(("project", name)) if !name.trim().is_empty() => {
+                    project = Some(name.trim().to_string())
+                }
+                _ => {
+                    return Err(invalid(&format!(
+                        "unknown action \"{}\", expected tag:<tag> or project:<project>",
+                        action.trim()
+                    )))
+                }
+            }
+        }
+        Ok(Self {
+            pattern: Pattern::new(pattern.trim())?,
+            tags,
+            project,
+        })
+    }
+}
+
+pub fn parse(rules: &[String]) -> Result<Vec<Rule>, Error> {
+    rules.iter().map(|rule| Rule::parse(rule)).collect()
+}
+
+/// applies the rules matching the description. Tags are added, the project of the first
+/// matching rule is only set if there is none yet, together with the tags it inherits.
+pub fn classify(
+    settings: &Settings,
+    rules: &[Rule],
+    description: &str,
+    meta: &mut Metadata,
+) -> Result<(), Error> {
+    let add_tag = |tags: &mut Vec<String>, tag: &String| {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    };
+    for rule in rules {
+        if !rule.pattern.is_match(description)? {
+            continue;
+        }
+        for tag in &rule.tags {
+            add_tag(&mut meta.tags, tag);
+        }
+        if let (None, Some(name)) = (&meta.project, &rule.project) {
+            for tag in &project::inherited_tags(&settings.projects, name) {
+                add_tag(&mut meta.tags, tag);
+            }
+            meta.project = Some(name.clone());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let settings = Settings::default();
+        let rules = parse(&[
+            "^meeting|standup -> tag:meeting".to_string(),
+            r"PROJ-\d+ -> project:client-a, tag:billable".to_string(),
+            "standup -> tag:daily, tag:meeting".to_string(),
+        ])
+        .unwrap();
+
+        let mut meta = Metadata::default();
+        classify(&settings, &rules, "standup about PROJ-12", &mut meta).unwrap();
+        assert_eq!(vec!["meeting", "billable", "daily"], meta.tags);
+        assert_eq!(Some("client-a".to_string()), meta.project);
+
+        let mut meta = Metadata {
+            project: Some("internal".to_string()),
+            ..Metadata::default()
+        };
+        classify(&settings, &rules, "PROJ-1", &mut meta).unwrap();
+        assert_eq!(Some("internal".to_string()), meta.project);
+
+        assert!(Rule::parse("meeting").is_err());
+        assert!(Rule::parse("meeting -> color:red").is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! the binary data file format. The events are split into one segment per month, followed by
+//! an index of the segments, the sha-256 hash of the index and the offset of the index in the
+//! last 8 bytes, so commands which only need a time range don't have to deserialize the whole
+//! history. The index holds the hash of each segment, so a file which was damaged on the disk or
+//! by a sync tool is reported instead of being read partially.
+
+use crate::error::Error;
+use crate::sha256::sha256;
+use crate::storage::Selection;
+use crate::TrackingEvent;
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"TTSEG02\n";
+
+/// files written before the segments had hashes
+const MAGIC_WITHOUT_HASHES: &[u8; 8] = b"TTSEG01\n";
+
+/// the length of a hash in hex
+const HASH_LENGTH: u64 = 64;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Segment {
+    /// months since year 0, in utc
+    month: i32,
+    offset: u64,
+    length: u64,
+    hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SegmentWithoutHash {
+    month: i32,
+    offset: u64,
+    length: u64,
+}
+
+fn month(time: DateTime<Utc>) -> i32 {
+    time.year() * 12 + time.month0() as i32
+}
+
+fn invalid(path: &Path) -> Error {
+    Error::InvalidState(format!("invalid data file {}", path.display()))
+}
+
+fn damaged(path: &Path, part: String) -> Error {
+    Error::InvalidState(format!(
+        "The data file {} is damaged, {} doesn't match its checksum! Restore it from a backup",
+        path.display(),
+        part
+    ))
+}
+
+pub fn write<P: AsRef<Path>>(path: P, data: &[TrackingEvent]) -> Result<(), Error> {
+    let mut content = MAGIC.to_vec();
+    let mut index = Vec::new();
+    let mut rest = data;
+    while let Some(first) = rest.first() {
+        let month = month(first.time(true));
+        let length = rest
+            .iter()
+            .position(|event| self::month(event.time(true)) != month)
+            .unwrap_or(rest.len());
+        let segment = bincode::serialize(&rest[..length]).expect("could not serialize data");
+        index.push(Segment {
+            month,
+            offset: content.len() as u64,
+            length: segment.len() as u64,
+            hash: sha256(&segment),
+        });
+        content.extend(segment);
+        rest = &rest[length..];
+    }
+    let index_offset = content.len() as u64;
+    let index = bincode::serialize(&index).expect("could not serialize index");
+    content.extend(sha256(&index).into_bytes());
+    content.extend(index);
+    content.extend(index_offset.to_le_bytes());
+    Ok(std::fs::write(path, content)?)
+}
+
+fn read_exact_at(file: &mut File, offset: u64, length: u64) -> io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buffer = vec![0; length as usize];
+    file.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// the index of a file with hashes, which starts with the hash of the rest of the index
+fn read_index(file: &mut File, path: &Path, offset: u64, size: u64) -> Result<Vec<Segment>, Error> {
+    if offset + HASH_LENGTH > size - 8 {
+        return Err(invalid(path));
+    }
+    let hash = read_exact_at(file, offset, HASH_LENGTH)?;
+    let index = read_exact_at(file, offset + HASH_LENGTH, size - 8 - offset - HASH_LENGTH)?;
+    if sha256(&index).as_bytes() != hash.as_slice() {
+        return Err(damaged(path, "the index".to_string()));
+    }
+    bincode::deserialize(&index).map_err(|_| invalid(path))
+}
+
+/// reads the selected segments. Returns none if the file is not in this format.
+pub fn read<P: AsRef<Path>>(
+    path: P,
+    selection: &Selection,
+) -> Result<Option<Vec<TrackingEvent>>, Error> {
+    let path = path.as_ref();
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut magic = [0; 8];
+    if size < 16 || file.read_exact(&mut magic).is_err() {
+        return Ok(None);
+    }
+    let has_hashes = match &magic {
+        magic if magic == MAGIC => true,
+        magic if magic == MAGIC_WITHOUT_HASHES => false,
+        _ => return Ok(None),
+    };
+    let footer = read_exact_at(&mut file, size - 8, 8)?;
+    let index_offset = u64::from_le_bytes(footer.try_into().expect("footer has 8 bytes"));
+    if index_offset < 8 || index_offset > size - 8 {
+        return Err(invalid(path));
+    }
+    let index = if has_hashes {
+        read_index(&mut file, path, index_offset, size)?
+    } else {
+        let index = read_exact_at(&mut file, index_offset, size - 8 - index_offset)?;
+        let index: Vec<SegmentWithoutHash> =
+            bincode::deserialize(&index).map_err(|_| invalid(path))?;
+        index
+            .into_iter()
+            .map(|segment| Segment {
+                month: segment.month,
+                offset: segment.offset,
+                length: segment.length,
+                hash: String::new(),
+            })
+            .collect()
+    };
+
+    let selected: Vec<&Segment> = match selection {
+        Selection::All => index.iter().collect(),
+        Selection::Latest => index.last().into_iter().collect(),
+        Selection::Range(from, to) => index
+            .iter()
+            .filter(|segment| (month(*from)..=month(*to)).contains(&segment.month))
+            .collect(),
+    };
+    let mut data = Vec::new();
+    for segment in selected {
+        if segment.offset + segment.length > index_offset {
+            return Err(invalid(path));
+        }
+        let content = read_exact_at(&mut file, segment.offset, segment.length)?;
+        if has_hashes && sha256(&content) != segment.hash {
+            return Err(damaged(
+                path,
+                format!(
+                    "the segment of {}-{:02}",
+                    segment.month.div_euclid(12),
+                    segment.month.rem_euclid(12) + 1
+                ),
+            ));
+        }
+        let events: Vec<TrackingEvent> =
+            bincode::deserialize(&content).map_err(|_| invalid(path))?;
+        data.extend(events);
+    }
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data;
+    use chrono::TimeZone;
+
+    fn event(month: u32, day: u32) -> TrackingEvent {
+        test_data::event(true, Utc.ymd(2021, month, day).and_hms(8, 0, 0))
+    }
+
+    #[test]
+    fn test_read_selection() {
+        let path = std::env::temp_dir().join("timetracking_test_segments.bin");
+        let data = vec![event(1, 5), event(1, 20), event(3, 1), event(4, 2)];
+        write(&path, &data).unwrap();
+
+        assert_eq!(Some(data.clone()), read(&path, &Selection::All).unwrap());
+        assert_eq!(
+            Some(vec![event(4, 2)]),
+            read(&path, &Selection::Latest).unwrap()
+        );
+        let range = Selection::Range(
+            Utc.ymd(2021, 1, 31).and_hms(0, 0, 0),
+            Utc.ymd(2021, 3, 2).and_hms(0, 0, 0),
+        );
+        assert_eq!(Some(data[..3].to_vec()), read(&path, &range).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_empty_and_other_formats() {
+        let path = std::env::temp_dir().join("timetracking_test_segments_empty.bin");
+        write(&path, &[]).unwrap();
+        assert_eq!(Some(vec![]), read(&path, &Selection::All).unwrap());
+        assert_eq!(Some(vec![]), read(&path, &Selection::Latest).unwrap());
+        std::fs::write(&path, bincode::serialize(&[event(1, 5)]).unwrap()).unwrap();
+        assert_eq!(None, read(&path, &Selection::All).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_damaged_segment() {
+        let path = std::env::temp_dir().join("timetracking_test_segments_damaged.bin");
+        let data = vec![event(1, 5), event(1, 20), event(3, 1), event(4, 2)];
+        // a changed byte in a segment is noticed instead of decoding other events
+        write(&path, &data).unwrap();
+        let mut content = std::fs::read(&path).unwrap();
+        content[MAGIC.len() + 4] ^= 1;
+        std::fs::write(&path, &content).unwrap();
+        assert!(matches!(
+            read(&path, &Selection::All),
+            Err(Error::InvalidState(message)) if message.contains("2021-01")
+        ));
+        // the other segments can still be read
+        assert_eq!(
+            Some(vec![event(4, 2)]),
+            read(&path, &Selection::Latest).unwrap()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,7 +1,9 @@
+use crate::paths;
 use config::{Config, ConfigError, Environment, File, FileFormat};
 use serde::Deserialize;
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Default, Debug, Deserialize)]
 pub struct Time {
@@ -15,23 +17,176 @@ pub struct TimeGoal {
     pub weekly: Time,
 }
 
+#[derive(Default, Debug, Deserialize)]
+pub struct WorkingHours {
+    pub days: Vec<String>,
+    pub start: Time,
+    pub end: Time,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct ContextSettings {
+    pub default: Option<String>,
+    #[serde(default)]
+    pub hostnames: HashMap<String, String>,
+    #[serde(default)]
+    pub ssids: HashMap<String, String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct OnCallWindow {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct OnCallSettings {
+    pub calendar: Option<String>,
+    #[serde(default)]
+    pub windows: Vec<OnCallWindow>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct DescriptionSettings {
+    /// a regex which the descriptions of started intervals should match
+    pub template: Option<String>,
+    /// reject non-matching descriptions instead of warning about them
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct BreakSettings {
+    /// minutes of work and break, e.g. "50/10"
+    pub policy: Option<String>,
+    /// the work time and the break, e.g. "6h/30m"
+    pub lunch_deduction: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct OvertimeSettings {
+    /// "reset", "full" or a cap like "cap 10h"
+    pub carry_over: Option<String>,
+}
+
+/// the mail of `tt email-report`
+#[derive(Default, Debug, Deserialize)]
+pub struct EmailSettings {
+    pub to: Option<String>,
+    pub from: Option<String>,
+    /// reads the mail with its headers from stdin, like "sendmail -t"
+    pub sendmail: Option<String>,
+}
+
+/// checks the tickets of `tt start --task`
+#[derive(Default, Debug, Deserialize)]
+pub struct IssueSettings {
+    /// prints the state of the ticket, which is its last argument, as json
+    pub command: Option<String>,
+    /// the assignee the tickets should have
+    pub user: Option<String>,
+}
+
+/// the sync of `tt import toggl-api` and `tt export toggl-api`
+#[derive(Default, Debug, Deserialize)]
+pub struct TogglSettings {
+    /// the workspace the intervals are pushed to
+    pub workspace_id: Option<u64>,
+    /// the name in toggl by local project
+    #[serde(default)]
+    pub projects: HashMap<String, String>,
+    /// the name in toggl by local tag
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+/// the most time per day, like "2h", by description and by project
+#[derive(Default, Debug, Deserialize)]
+pub struct LimitSettings {
+    #[serde(default)]
+    pub descriptions: HashMap<String, String>,
+    #[serde(default)]
+    pub projects: HashMap<String, String>,
+}
+
+/// metadata which `tt start --template <name>` uses
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct Template {
+    pub description: Option<String>,
+    pub project: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct ProjectSettings {
+    /// added to the intervals of the project and all of its sub projects
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// the increments in which the project and its sub projects are billed, like "30m per day"
+    #[serde(default)]
+    pub rounding: Option<String>,
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct Settings {
-    pub data_file: String,
+    pub data_file: Option<String>,
+    pub storage: Option<String>,
+    pub per_year_files: bool,
+    pub read_only: bool,
     pub auto_insert_stop: bool,
+    pub suggest_description: bool,
+    pub capture_window_title: bool,
+    pub language: String,
     pub enable_project_settings: bool,
     pub time_goal: TimeGoal,
     pub min_daily_break: u8,
+    pub max_clock_skew: u32,
+    pub duplicate_window: u32,
+    pub ambiguous_time: String,
+    pub working_hours: WorkingHours,
+    #[serde(default)]
+    pub context: ContextSettings,
+    #[serde(default)]
+    pub on_call: OnCallSettings,
+    #[serde(default)]
+    pub description: DescriptionSettings,
+    #[serde(default)]
+    pub breaks: BreakSettings,
+    #[serde(default)]
+    pub overtime: OvertimeSettings,
+    #[serde(default)]
+    pub email: EmailSettings,
+    #[serde(default)]
+    pub issues: IssueSettings,
+    #[serde(default)]
+    pub toggl: TogglSettings,
+    #[serde(default)]
+    pub limits: LimitSettings,
+    #[serde(default)]
+    pub projects: HashMap<String, ProjectSettings>,
+    #[serde(default)]
+    pub templates: HashMap<String, Template>,
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
 }
 
-fn add_file_if_exists(s: &mut Config, file: &str) -> Result<bool, ConfigError> {
-    let result = if Path::new(file).exists() {
-        s.merge(File::new(file, FileFormat::Toml).required(false))?;
-        true
-    } else {
-        false
-    };
-    Ok(result)
+/// the project settings in the current directory or the closest parent which has them
+pub fn project_config_file() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().expect("Could not get current directory");
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join("timetracking.project.toml"))
+        .find(|path| path.exists())
+}
+
+/// the local config in the current directory
+pub fn local_config_file() -> Option<PathBuf> {
+    let path = std::env::current_dir().ok()?.join(".timetracking.toml");
+    path.is_file().then_some(path)
 }
 
 fn path_to_string_lossy<P: AsRef<Path>>(path: P) -> String {
@@ -48,37 +203,20 @@ impl Settings {
             config::FileFormat::Toml,
         ))?;
 
-        #[cfg(not(feature = "binary"))]
-        s.merge(File::from_str(
-            include_str!("../default_config_development.toml"),
-            config::FileFormat::Toml,
-        ))?;
-
-        let config_path = shellexpand::full("~/.config/timetracking/config.toml")
-            .expect("could not expand path")
-            .to_string();
-        s.merge(File::with_name(config_path.as_str()).required(false))?;
+        // without a home directory there is no global config
+        if let Some(config_path) = paths::global_config_file() {
+            s.merge(File::from(config_path).required(false))?;
+        }
 
         if s.get_bool("enable_project_settings")? {
-            let current_dir = std::env::current_dir().expect("Could not get current directory");
-            let mut path = current_dir.as_path();
-            if !add_file_if_exists(
-                &mut s,
-                &format!("{}/timetracking.project.toml", path_to_string_lossy(&path)),
-            )? {
-                while let Some(parent) = path.parent() {
-                    if add_file_if_exists(
-                        &mut s,
-                        &format!("{}/timetracking.project.toml", path_to_string_lossy(&path)),
-                    )? {
-                        break;
-                    }
-                    path = parent;
-                }
+            if let Some(path) = project_config_file() {
+                s.merge(File::new(&path_to_string_lossy(path), FileFormat::Toml))?;
             }
         }
 
-        s.merge(File::with_name(".timetracking.config").required(false))?;
+        if let Some(path) = local_config_file() {
+            s.merge(File::from(path))?;
+        }
 
         s.merge(Environment::with_prefix("tt"))?;
 
@@ -91,7 +229,27 @@ impl Settings {
         let weekly_minutes = s.get_int("time_goal.weekly.minutes")?;
         s.set("time_goal.weekly.minutes", weekly_minutes.min(59))?;
 
+        let start_hours = s.get_int("working_hours.start.hours")?;
+        s.set("working_hours.start.hours", start_hours.min(24))?;
+        let start_minutes = s.get_int("working_hours.start.minutes")?;
+        s.set("working_hours.start.minutes", start_minutes.min(59))?;
+        let end_hours = s.get_int("working_hours.end.hours")?;
+        s.set("working_hours.end.hours", end_hours.min(24))?;
+        let end_minutes = s.get_int("working_hours.end.minutes")?;
+        s.set("working_hours.end.minutes", end_minutes.min(59))?;
+
         // You can deserialize (and thus freeze) the entire configuration as
         s.try_into()
     }
+
+    /// whether the config file can be read, on top of the default config
+    pub fn check_file(path: &Path) -> Result<(), ConfigError> {
+        let mut s = Config::new();
+        s.merge(File::from_str(
+            include_str!("../default_config.toml"),
+            config::FileFormat::Toml,
+        ))?;
+        s.merge(File::new(&path_to_string_lossy(path), FileFormat::Toml))?;
+        s.try_into::<Self>().map(|_| ())
+    }
 }
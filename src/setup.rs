@@ -0,0 +1,110 @@
+//! the answers of `tt init`, which are written as the global config
+
+use chrono::{Duration, Weekday};
+
+#[derive(Debug)]
+pub struct Setup {
+    /// none keeps the default location
+    pub data_file: Option<String>,
+    /// none uses the format for the extension of the data file
+    pub storage: Option<String>,
+    pub daily_goal: Duration,
+    pub work_days: Vec<Weekday>,
+    /// a project and its rounding, like "30m per day"
+    pub rounding: Option<(String, String)>,
+    pub break_policy: Option<String>,
+    pub capture_window_title: bool,
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn time(duration: Duration) -> String {
+    format!(
+        "hours = {}\nminutes = {}\n",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+impl Setup {
+    /// the config with only the answered settings, everything else keeps its default
+    pub fn config(&self) -> String {
+        let mut config = String::from(
+            "# created by `tt init`. all settings and their defaults are listed in the readme\n",
+        );
+        let mut settings = String::new();
+        if let Some(data_file) = &self.data_file {
+            settings += &format!("data_file = {}\n", quote(data_file));
+        }
+        if let Some(storage) = &self.storage {
+            settings += &format!("storage = {}\n", quote(storage));
+        }
+        if self.capture_window_title {
+            settings += "capture_window_title = true\n";
+        }
+        if !settings.is_empty() {
+            config += &format!("\n{}", settings);
+        }
+        config += &format!("\n[time_goal.daily]\n{}", time(self.daily_goal));
+        let weekly = self.daily_goal * self.work_days.len() as i32;
+        config += &format!("\n[time_goal.weekly]\n{}", time(weekly));
+        let days = self
+            .work_days
+            .iter()
+            .map(|day| quote(&format!("{:?}", day).to_lowercase()))
+            .collect::<Vec<_>>();
+        config += &format!("\n[working_hours]\ndays = [{}]\n", days.join(", "));
+        if let Some(policy) = &self.break_policy {
+            config += &format!("\n[breaks]\npolicy = {}\n", quote(policy));
+        }
+        if let Some((project, rounding)) = &self.rounding {
+            // the project keys are lowercase
+            config += &format!(
+                "\n[projects.{}]\nrounding = {}\n",
+                quote(&project.to_lowercase()),
+                quote(rounding)
+            );
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let setup = Setup {
+            data_file: Some("C:\\tt \"work\".json".to_string()),
+            storage: None,
+            daily_goal: Duration::minutes(7 * 60 + 30),
+            work_days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu],
+            rounding: Some(("Client-A".to_string(), "30m per day".to_string())),
+            break_policy: None,
+            capture_window_title: false,
+        };
+        let expected = "\
+# created by `tt init`. all settings and their defaults are listed in the readme
+
+data_file = \"C:\\\\tt \\\"work\\\".json\"
+
+[time_goal.daily]
+hours = 7
+minutes = 30
+
+[time_goal.weekly]
+hours = 30
+minutes = 0
+
+[working_hours]
+days = [\"mon\", \"tue\", \"wed\", \"thu\"]
+
+[projects.\"client-a\"]
+rounding = \"30m per day\"
+";
+        assert_eq!(expected, setup.config());
+    }
+}
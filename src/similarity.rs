@@ -0,0 +1,80 @@
+//! finds descriptions which are probably typos of each other, e.g. "code reivew" and "code review"
+
+/// the edit distance of the lowercase texts
+pub fn distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_lowercase().chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// whether the texts differ in at most max_distance characters and in at most a quarter of them,
+/// so that short descriptions like "bug" and "bus" are kept apart
+fn is_similar(a: &str, b: &str, max_distance: usize) -> bool {
+    let length = a.chars().count().min(b.chars().count());
+    let distance = distance(a, b);
+    distance <= max_distance && distance * 4 <= length
+}
+
+/// groups the descriptions with their number of uses. Each group starts with the most used
+/// description, which the others are similar to. Descriptions without similar ones are left out.
+pub fn clusters(
+    descriptions: &[(String, usize)],
+    max_distance: usize,
+) -> Vec<Vec<(String, usize)>> {
+    let mut descriptions = descriptions.to_vec();
+    descriptions.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    let mut clusters: Vec<Vec<(String, usize)>> = Vec::new();
+    for (description, count) in descriptions {
+        match clusters
+            .iter_mut()
+            .find(|cluster| is_similar(&cluster[0].0, &description, max_distance))
+        {
+            Some(cluster) => cluster.push((description, count)),
+            None => clusters.push(vec![(description, count)]),
+        }
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(0, distance("Review", "review"));
+        assert_eq!(2, distance("code reivew", "code review"));
+        assert_eq!(3, distance("kitten", "sitting"));
+        assert_eq!(4, distance("", "code"));
+    }
+
+    #[test]
+    fn test_clusters() {
+        let descriptions = [
+            ("code reivew".to_string(), 1),
+            ("code review".to_string(), 12),
+            ("Code review".to_string(), 2),
+            ("bug".to_string(), 3),
+            ("bus".to_string(), 1),
+            ("meeting".to_string(), 5),
+        ];
+        assert_eq!(
+            vec![vec![
+                ("code review".to_string(), 12),
+                ("Code review".to_string(), 2),
+                ("code reivew".to_string(), 1),
+            ]],
+            clusters(&descriptions, 2)
+        );
+    }
+}
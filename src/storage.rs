@@ -0,0 +1,310 @@
+use crate::error::Error;
+use crate::{read_json_data, write_json_data, TrackingEvent};
+use chrono::{DateTime, Datelike, Local, Utc};
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// which events a command needs. Only the binary format can read less than all events, the
+/// commands have to filter the events themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    All,
+    /// at least the events between these times
+    Range(DateTime<Utc>, DateTime<Utc>),
+    /// at least the latest event
+    Latest,
+}
+
+/// a format in which the events are stored. The format is chosen at runtime, so other formats
+/// can be added by implementing this trait.
+pub trait Storage {
+    /// reads at least the selected events of the file
+    fn read(&self, path: &Path, selection: &Selection) -> Result<Vec<TrackingEvent>, Error>;
+    fn write(&self, path: &Path, data: &[TrackingEvent]) -> Result<(), Error>;
+}
+
+fn invalid_data_file(path: &Path) -> Error {
+    Error::InvalidState(format!("could not decode the data file {}", path.display()))
+}
+
+fn is_empty(path: &Path) -> Result<bool, Error> {
+    Ok(std::fs::metadata(path)?.len() == 0)
+}
+
+/// all events in one json array
+pub struct Json;
+
+impl Storage for Json {
+    fn read(&self, path: &Path, _selection: &Selection) -> Result<Vec<TrackingEvent>, Error> {
+        if is_empty(path)? {
+            return Ok(Vec::new());
+        }
+        read_json_data(path).map_err(|e| match e {
+            Error::Parse(_) => invalid_data_file(path),
+            e => e,
+        })
+    }
+
+    fn write(&self, path: &Path, data: &[TrackingEvent]) -> Result<(), Error> {
+        write_json_data(path, data, false)
+    }
+}
+
+/// one json object per event and line, which is easy to append to and to process line by line
+pub struct JsonLines;
+
+impl Storage for JsonLines {
+    fn read(&self, path: &Path, _selection: &Selection) -> Result<Vec<TrackingEvent>, Error> {
+        let file = io::BufReader::new(std::fs::File::open(path)?);
+        let mut data = Vec::new();
+        for line in file.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                data.push(serde_json::from_str(&line).map_err(|_| invalid_data_file(path))?);
+            }
+        }
+        Ok(data)
+    }
+
+    fn write(&self, path: &Path, data: &[TrackingEvent]) -> Result<(), Error> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        for event in data {
+            serde_json::to_writer(&mut file, event).expect("could not serialize data");
+            file.write_all(b"\n")?;
+        }
+        Ok(file.flush()?)
+    }
+}
+
+/// bincode split into monthly segments, see [`crate::segments`]. Files written by older versions
+/// are still read.
+#[cfg(feature = "binary")]
+pub struct Binary;
+
+#[cfg(feature = "binary")]
+impl Storage for Binary {
+    fn read(&self, path: &Path, selection: &Selection) -> Result<Vec<TrackingEvent>, Error> {
+        if is_empty(path)? {
+            return Ok(Vec::new());
+        }
+        if let Some(data) = crate::segments::read(path, selection)? {
+            return Ok(data);
+        }
+        let data = std::fs::read(path)?;
+        bincode::deserialize(&data).or_else(|_| {
+            let legacy: Vec<crate::legacy::LegacyTrackingEvent> =
+                bincode::deserialize(&data).map_err(|_| invalid_data_file(path))?;
+            Ok(legacy.into_iter().map(Into::into).collect())
+        })
+    }
+
+    fn write(&self, path: &Path, data: &[TrackingEvent]) -> Result<(), Error> {
+        crate::segments::write(path, data)
+    }
+}
+
+#[cfg(feature = "binary")]
+const FORMATS: &str = "\"binary\", \"json\" or \"json-lines\"";
+#[cfg(not(feature = "binary"))]
+const FORMATS: &str = "\"json\" or \"json-lines\"";
+
+/// the storage for the format from the config or, without one, for the extension of the data
+/// file. Files without a known extension use the binary format, if it is enabled.
+pub fn backend(format: Option<&str>, path: &Path) -> Result<Box<dyn Storage>, Error> {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let format = match (format, extension) {
+        (Some(format), _) => format,
+        (None, Some("json")) => "json",
+        (None, Some("jsonl")) => "json-lines",
+        (None, _) if cfg!(feature = "binary") => "binary",
+        (None, _) => "json",
+    };
+    match format {
+        "json" => Ok(Box::new(Json)),
+        "json-lines" => Ok(Box::new(JsonLines)),
+        #[cfg(feature = "binary")]
+        "binary" => Ok(Box::new(Binary)),
+        format => Err(Error::Parse(format!(
+            "unknown storage format \"{}\", expected {}",
+            format, FORMATS
+        ))),
+    }
+}
+
+/// the file holding the events of one year, e.g. `timetracking-2021.bin` for `timetracking.bin`
+pub fn shard_path<P: AsRef<Path>>(path: P, year: i32) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, year, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, year),
+    };
+    path.with_file_name(name)
+}
+
+/// all existing per year files next to `path`, sorted by year
+pub fn shard_paths<P: AsRef<Path>>(path: P) -> Result<Vec<(i32, PathBuf)>, Error> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(dir) if dir.as_os_str().is_empty() => Path::new("."),
+        Some(dir) => dir,
+        None => return Ok(Vec::new()),
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        entries => entries?,
+    };
+    let mut shards = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let year = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.rsplit_once('-'))
+            .and_then(|(_, rest)| rest.get(..4))
+            .and_then(|year| year.parse().ok());
+        if let Some(year) = year {
+            if shard_path(path, year).file_name() == Some(entry.file_name().as_os_str()) {
+                shards.push((year, entry.path()));
+            }
+        }
+    }
+    shards.sort();
+    Ok(shards)
+}
+
+fn read_if_exists(
+    storage: &dyn Storage,
+    path: &Path,
+    selection: &Selection,
+) -> Result<Vec<TrackingEvent>, Error> {
+    match storage.read(path, selection) {
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        data => data,
+    }
+}
+
+/// reads the selected events of the data file and, if `per_year` is set, of its per year files,
+/// sorted by time
+pub fn read<P: AsRef<Path>>(
+    storage: &dyn Storage,
+    path: P,
+    per_year: bool,
+    selection: Selection,
+) -> Result<Vec<TrackingEvent>, Error> {
+    let path = path.as_ref();
+    let mut data = read_if_exists(storage, path, &selection)?;
+    if per_year {
+        let mut shards = shard_paths(path)?;
+        match selection {
+            Selection::All => {}
+            Selection::Latest => shards = shards.split_off(shards.len().saturating_sub(1)),
+            Selection::Range(from, to) => {
+                let years = from.with_timezone(&Local).year()..=to.with_timezone(&Local).year();
+                shards.retain(|(year, _)| years.contains(year));
+            }
+        }
+        for (_, shard) in shards {
+            data.extend(storage.read(&shard, &selection)?);
+        }
+    }
+    // the commands expect the events in chronological order, which older versions didn't ensure
+    data.sort_by_key(|e| e.time(true));
+    Ok(data)
+}
+
+/// writes the events to the data file or, if `per_year` is set, to one file per year.
+/// A data file written without `per_year` gets split up into the per year files.
+pub fn write<P: AsRef<Path>>(
+    storage: &dyn Storage,
+    path: P,
+    data: &[TrackingEvent],
+    per_year: bool,
+) -> Result<(), Error> {
+    let path = path.as_ref();
+    if !per_year {
+        return storage.write(path, data);
+    }
+
+    let mut years = BTreeMap::<i32, Vec<TrackingEvent>>::new();
+    for event in data {
+        let year = event.time(true).with_timezone(&Local).year();
+        years.entry(year).or_default().push(event.clone());
+    }
+    for (year, events) in &years {
+        storage.write(&shard_path(path, *year), events)?;
+    }
+    for (year, shard) in shard_paths(path)? {
+        if !years.contains_key(&year) {
+            std::fs::remove_file(shard)?;
+        }
+    }
+    if path.is_file() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, event, start, with_meta};
+    use crate::Metadata;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_per_year_files() {
+        let dir = std::env::temp_dir().join("timetracking_test_per_year");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("timetracking.bin");
+        let time = |year| Local.ymd(year, 6, 1).and_hms(8, 0, 0).into();
+        let data = vec![event(true, time(2020)), event(false, time(2021))];
+        let storage = &*backend(None, &path).unwrap();
+
+        write(storage, &path, &data, false).unwrap();
+        write(storage, &path, &data, true).unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            vec![
+                (2020, shard_path(&path, 2020)),
+                (2021, shard_path(&path, 2021))
+            ],
+            shard_paths(&path).unwrap()
+        );
+        assert_eq!(data, read(storage, &path, true, Selection::All).unwrap());
+        assert_eq!(
+            data[1..],
+            read(storage, &path, true, Selection::Latest).unwrap()
+        );
+
+        write(storage, &path, &data[..1], true).unwrap();
+        assert_eq!(
+            vec![(2020, shard_path(&path, 2020))],
+            shard_paths(&path).unwrap()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let path = std::env::temp_dir().join("timetracking_test.jsonl");
+        let meta = Metadata {
+            tags: vec!["billable".to_string()],
+            ..Metadata::default()
+        };
+        let data = vec![with_meta(start(at(8, 0), "coding"), meta)];
+        let storage = backend(None, &path).unwrap();
+        storage.write(&path, &data).unwrap();
+        assert_eq!(
+            "{\"Start\":{\"description\":\"coding\",\"time\":1617264000,\"meta\":{\"tags\":[\"billable\"]}}}\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(data, storage.read(&path, &Selection::All).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(backend(Some("sqlite"), &path).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! consecutive days with tracked time, or which reached the daily goal
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct Streaks {
+    /// the days of the streak which lasts until today
+    pub current: usize,
+    pub longest: usize,
+}
+
+/// counts the consecutive days out of the sorted days. A streak is only broken by a missed work
+/// day, so weekends don't end it, and today doesn't end it before it is over.
+pub fn streaks(days: &[NaiveDate], today: NaiveDate, work_days: &[Weekday]) -> Streaks {
+    let mut streaks = Streaks::default();
+    let mut date = match days.first() {
+        Some(first) => *first,
+        None => return streaks,
+    };
+    let mut days = days.iter().peekable();
+    while date <= today {
+        if days.peek() == Some(&&date) {
+            days.next();
+            streaks.current += 1;
+            streaks.longest = streaks.longest.max(streaks.current);
+        } else if work_days.contains(&date.weekday()) && date != today {
+            streaks.current = 0;
+        }
+        date = date.succ();
+    }
+    streaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaks() {
+        let work_days = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        // 2021-04-01 is a thursday
+        let day = |day| NaiveDate::from_ymd(2021, 4, day);
+        let days = [day(1), day(2), day(5), day(7), day(8), day(9)];
+        assert_eq!(
+            Streaks {
+                current: 3,
+                longest: 3
+            },
+            streaks(&days, day(9), &work_days)
+        );
+        // the missed tuesday ended the first streak, today is not over yet
+        assert_eq!(
+            Streaks {
+                current: 2,
+                longest: 3
+            },
+            streaks(&days[..5], day(9), &work_days)
+        );
+        assert_eq!(
+            Streaks {
+                current: 0,
+                longest: 3
+            },
+            streaks(&days[..5], day(12), &work_days)
+        );
+        assert_eq!(Streaks::default(), streaks(&[], day(9), &work_days));
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Timelike};
+use std::collections::HashMap;
+
+/// how far apart the time of day of past events can be, to count for a suggestion
+const WINDOW_MINUTES: i64 = 30;
+
+/// how many past events have to match, before something gets suggested
+const MIN_OCCURRENCES: usize = 2;
+
+#[derive(Debug, PartialEq)]
+pub struct Suggestion {
+    pub description: String,
+    /// the usual start time for the description
+    pub time: NaiveTime,
+}
+
+fn minute_of_day(time: &DateTime<Local>) -> i64 {
+    i64::from(time.hour() * 60 + time.minute())
+}
+
+/// suggests the description which was most often started around the same time of day. Events on
+/// the same weekday count double.
+pub fn suggest(history: &[(DateTime<Local>, String)], at: DateTime<Local>) -> Option<Suggestion> {
+    let minute = minute_of_day(&at);
+    let mut candidates: HashMap<&str, (usize, usize, Vec<i64>)> = HashMap::new();
+    for (time, description) in history {
+        let event_minute = minute_of_day(time);
+        if (event_minute - minute).abs() > WINDOW_MINUTES {
+            continue;
+        }
+        let weight = if time.weekday() == at.weekday() { 2 } else { 1 };
+        let (score, count, minutes) = candidates.entry(description).or_default();
+        *score += weight;
+        *count += 1;
+        minutes.push(event_minute);
+    }
+    let (description, (_, _, minutes)) = candidates
+        .into_iter()
+        .filter(|(_, (_, count, _))| *count >= MIN_OCCURRENCES)
+        // sorting by description as well keeps the result stable for equal scores
+        .max_by(|(a, (a_score, ..)), (b, (b_score, ..))| a_score.cmp(b_score).then(b.cmp(a)))?;
+    let average = minutes.iter().sum::<i64>() / minutes.len() as i64;
+    Some(Suggestion {
+        description: description.to_string(),
+        time: NaiveTime::from_hms((average / 60) as u32, (average % 60) as u32, 0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_suggest() {
+        // 2021-04-05 is a monday
+        let at = |day, hour, minute| Local.ymd(2021, 4, day).and_hms(hour, minute, 0);
+        let history = vec![
+            (at(5, 9, 25), "standup".to_string()),
+            (at(6, 9, 35), "standup".to_string()),
+            (at(6, 9, 40), "email".to_string()),
+            (at(7, 9, 30), "email".to_string()),
+            (at(7, 14, 0), "review".to_string()),
+            (at(8, 14, 0), "review".to_string()),
+        ];
+        assert_eq!(
+            Some(Suggestion {
+                description: "standup".to_string(),
+                time: NaiveTime::from_hms(9, 30, 0),
+            }),
+            suggest(&history, at(12, 9, 30))
+        );
+        assert_eq!(
+            Some(Suggestion {
+                description: "email".to_string(),
+                time: NaiveTime::from_hms(9, 35, 0),
+            }),
+            suggest(&history, at(14, 9, 30))
+        );
+        assert_eq!(None, suggest(&history, at(12, 20, 0)));
+    }
+}
@@ -0,0 +1,48 @@
+//! context switches: how often the work changed from one description or project to another
+//! during a day, and how long the focus blocks in between were
+
+use chrono::Duration;
+
+/// the tracked time of each run of consecutive intervals with the same key, e.g. the description
+/// and project. The switches of the day are one less than its blocks.
+pub fn focus_blocks<K: PartialEq>(intervals: &[(K, Duration)]) -> Vec<Duration> {
+    let mut blocks: Vec<(&K, Duration)> = Vec::new();
+    for (key, time) in intervals {
+        match blocks.last_mut() {
+            Some((last, total)) if *last == key => *total = *total + *time,
+            _ => blocks.push((key, *time)),
+        }
+    }
+    blocks.into_iter().map(|(_, time)| time).collect()
+}
+
+/// the mean of the durations, zero without any
+pub fn average(durations: &[Duration]) -> Duration {
+    let total = durations
+        .iter()
+        .fold(Duration::zero(), |total, time| total + *time);
+    total / (durations.len().max(1) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_blocks() {
+        let minutes = Duration::minutes;
+        let intervals = [
+            ("review", minutes(30)),
+            ("review", minutes(15)),
+            ("email", minutes(10)),
+            ("review", minutes(20)),
+        ];
+        assert_eq!(
+            vec![minutes(45), minutes(10), minutes(20)],
+            focus_blocks(&intervals)
+        );
+        assert_eq!(minutes(25), average(&focus_blocks(&intervals)));
+        assert!(focus_blocks::<&str>(&[]).is_empty());
+        assert_eq!(Duration::zero(), average(&[]));
+    }
+}
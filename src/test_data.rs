@@ -0,0 +1,54 @@
+//! the events the unit tests are built from. The times are in april 2021 in utc, unless they are
+//! built with `local`.
+
+use crate::{Metadata, TrackingData, TrackingEvent};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use iif::iif;
+
+/// a time on 2021-04-01 in utc
+pub fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    on(1, hour, minute)
+}
+
+/// a time on the day of april 2021 in utc
+pub fn on(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2021, 4, day).and_hms(hour, minute, 0)
+}
+
+/// a time on the day of april 2021 in the local time zone
+pub fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Local
+        .ymd(2021, 4, day)
+        .and_hms(hour, minute, 0)
+        .with_timezone(&Utc)
+}
+
+/// a start or stop without a description
+pub fn event(start: bool, time: DateTime<Utc>) -> TrackingEvent {
+    let data = TrackingData {
+        description: None,
+        time,
+        meta: Metadata::default(),
+    };
+    iif!(start, TrackingEvent::Start(data), TrackingEvent::Stop(data))
+}
+
+pub fn start(time: DateTime<Utc>, description: &str) -> TrackingEvent {
+    TrackingEvent::Start(TrackingData {
+        description: Some(description.to_string()),
+        time,
+        meta: Metadata::default(),
+    })
+}
+
+/// the start with the description and its stop
+pub fn interval(from: DateTime<Utc>, to: DateTime<Utc>, description: &str) -> Vec<TrackingEvent> {
+    vec![start(from, description), event(false, to)]
+}
+
+pub fn with_meta(event: TrackingEvent, meta: Metadata) -> TrackingEvent {
+    match event {
+        TrackingEvent::Start(data) => TrackingEvent::Start(TrackingData { meta, ..data }),
+        TrackingEvent::Stop(data) => TrackingEvent::Stop(TrackingData { meta, ..data }),
+    }
+}
@@ -0,0 +1,139 @@
+//! the time ranges of `tt record` and its shorthand `tt 9-17:30 "client work"`. A range is
+//! `[day] start-end`, where the day is "today", "yesterday", "tomorrow" or a date like "2021-04-01" and the
+//! times are times of day like "9", "9:15" or "2:30pm". An end before the start is on the next
+//! day, e.g. "22-2".
+
+use crate::engine::parse_time_of_day;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Day {
+    Today,
+    Yesterday,
+    /// only for placeholders, see `tt record --planned`
+    Tomorrow,
+    Date(NaiveDate),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeRange {
+    pub day: Day,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+fn parse_day(day: &str) -> Option<Day> {
+    match day.to_lowercase().as_str() {
+        "today" => Some(Day::Today),
+        "yesterday" => Some(Day::Yesterday),
+        "tomorrow" => Some(Day::Tomorrow),
+        day => NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .ok()
+            .map(Day::Date),
+    }
+}
+
+pub fn parse(range: &str) -> Option<TimeRange> {
+    let range = range.trim();
+    // the times can't contain a space, so everything before the last one is the day
+    let (day, times) = match range.rsplit_once(' ') {
+        Some((day, times)) => (parse_day(day.trim())?, times),
+        None => (Day::Today, range),
+    };
+    let (start, end) = times.split_once('-')?;
+    let (start, end) = (parse_time_of_day(start)?, parse_time_of_day(end)?);
+    (start != end).then_some(TimeRange { day, start, end })
+}
+
+impl TimeRange {
+    /// the local start and end
+    pub fn resolve(&self, today: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+        let date = match self.day {
+            Day::Today => today,
+            Day::Yesterday => today.pred(),
+            Day::Tomorrow => today.succ(),
+            Day::Date(date) => date,
+        };
+        let end_date = if self.end < self.start {
+            date.succ()
+        } else {
+            date
+        };
+        (date.and_time(self.start), end_date.and_time(self.end))
+    }
+}
+
+/// inserts the record command before a command which is a time range, so the shorthand is parsed
+/// like `tt record 9-17:30 "client work"`. A day before the range, like in
+/// `tt yesterday 9-12`, becomes part of the range.
+pub fn expand(args: Vec<String>) -> Vec<String> {
+    let index = crate::alias::command_index(&args);
+    let (range, rest) = match (args.get(index), args.get(index + 1)) {
+        (Some(day), Some(range)) if parse_day(day).is_some() && parse(range).is_some() => {
+            (format!("{} {}", day, range), index + 2)
+        }
+        (Some(range), _) if parse(range).is_some() => (range.clone(), index + 1),
+        _ => return args,
+    };
+    let mut expanded = args[..index].to_vec();
+    expanded.push("record".to_string());
+    expanded.push(range);
+    expanded.extend_from_slice(&args[rest..]);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let time = |hour, minute| NaiveTime::from_hms(hour, minute, 0);
+        assert_eq!(
+            Some(TimeRange {
+                day: Day::Today,
+                start: time(9, 0),
+                end: time(17, 30),
+            }),
+            parse("9-17:30")
+        );
+        let range = parse("2021-04-01 10pm-2:15").unwrap();
+        assert_eq!(Day::Date(NaiveDate::from_ymd(2021, 4, 1)), range.day);
+        // the end is on the next day
+        assert_eq!(
+            NaiveDate::from_ymd(2021, 4, 2).and_hms(2, 15, 0),
+            range.resolve(NaiveDate::from_ymd(2021, 5, 1)).1
+        );
+        assert_eq!(Some(Day::Yesterday), parse("Yesterday 9-12").map(|r| r.day));
+        assert_eq!(Some(Day::Tomorrow), parse("tomorrow 9-12").map(|r| r.day));
+        for invalid in &[
+            "9",
+            "9-9",
+            "9-25",
+            "someday 9-12",
+            "client work",
+            "-9",
+            "9-",
+        ] {
+            assert_eq!(None, parse(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            args(&["tt", "--output", "json", "record", "9-17:30", "client work"]),
+            expand(args(&["tt", "--output", "json", "9-17:30", "client work"]))
+        );
+        assert_eq!(
+            args(&["tt", "record", "2021-04-01 9-12"]),
+            expand(args(&["tt", "2021-04-01", "9-12"]))
+        );
+        assert_eq!(
+            args(&["tt", "start", "9-17"]),
+            expand(args(&["tt", "start", "9-17"]))
+        );
+        assert_eq!(args(&["tt"]), expand(args(&["tt"])));
+    }
+}
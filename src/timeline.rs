@@ -0,0 +1,148 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Timelike};
+use iif::iif;
+
+pub type Interval = (DateTime<Local>, DateTime<Local>);
+
+/// each hour is split into this many slots, so one character represents five minutes
+const SLOTS_PER_HOUR: i32 = 12;
+
+fn overlap((start, stop): &Interval, from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+    let duration = (*stop).min(to) - (*start).max(from);
+    duration.max(Duration::zero())
+}
+
+/// renders one line per hour, from the first tracked hour to the last one. Tracked time is
+/// drawn as '#' and gaps as '.', followed by the tracked time within that hour.
+pub fn render(intervals: &[Interval]) -> Vec<String> {
+    let (first, last) = match (intervals.first(), intervals.last()) {
+        (Some((first, _)), Some((_, last))) => (*first, *last),
+        _ => return vec![],
+    };
+    let slot = Duration::minutes(i64::from(60 / SLOTS_PER_HOUR));
+    let mut hour = first
+        .with_minute(0)
+        .and_then(|time| time.with_second(0))
+        .and_then(|time| time.with_nanosecond(0))
+        .expect("could not round time to the hour");
+    let mut lines = Vec::new();
+    while hour < last {
+        let mut bar = String::new();
+        let mut tracked = Duration::zero();
+        for i in 0..SLOTS_PER_HOUR {
+            let from = hour + slot * i;
+            let covered = intervals
+                .iter()
+                .map(|interval| overlap(interval, from, from + slot))
+                .fold(Duration::zero(), |total, duration| total + duration);
+            tracked = tracked + covered;
+            bar.push(iif!(covered * 2 >= slot, '#', '.'));
+        }
+        let minutes = tracked.num_minutes();
+        lines.push(format!(
+            "{:02}:00 |{}| {:02}:{:02}",
+            hour.hour(),
+            bar,
+            minutes / 60,
+            minutes % 60
+        ));
+        hour = hour + Duration::hours(1);
+    }
+    lines
+}
+
+/// renders a row per label with a column per day, in which each character is a part of the day.
+/// Parts of the day with tracked time are drawn as '#'. The first line names the days.
+pub fn gantt(
+    rows: &[(String, Vec<Interval>)],
+    days: &[NaiveDate],
+    slots_per_day: i32,
+) -> Vec<String> {
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let slot = Duration::minutes(i64::from(24 * 60 / slots_per_day));
+    let day_width = slots_per_day as usize;
+    let mut header = format!("{:width$} |", "", width = width);
+    for day in days {
+        let name = day.format("%a %d").to_string();
+        header.push_str(&format!(
+            "{:day_width$.day_width$}|",
+            name,
+            day_width = day_width
+        ));
+    }
+    let mut lines = vec![header];
+    for (label, intervals) in rows {
+        let mut line = format!("{:width$} |", label, width = width);
+        let mut tracked = Duration::zero();
+        for day in days {
+            let start = Local
+                .from_local_datetime(&day.and_hms(0, 0, 0))
+                .earliest()
+                .expect("could not get the start of the day");
+            for i in 0..slots_per_day {
+                let from = start + slot * i;
+                let covered = intervals
+                    .iter()
+                    .map(|interval| overlap(interval, from, from + slot))
+                    .fold(Duration::zero(), |total, duration| total + duration);
+                tracked = tracked + covered;
+                line.push(iif!(covered > Duration::zero(), '#', '.'));
+            }
+            line.push('|');
+        }
+        let minutes = tracked.num_minutes();
+        lines.push(format!("{} {:02}:{:02}", line, minutes / 60, minutes % 60));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render() {
+        let at = |hour, minute| Local.ymd(2021, 4, 1).and_hms(hour, minute, 0);
+        assert_eq!(
+            vec![
+                "08:00 |......######| 00:30",
+                "09:00 |############| 01:00",
+                "10:00 |###......###| 00:30",
+                "11:00 |######......| 00:30",
+            ],
+            render(&[(at(8, 30), at(10, 15)), (at(10, 45), at(11, 30))])
+        );
+        assert!(render(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_gantt() {
+        let at = |day, hour| Local.ymd(2021, 4, day).and_hms(hour, 0, 0);
+        let days = [
+            NaiveDate::from_ymd(2021, 4, 1),
+            NaiveDate::from_ymd(2021, 4, 2),
+        ];
+        assert_eq!(
+            vec![
+                "       |Thu 01|Fri 02|",
+                "coding |..##..|...#..| 10:00",
+                "review |......|..#...| 01:00",
+            ],
+            gantt(
+                &[
+                    (
+                        "coding".to_string(),
+                        vec![(at(1, 8), at(1, 16)), (at(2, 12), at(2, 14))]
+                    ),
+                    ("review".to_string(), vec![(at(2, 11), at(2, 12))]),
+                ],
+                &days,
+                6
+            )
+        );
+    }
+}
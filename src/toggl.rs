@@ -0,0 +1,197 @@
+//! the sync with the Toggl Track api of `tt import toggl-api` and `tt export toggl-api`. tt has
+//! no http client, the requests are made with curl, which reads the api token from stdin so it
+//! doesn't show up in the process list.
+
+use crate::error::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const API: &str = "https://api.track.toggl.com/api/v9";
+
+/// a time entry in toggl, the running one has no stop
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Entry {
+    pub workspace_id: u64,
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub stop: Option<DateTime<Utc>>,
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NewEntry {
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub stop: DateTime<Utc>,
+    /// in seconds
+    pub duration: i64,
+    pub workspace_id: u64,
+    pub project_id: Option<u64>,
+    pub tags: Vec<String>,
+    pub created_with: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    pub id: u64,
+    pub name: String,
+}
+
+pub struct Client {
+    token: String,
+}
+
+impl Client {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<T, Error> {
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .args(["--request", method])
+            .args(["--header", "Content-Type: application/json"]);
+        if let Some(body) = body {
+            command.args(["--data", &body]);
+        }
+        let mut child = command
+            .arg(format!("{}{}", API, path))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let token = self.token.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(
+            child.stdin.take().expect("could not open stdin"),
+            "user = \"{}:api_token\"",
+            token
+        )?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::InvalidState(format!(
+                "the request to toggl failed with {}",
+                output.status
+            )));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| Error::Parse(format!("could not read the answer of toggl: {}", e)))
+    }
+
+    /// the entries which started between from and to
+    pub fn entries(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Entry>, Error> {
+        let time = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.request(
+            "GET",
+            &format!(
+                "/me/time_entries?start_date={}&end_date={}",
+                time(from),
+                time(to)
+            ),
+            None,
+        )
+    }
+
+    pub fn projects(&self, workspace: u64) -> Result<Vec<Project>, Error> {
+        self.request("GET", &format!("/workspaces/{}/projects", workspace), None)
+    }
+
+    pub fn create(&self, entry: &NewEntry) -> Result<Entry, Error> {
+        let body = serde_json::to_string(entry).expect("could not serialize the entry");
+        self.request(
+            "POST",
+            &format!("/workspaces/{}/time_entries", entry.workspace_id),
+            Some(body),
+        )
+    }
+}
+
+/// the name in toggl for a local project or tag, the same name without a mapping
+pub fn remote<'a>(mapping: &'a HashMap<String, String>, name: &'a str) -> &'a str {
+    mapping.get(name).map_or(name, String::as_str)
+}
+
+/// the local name of a project or tag in toggl
+pub fn local<'a>(mapping: &'a HashMap<String, String>, name: &'a str) -> &'a str {
+    mapping
+        .iter()
+        .find(|(_, remote)| *remote == name)
+        .map_or(name, |(local, _)| local.as_str())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Sync {
+    /// not on the other side yet
+    New,
+    /// already on the other side
+    Synced,
+    /// overlaps with a different interval on the other side
+    Conflict,
+}
+
+/// how an interval relates to the intervals on the other side. Intervals which start and stop
+/// within the same minute are the same, toggl and tt round differently.
+pub fn sync(
+    interval: (DateTime<Utc>, DateTime<Utc>),
+    others: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Sync {
+    let close = |a: DateTime<Utc>, b: DateTime<Utc>| (a - b).num_seconds().abs() < 60;
+    let (start, stop) = interval;
+    if others
+        .iter()
+        .any(|(other_start, other_stop)| close(start, *other_start) && close(stop, *other_stop))
+    {
+        Sync::Synced
+    } else if others
+        .iter()
+        .any(|(other_start, other_stop)| start < *other_stop && stop > *other_start)
+    {
+        Sync::Conflict
+    } else {
+        Sync::New
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sync() {
+        let at = |hour, minute, second| Utc.ymd(2021, 4, 1).and_hms(hour, minute, second);
+        let others = [(at(9, 0, 0), at(10, 0, 0)), (at(13, 0, 0), at(14, 0, 0))];
+        assert_eq!(Sync::Synced, sync((at(9, 0, 30), at(9, 59, 45)), &others));
+        assert_eq!(Sync::Conflict, sync((at(9, 30, 0), at(11, 0, 0)), &others));
+        assert_eq!(Sync::New, sync((at(10, 0, 0), at(13, 0, 0)), &others));
+    }
+
+    #[test]
+    fn test_mapping() {
+        let mapping = vec![("client-a".to_string(), "Client A".to_string())]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        assert_eq!("Client A", remote(&mapping, "client-a"));
+        assert_eq!("intern", remote(&mapping, "intern"));
+        assert_eq!("client-a", local(&mapping, "Client A"));
+        assert_eq!("Intern", local(&mapping, "Intern"));
+        let entry: Entry = serde_json::from_str(
+            r#"{"id": 1, "description": "fix", "start": "2021-04-01T09:00:00+00:00",
+                "stop": null, "project_id": null, "tags": null, "workspace_id": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(None, entry.stop);
+        assert_eq!(None, entry.tags);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::clock;
+use crate::error::Error;
+use crate::TrackingEvent;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// events which were removed together
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub deleted_at: DateTime<Utc>,
+    pub command: String,
+    pub events: Vec<TrackingEvent>,
+}
+
+/// removed events, saved next to the data file until the trash is emptied
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trash {
+    /// the latest entry first
+    pub entries: Vec<TrashEntry>,
+}
+
+impl Trash {
+    pub fn path<P: AsRef<Path>>(data_file: P) -> PathBuf {
+        let mut path = data_file.as_ref().as_os_str().to_owned();
+        path.push(".trash");
+        path.into()
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        match std::fs::read_to_string(&path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+            Ok(content) => serde_json::from_str(&content).map_err(|_| {
+                Error::InvalidState(format!(
+                    "could not decode the trash {}",
+                    path.as_ref().display()
+                ))
+            }),
+        }
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        if self.entries.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        let content = serde_json::to_string(self).expect("could not serialize trash");
+        Ok(std::fs::write(path, content)?)
+    }
+
+    pub fn add(&mut self, command: String, events: Vec<TrackingEvent>) {
+        if !events.is_empty() {
+            self.entries.insert(
+                0,
+                TrashEntry {
+                    deleted_at: clock::now(),
+                    command,
+                    events,
+                },
+            );
+        }
+    }
+
+    /// takes the entry out of the trash
+    pub fn restore(&mut self, index: usize) -> Result<TrashEntry, Error> {
+        if index >= self.entries.len() {
+            return Err(Error::InvalidState(format!(
+                "there is no entry {} in the trash",
+                index
+            )));
+        }
+        Ok(self.entries.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{at, event};
+
+    #[test]
+    fn test_restore() {
+        let event = event(true, at(8, 0));
+        let mut trash = Trash::default();
+        trash.add("first".to_string(), vec![event.clone()]);
+        trash.add("second".to_string(), vec![event.clone(), event]);
+
+        assert_eq!("first", trash.restore(1).unwrap().command);
+        assert!(trash.restore(1).is_err());
+        assert_eq!(2, trash.restore(0).unwrap().events.len());
+    }
+
+    #[test]
+    fn test_add_nothing() {
+        let mut trash = Trash::default();
+        trash.add("nothing".to_string(), vec![]);
+        assert!(trash.entries.is_empty());
+        assert!(trash.restore(0).is_err());
+    }
+}
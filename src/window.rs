@@ -0,0 +1,40 @@
+//! the title of the active window, which is stored with started intervals to remember what was
+//! worked on
+
+use crate::context::command_output;
+
+#[cfg(target_os = "linux")]
+pub fn active_title() -> Option<String> {
+    command_output("xdotool", &["getactivewindow", "getwindowname"])
+}
+
+#[cfg(target_os = "macos")]
+pub fn active_title() -> Option<String> {
+    command_output(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to tell (first process whose frontmost is true) \
+             to get name of front window",
+        ],
+    )
+}
+
+#[cfg(windows)]
+pub fn active_title() -> Option<String> {
+    command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "(Get-Process | Where-Object { $_.MainWindowHandle -eq (Add-Type -MemberDefinition \
+             '[DllImport(\"user32.dll\")] public static extern IntPtr GetForegroundWindow();' \
+             -Name Window -PassThru)::GetForegroundWindow() }).MainWindowTitle",
+        ],
+    )
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn active_title() -> Option<String> {
+    None
+}
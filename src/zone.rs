@@ -0,0 +1,105 @@
+//! the time zone in which events are recorded and shown. `tt tz set` overrides the zone of the
+//! system for all following commands, e.g. while traveling.
+
+use crate::error::Error;
+use crate::paths;
+use crate::Metadata;
+use chrono::{DateTime, Local, Offset, Utc};
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// where the zone set with `tt tz set` is saved. There is none without a data directory.
+pub fn session_file() -> Option<PathBuf> {
+    paths::data_dir().ok().map(|dir| dir.join("zone"))
+}
+
+pub fn read_session() -> Result<Option<String>, Error> {
+    let path = match session_file() {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+        Ok(zone) => Ok(Some(zone.trim().to_string()).filter(|zone| !zone.is_empty())),
+    }
+}
+
+/// saves the zone for the following commands, or removes the saved zone
+pub fn write_session(zone: Option<&str>) -> Result<(), Error> {
+    let path = session_file().ok_or_else(|| {
+        Error::InvalidState("Could not find the home directory to save the zone in!".to_string())
+    })?;
+    match zone {
+        Some(zone) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            Ok(std::fs::write(path, zone)?)
+        }
+        None => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// checks the zone against the zone database of the system, if there is one
+pub fn validate(zone: &str) -> Result<(), Error> {
+    let database = Path::new("/usr/share/zoneinfo");
+    let invalid = zone.is_empty() || zone.split('/').any(|part| part.is_empty() || part == "..");
+    if invalid || (database.is_dir() && !database.join(zone).is_file()) {
+        return Err(Error::Parse(format!("unknown time zone \"{}\"", zone)));
+    }
+    Ok(())
+}
+
+/// makes the zone the local time zone of this process
+pub fn apply(zone: &str) {
+    std::env::set_var("TZ", zone);
+}
+
+/// the name of the local time zone, e.g. "Europe/Berlin", if it is known
+pub fn current() -> Option<String> {
+    let from_environment = std::env::var("TZ")
+        .ok()
+        .map(|zone| zone.trim_start_matches(':').to_string());
+    from_environment
+        .filter(|zone| !zone.is_empty())
+        .or_else(|| {
+            let target = std::fs::read_link("/etc/localtime").ok()?;
+            let target = target.to_string_lossy();
+            let (_, zone) = target.split_once("zoneinfo/")?;
+            Some(zone.to_string())
+        })
+}
+
+/// stores the local time zone and its offset at the time of the event
+pub fn record(meta: &mut Metadata, time: DateTime<Utc>) {
+    meta.zone = current();
+    meta.utc_offset = Some(time.with_timezone(&Local).offset().fix().local_minus_utc());
+}
+
+/// metadata with only the local time zone
+pub fn recorded(time: DateTime<Utc>) -> Metadata {
+    let mut meta = Metadata::default();
+    record(&mut meta, time);
+    meta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut meta = Metadata::default();
+        record(&mut meta, Utc::now());
+        assert!(meta.utc_offset.is_some());
+
+        assert!(validate("").is_err());
+        assert!(validate("../etc/passwd").is_err());
+        assert!(validate("Europe//Berlin").is_err());
+    }
+}
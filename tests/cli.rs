@@ -0,0 +1,214 @@
+//! runs the tt binary with a frozen clock against a data file in a temporary home directory
+
+use std::path::PathBuf;
+use std::process::Command;
+
+const NOW: &str = "2021-04-01 12:00:00";
+
+struct Tt {
+    home: PathBuf,
+    zone: &'static str,
+}
+
+impl Tt {
+    fn new(name: &str) -> Self {
+        Self::in_zone(name, "UTC")
+    }
+
+    fn in_zone(name: &str, zone: &'static str) -> Self {
+        let home = std::env::temp_dir().join(format!("timetracking_cli_{}", name));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).unwrap();
+        Self { home, zone }
+    }
+
+    /// returns stdout and the exit code
+    fn run(&self, args: &[&str]) -> (String, i32) {
+        let output = Command::new(env!("CARGO_BIN_EXE_tt"))
+            .env("HOME", &self.home)
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_DATA_HOME")
+            .env("TZ", self.zone)
+            .env("TT_LANGUAGE", "en")
+            .args(["--now", NOW])
+            .args(args)
+            .output()
+            .unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            output.status.code().unwrap(),
+        )
+    }
+}
+
+impl Drop for Tt {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.home);
+    }
+}
+
+#[test]
+fn test_show_running_interval() {
+    let tt = Tt::new("show");
+    tt.run(&["start", "coding", "--at", "08:00"]);
+    tt.run(&["stop", "--at", "09:30"]);
+    tt.run(&["start", "review", "--at", "10:15"]);
+
+    assert_eq!(("Work Time: 03:15:00\n".to_string(), 0), tt.run(&["show"]));
+    assert_eq!(
+        ("Work Time (no context): 03:15\n".to_string(), 0),
+        tt.run(&["show", "--by-context", "--format", "{hh}:{mm}"])
+    );
+    assert_eq!(
+        ("2021-04-01  03:15\nTotal: 03:15\n".to_string(), 0),
+        tt.run(&["report"])
+    );
+}
+
+#[test]
+fn test_report_local_days() {
+    // 00:30 in Berlin is still the day before in utc, but it is shown as the same day
+    let tt = Tt::in_zone("local_days", "Europe/Berlin");
+    tt.run(&["start", "night shift", "--at", "00:30"]);
+    tt.run(&["stop", "--at", "01:30"]);
+    tt.run(&["start", "coding", "--at", "09:00"]);
+    tt.run(&["stop", "--at", "10:00"]);
+
+    assert_eq!(
+        ("2021-04-01  02:00\nTotal: 02:00\n".to_string(), 0),
+        tt.run(&["report", "--range", "today"])
+    );
+    assert_eq!(("Work Time: 02:00:00\n".to_string(), 0), tt.run(&["show"]));
+}
+
+#[test]
+fn test_show_several_ranges() {
+    let tt = Tt::new("ranges");
+    tt.run(&["start", "coding", "--at", "2021-03-29 08:00:00"]);
+    tt.run(&["stop", "--at", "2021-03-29 10:00:00"]);
+    tt.run(&["start", "coding", "--at", "2021-03-31 08:00:00"]);
+    tt.run(&["stop", "--at", "2021-03-31 09:00:00"]);
+    tt.run(&["start", "coding", "--at", "09:00"]);
+    tt.run(&["stop", "--at", "09:30"]);
+
+    assert_eq!(
+        (
+            "2021-03-29..2021-03-30: 02:00
+today: 00:30
+Work Time: 02:30
+"
+            .to_string(),
+            0
+        ),
+        tt.run(&[
+            "show",
+            "--range",
+            "2021-03-29..2021-03-30",
+            "--range",
+            "today",
+            "--format",
+            "{hh}:{mm}"
+        ])
+    );
+}
+
+#[test]
+fn test_status() {
+    let tt = Tt::new("status");
+    assert_eq!(("No Events found!\n".to_string(), 255), tt.run(&["status"]));
+    assert_eq!((String::new(), 1), tt.run(&["status", "--quiet"]));
+
+    tt.run(&["start", "coding", "--at", "08:00"]);
+    assert_eq!(
+        (
+            "Active: true\nDescription: coding\nStart Time: 08:00:00\n".to_string(),
+            0
+        ),
+        tt.run(&["status"])
+    );
+    assert_eq!(
+        (
+            "{\"active\":true,\"event\":{\"context\":null,\"description\":\"coding\",\
+             \"project\":null,\"tags\":[],\"time\":\"2021-04-01T08:00:00+00:00\",\
+             \"type\":\"start\"}}\n"
+                .to_string(),
+            0
+        ),
+        tt.run(&["--output", "json", "status"])
+    );
+    assert_eq!((String::new(), 0), tt.run(&["status", "-q"]));
+
+    tt.run(&["stop"]);
+    assert_eq!(
+        ("Active: false\nEnd Time: 12:00:00\n".to_string(), 255),
+        tt.run(&["status"])
+    );
+}
+
+#[test]
+fn test_start_at_conflicts() {
+    let tt = Tt::new("start_at");
+    tt.run(&["start", "coding", "--at", "08:00"]);
+    assert_eq!(1, tt.run(&["start", "review", "--at", "09:00"]).1);
+
+    tt.run(&["stop", "--at", "09:30"]);
+    tt.run(&["start", "review", "--at", "10:15"]);
+    tt.run(&["stop", "--at", "11:00"]);
+    // in the gap before a start, there is no stop for the interval
+    assert_eq!(1, tt.run(&["start", "call", "--at", "09:45"]).1);
+    assert_eq!(("Work Time: 02:15:00\n".to_string(), 0), tt.run(&["show"]));
+}
+
+#[test]
+fn test_read_only() {
+    let tt = Tt::new("read_only");
+    tt.run(&["start", "coding", "--at", "08:00"]);
+
+    assert_eq!((String::new(), 1), tt.run(&["--read-only", "stop"]));
+    assert_eq!(
+        ("Work Time: 04:00:00\n".to_string(), 0),
+        tt.run(&["--read-only", "show"])
+    );
+}
+
+#[test]
+fn test_closed_month() {
+    let tt = Tt::new("closed_month");
+    tt.run(&["start", "coding", "--at", "2021-03-31 08:00:00"]);
+    tt.run(&["stop", "--at", "2021-03-31 12:00:00"]);
+    assert_eq!(
+        ("Entries before 2021-04-01 are closed\n".to_string(), 0),
+        tt.run(&["close-month", "2021-03"])
+    );
+
+    assert_eq!(1, tt.run(&["delete", "all", "--yes"]).1);
+    assert_eq!(0, tt.run(&["start", "--at", "08:00"]).1);
+    assert_eq!(0, tt.run(&["--unlock", "delete", "all", "--yes"]).1);
+    let (data_file, _) = tt.run(&["path"]);
+    assert!(PathBuf::from(format!("{}.audit", data_file.trim())).is_file());
+}
+
+#[test]
+fn test_report_diff_against_export() {
+    let tt = Tt::new("diff_against");
+    tt.run(&["start", "coding", "--at", "08:00"]);
+    tt.run(&["stop", "--at", "09:30"]);
+    let exported = tt.home.join("submitted.json");
+    let exported = exported.to_str().unwrap();
+    // without the binary feature the data file is in the json format of the export already
+    #[cfg(feature = "binary")]
+    tt.run(&["export", exported]);
+    #[cfg(not(feature = "binary"))]
+    std::fs::copy(tt.run(&["path"]).0.trim(), exported).unwrap();
+    tt.run(&["start", "review", "--at", "10:00"]);
+
+    assert_eq!(
+        (
+            "+ Start at 2021-04-01 10:00:00 \"review\"\n\
+             1 entries added and 0 removed since the export\n"
+                .to_string(),
+            0
+        ),
+        tt.run(&["report", "--diff-against", exported])
+    );
+}